@@ -0,0 +1,25 @@
+-- The schema of the init.sql the database used to be created with, before
+-- the application managed its own migrations. Every statement tolerates
+-- existing objects so that such databases are adopted as they are; the
+-- later migrations bring them up to date.
+
+CREATE TABLE IF NOT EXISTS `tables` (
+	`number` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`number`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+CREATE TABLE IF NOT EXISTS `items` (
+	`id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`name` VARCHAR(255) NOT NULL,
+	`quantity` INT UNSIGNED NOT NULL,
+	`table_number` INT UNSIGNED NOT NULL,
+	`start_cooking_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`finish_cooking_at` DATETIME NOT NULL,
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`id`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+INSERT IGNORE INTO `tables` (`number`) VALUES (1), (2), (3), (4), (5);
@@ -0,0 +1,18 @@
+-- Tables get a size, a zone and a status, and items their cooking status
+-- and a version for optimistic locking.
+ALTER TABLE `tables`
+	ADD COLUMN `capacity` INT UNSIGNED NOT NULL DEFAULT 4 AFTER `number`,
+	ADD COLUMN `zone` VARCHAR(32) NOT NULL DEFAULT 'main' AFTER `capacity`,
+	ADD COLUMN `status` VARCHAR(16) NOT NULL DEFAULT 'free' AFTER `zone`,
+	ADD COLUMN `seated_at` DATETIME NULL AFTER `status`;
+
+-- the five tables the baseline seats
+UPDATE `tables` SET `capacity` = 2 WHERE `number` IN (1, 2);
+UPDATE `tables` SET `zone` = 'terrace' WHERE `number` IN (4, 5);
+UPDATE `tables` SET `capacity` = 6 WHERE `number` = 5;
+
+ALTER TABLE `items`
+	ADD COLUMN `status` VARCHAR(16) NOT NULL DEFAULT 'ordered' AFTER `finish_cooking_at`,
+	ADD COLUMN `actual_start_cooking_at` DATETIME NULL AFTER `status`,
+	ADD COLUMN `actual_finish_cooking_at` DATETIME NULL AFTER `actual_start_cooking_at`,
+	ADD COLUMN `version` INT UNSIGNED NOT NULL DEFAULT 1 AFTER `actual_finish_cooking_at`;
@@ -0,0 +1,135 @@
+-- The tables of everything the service learned after the baseline: moving
+-- items, the waitlist, staff and devices, the audit trail, idempotent orders,
+-- the outbox, webhooks and reservations.
+
+CREATE TABLE `item_transfers` (
+	`id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`kind` VARCHAR(16) NOT NULL,
+	`from_table_number` INT UNSIGNED NOT NULL,
+	`to_table_number` INT UNSIGNED NOT NULL,
+	`item_ids` TEXT NULL,
+	`moved_items` INT UNSIGNED NOT NULL,
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`id`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+CREATE TABLE `waitlist_entries` (
+	`id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`guest_name` VARCHAR(255) NOT NULL,
+	`guest_phone` VARCHAR(32) NOT NULL,
+	`party_size` INT UNSIGNED NOT NULL,
+	`quoted_wait_minutes` BIGINT NOT NULL DEFAULT 0,
+	`status` VARCHAR(16) NOT NULL DEFAULT 'waiting',
+	`joined_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`id`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+CREATE TABLE `staff` (
+	`id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`name` VARCHAR(255) NOT NULL,
+	`role` VARCHAR(16) NOT NULL,
+	`active` BOOLEAN NOT NULL DEFAULT TRUE,
+	`password_hash` VARCHAR(255),
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`id`),
+	UNIQUE KEY `staff_name` (`name`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+CREATE TABLE `devices` (
+	`id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`name` VARCHAR(255) NOT NULL,
+	`scope` VARCHAR(16) NOT NULL,
+	`table_number` INT UNSIGNED,
+	`key_hash` CHAR(64) NOT NULL,
+	`revoked` BOOLEAN NOT NULL DEFAULT FALSE,
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`id`),
+	UNIQUE KEY `device_key_hash` (`key_hash`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+CREATE TABLE `audit_records` (
+	`id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`actor_kind` VARCHAR(16) NOT NULL,
+	`actor_id` INT UNSIGNED NOT NULL,
+	`action` VARCHAR(32) NOT NULL,
+	`table_number` INT UNSIGNED,
+	`item_id` INT UNSIGNED,
+	`before_snapshot` TEXT,
+	`after_snapshot` TEXT,
+	`recorded_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`id`),
+	KEY `audit_table_number` (`table_number`),
+	KEY `audit_item_id` (`item_id`),
+	KEY `audit_actor` (`actor_kind`, `actor_id`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+CREATE TABLE `idempotency_keys` (
+	`idempotency_key` VARCHAR(255) NOT NULL,
+	`fingerprint` CHAR(64) NOT NULL,
+	`response_status` SMALLINT UNSIGNED,
+	`response_body` TEXT,
+	`expires_at` DATETIME NOT NULL,
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`idempotency_key`),
+	KEY `idempotency_expires_at` (`expires_at`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+CREATE TABLE `outbox_events` (
+	`id` BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`event_type` VARCHAR(64) NOT NULL,
+	`payload` TEXT NOT NULL,
+	`occurred_at` DATETIME NOT NULL,
+	`attempts` INT UNSIGNED NOT NULL DEFAULT 0,
+	`next_attempt_at` DATETIME NOT NULL,
+	`last_error` TEXT,
+	`delivered_at` DATETIME,
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`id`),
+	KEY `outbox_pending` (`delivered_at`, `next_attempt_at`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+CREATE TABLE `webhook_subscriptions` (
+	`id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`url` VARCHAR(2048) NOT NULL,
+	`secret` VARCHAR(255) NOT NULL,
+	`event_types` TEXT NOT NULL,
+	`active` BOOLEAN NOT NULL DEFAULT TRUE,
+	`consecutive_failures` INT UNSIGNED NOT NULL DEFAULT 0,
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`id`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+CREATE TABLE `webhook_deliveries` (
+	`id` BIGINT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`subscription_id` INT UNSIGNED NOT NULL,
+	`event_type` VARCHAR(64) NOT NULL,
+	`payload` TEXT NOT NULL,
+	`status` VARCHAR(16) NOT NULL DEFAULT 'pending',
+	`attempts` INT UNSIGNED NOT NULL DEFAULT 0,
+	`next_attempt_at` DATETIME NOT NULL,
+	`response_status` SMALLINT UNSIGNED,
+	`last_error` TEXT,
+	`delivered_at` DATETIME,
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`id`),
+	KEY `webhook_deliveries_due` (`status`, `next_attempt_at`),
+	KEY `webhook_deliveries_subscription` (`subscription_id`, `id`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+CREATE TABLE `reservations` (
+	`id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`guest_name` VARCHAR(255) NOT NULL,
+	`guest_phone` VARCHAR(32) NOT NULL,
+	`party_size` INT UNSIGNED NOT NULL,
+	`table_number` INT UNSIGNED NOT NULL,
+	`starts_at` DATETIME NOT NULL,
+	`ends_at` DATETIME NOT NULL,
+	`status` VARCHAR(16) NOT NULL DEFAULT 'booked',
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`id`)
+) DEFAULT CHARACTER SET=utf8mb4;
@@ -0,0 +1,15 @@
+-- Items of tables that no longer exist can't be reached through the API, and
+-- would keep the foreign key from being added. They are moved aside to
+-- `orphaned_items` rather than dropped, for someone to look through and
+-- delete once nobody needs them.
+CREATE TABLE `orphaned_items` LIKE `items`;
+INSERT INTO `orphaned_items`
+	SELECT * FROM `items` WHERE `table_number` NOT IN (SELECT `number` FROM `tables`);
+DELETE FROM `items` WHERE `table_number` NOT IN (SELECT `number` FROM `tables`);
+
+ALTER TABLE `items`
+	ADD KEY `items_table_number` (`table_number`, `id`),
+	ADD KEY `items_kitchen_queue` (`status`, `finish_cooking_at`),
+	ADD CONSTRAINT `items_table_number_fk` FOREIGN KEY (`table_number`)
+		REFERENCES `tables` (`number`)
+		ON UPDATE CASCADE;
@@ -0,0 +1,5 @@
+-- A request holds its idempotency key only until `locked_until`, so that a
+-- retry can take over a key whose server died before answering. Keys left
+-- unanswered from before are free to take over at once.
+ALTER TABLE `idempotency_keys`
+	ADD COLUMN `locked_until` DATETIME NOT NULL DEFAULT '1970-01-01 00:00:00' AFTER `expires_at`;
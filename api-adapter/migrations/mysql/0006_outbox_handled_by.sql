@@ -0,0 +1,4 @@
+-- The subscribers that already handled an event, comma separated, so that a
+-- retry only goes to the ones that failed it.
+ALTER TABLE `outbox_events`
+	ADD COLUMN `handled_by` VARCHAR(1024) NOT NULL DEFAULT '' AFTER `attempts`;
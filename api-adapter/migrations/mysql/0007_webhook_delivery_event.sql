@@ -0,0 +1,6 @@
+-- The outbox event a delivery was queued for, so that an event handled again
+-- queues no second delivery to the same subscription. Older deliveries have
+-- none.
+ALTER TABLE `webhook_deliveries`
+	ADD COLUMN `event_id` BIGINT UNSIGNED AFTER `subscription_id`,
+	ADD UNIQUE KEY `webhook_deliveries_event` (`event_id`, `subscription_id`);
@@ -0,0 +1,161 @@
+-- The schema of migrations/mysql up to 0004, for PostgreSQL. Integers are
+-- BIGINT throughout since PostgreSQL has no unsigned types.
+
+CREATE TABLE tables (
+	number BIGSERIAL PRIMARY KEY,
+	capacity BIGINT NOT NULL DEFAULT 4,
+	zone VARCHAR(32) NOT NULL DEFAULT 'main',
+	status VARCHAR(16) NOT NULL DEFAULT 'free',
+	seated_at TIMESTAMP NULL,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE items (
+	id BIGSERIAL PRIMARY KEY,
+	name VARCHAR(255) NOT NULL,
+	quantity BIGINT NOT NULL,
+	table_number BIGINT NOT NULL
+		REFERENCES tables (number)
+		ON UPDATE CASCADE,
+	start_cooking_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	finish_cooking_at TIMESTAMP NOT NULL,
+	status VARCHAR(16) NOT NULL DEFAULT 'ordered',
+	actual_start_cooking_at TIMESTAMP NULL,
+	actual_finish_cooking_at TIMESTAMP NULL,
+	version BIGINT NOT NULL DEFAULT 1,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX items_table_number ON items (table_number, id);
+CREATE INDEX items_kitchen_queue ON items (status, finish_cooking_at);
+
+CREATE TABLE item_transfers (
+	id BIGSERIAL PRIMARY KEY,
+	kind VARCHAR(16) NOT NULL,
+	from_table_number BIGINT NOT NULL,
+	to_table_number BIGINT NOT NULL,
+	item_ids TEXT NULL,
+	moved_items BIGINT NOT NULL,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE waitlist_entries (
+	id BIGSERIAL PRIMARY KEY,
+	guest_name VARCHAR(255) NOT NULL,
+	guest_phone VARCHAR(32) NOT NULL,
+	party_size BIGINT NOT NULL,
+	quoted_wait_minutes BIGINT NOT NULL DEFAULT 0,
+	status VARCHAR(16) NOT NULL DEFAULT 'waiting',
+	joined_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE staff (
+	id BIGSERIAL PRIMARY KEY,
+	name VARCHAR(255) NOT NULL,
+	role VARCHAR(16) NOT NULL,
+	active BOOLEAN NOT NULL DEFAULT TRUE,
+	password_hash VARCHAR(255),
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	CONSTRAINT staff_name UNIQUE (name)
+);
+
+CREATE TABLE devices (
+	id BIGSERIAL PRIMARY KEY,
+	name VARCHAR(255) NOT NULL,
+	scope VARCHAR(16) NOT NULL,
+	table_number BIGINT,
+	key_hash CHAR(64) NOT NULL,
+	revoked BOOLEAN NOT NULL DEFAULT FALSE,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	CONSTRAINT device_key_hash UNIQUE (key_hash)
+);
+
+CREATE TABLE audit_records (
+	id BIGSERIAL PRIMARY KEY,
+	actor_kind VARCHAR(16) NOT NULL,
+	actor_id BIGINT NOT NULL,
+	action VARCHAR(32) NOT NULL,
+	table_number BIGINT,
+	item_id BIGINT,
+	before_snapshot TEXT,
+	after_snapshot TEXT,
+	recorded_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX audit_table_number ON audit_records (table_number);
+CREATE INDEX audit_item_id ON audit_records (item_id);
+CREATE INDEX audit_actor ON audit_records (actor_kind, actor_id);
+
+CREATE TABLE idempotency_keys (
+	idempotency_key VARCHAR(255) PRIMARY KEY,
+	fingerprint CHAR(64) NOT NULL,
+	response_status BIGINT,
+	response_body TEXT,
+	expires_at TIMESTAMP NOT NULL,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX idempotency_expires_at ON idempotency_keys (expires_at);
+
+CREATE TABLE outbox_events (
+	id BIGSERIAL PRIMARY KEY,
+	event_type VARCHAR(64) NOT NULL,
+	payload TEXT NOT NULL,
+	occurred_at TIMESTAMP NOT NULL,
+	attempts BIGINT NOT NULL DEFAULT 0,
+	next_attempt_at TIMESTAMP NOT NULL,
+	last_error TEXT,
+	delivered_at TIMESTAMP,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX outbox_pending ON outbox_events (delivered_at, next_attempt_at);
+
+CREATE TABLE webhook_subscriptions (
+	id BIGSERIAL PRIMARY KEY,
+	url VARCHAR(2048) NOT NULL,
+	secret VARCHAR(255) NOT NULL,
+	event_types TEXT NOT NULL,
+	active BOOLEAN NOT NULL DEFAULT TRUE,
+	consecutive_failures BIGINT NOT NULL DEFAULT 0,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE webhook_deliveries (
+	id BIGSERIAL PRIMARY KEY,
+	subscription_id BIGINT NOT NULL,
+	event_type VARCHAR(64) NOT NULL,
+	payload TEXT NOT NULL,
+	status VARCHAR(16) NOT NULL DEFAULT 'pending',
+	attempts BIGINT NOT NULL DEFAULT 0,
+	next_attempt_at TIMESTAMP NOT NULL,
+	response_status BIGINT,
+	last_error TEXT,
+	delivered_at TIMESTAMP,
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX webhook_deliveries_due ON webhook_deliveries (status, next_attempt_at);
+CREATE INDEX webhook_deliveries_subscription ON webhook_deliveries (subscription_id, id);
+
+CREATE TABLE reservations (
+	id BIGSERIAL PRIMARY KEY,
+	guest_name VARCHAR(255) NOT NULL,
+	guest_phone VARCHAR(32) NOT NULL,
+	party_size BIGINT NOT NULL,
+	table_number BIGINT NOT NULL,
+	starts_at TIMESTAMP NOT NULL,
+	ends_at TIMESTAMP NOT NULL,
+	status VARCHAR(16) NOT NULL DEFAULT 'booked',
+	created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO tables (number, capacity, zone) VALUES
+	(1, 2, 'main'),
+	(2, 2, 'main'),
+	(3, 4, 'main'),
+	(4, 4, 'terrace'),
+	(5, 6, 'terrace');
+SELECT setval('tables_number_seq', (SELECT MAX(number) FROM tables));
@@ -0,0 +1,3 @@
+-- migrations/mysql/0005 for PostgreSQL.
+ALTER TABLE idempotency_keys
+	ADD COLUMN locked_until TIMESTAMP NOT NULL DEFAULT '1970-01-01 00:00:00';
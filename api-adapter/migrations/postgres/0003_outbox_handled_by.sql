@@ -0,0 +1,3 @@
+-- migrations/mysql/0006 for PostgreSQL.
+ALTER TABLE outbox_events
+	ADD COLUMN handled_by VARCHAR(1024) NOT NULL DEFAULT '';
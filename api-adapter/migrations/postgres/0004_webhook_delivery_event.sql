@@ -0,0 +1,4 @@
+-- migrations/mysql/0007 for PostgreSQL.
+ALTER TABLE webhook_deliveries
+	ADD COLUMN event_id BIGINT;
+CREATE UNIQUE INDEX webhook_deliveries_event ON webhook_deliveries (event_id, subscription_id);
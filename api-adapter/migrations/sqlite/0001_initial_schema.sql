@@ -0,0 +1,160 @@
+-- The schema of migrations/mysql up to 0004, for SQLite. Ids never get reused
+-- thanks to AUTOINCREMENT, and DATETIME columns hold the text sqlx writes.
+
+CREATE TABLE tables (
+	number INTEGER PRIMARY KEY AUTOINCREMENT,
+	capacity INTEGER NOT NULL DEFAULT 4,
+	zone VARCHAR(32) NOT NULL DEFAULT 'main',
+	status VARCHAR(16) NOT NULL DEFAULT 'free',
+	seated_at DATETIME NULL,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE items (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	name VARCHAR(255) NOT NULL,
+	quantity INTEGER NOT NULL,
+	table_number INTEGER NOT NULL
+		REFERENCES tables (number)
+		ON UPDATE CASCADE,
+	start_cooking_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	finish_cooking_at DATETIME NOT NULL,
+	status VARCHAR(16) NOT NULL DEFAULT 'ordered',
+	actual_start_cooking_at DATETIME NULL,
+	actual_finish_cooking_at DATETIME NULL,
+	version INTEGER NOT NULL DEFAULT 1,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX items_table_number ON items (table_number, id);
+CREATE INDEX items_kitchen_queue ON items (status, finish_cooking_at);
+
+CREATE TABLE item_transfers (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	kind VARCHAR(16) NOT NULL,
+	from_table_number INTEGER NOT NULL,
+	to_table_number INTEGER NOT NULL,
+	item_ids TEXT NULL,
+	moved_items INTEGER NOT NULL,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE waitlist_entries (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	guest_name VARCHAR(255) NOT NULL,
+	guest_phone VARCHAR(32) NOT NULL,
+	party_size INTEGER NOT NULL,
+	quoted_wait_minutes INTEGER NOT NULL DEFAULT 0,
+	status VARCHAR(16) NOT NULL DEFAULT 'waiting',
+	joined_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE staff (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	name VARCHAR(255) NOT NULL,
+	role VARCHAR(16) NOT NULL,
+	active BOOLEAN NOT NULL DEFAULT TRUE,
+	password_hash VARCHAR(255),
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	CONSTRAINT staff_name UNIQUE (name)
+);
+
+CREATE TABLE devices (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	name VARCHAR(255) NOT NULL,
+	scope VARCHAR(16) NOT NULL,
+	table_number INTEGER,
+	key_hash CHAR(64) NOT NULL,
+	revoked BOOLEAN NOT NULL DEFAULT FALSE,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	CONSTRAINT device_key_hash UNIQUE (key_hash)
+);
+
+CREATE TABLE audit_records (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	actor_kind VARCHAR(16) NOT NULL,
+	actor_id INTEGER NOT NULL,
+	action VARCHAR(32) NOT NULL,
+	table_number INTEGER,
+	item_id INTEGER,
+	before_snapshot TEXT,
+	after_snapshot TEXT,
+	recorded_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX audit_table_number ON audit_records (table_number);
+CREATE INDEX audit_item_id ON audit_records (item_id);
+CREATE INDEX audit_actor ON audit_records (actor_kind, actor_id);
+
+CREATE TABLE idempotency_keys (
+	idempotency_key VARCHAR(255) PRIMARY KEY,
+	fingerprint CHAR(64) NOT NULL,
+	response_status INTEGER,
+	response_body TEXT,
+	expires_at DATETIME NOT NULL,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX idempotency_expires_at ON idempotency_keys (expires_at);
+
+CREATE TABLE outbox_events (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	event_type VARCHAR(64) NOT NULL,
+	payload TEXT NOT NULL,
+	occurred_at DATETIME NOT NULL,
+	attempts INTEGER NOT NULL DEFAULT 0,
+	next_attempt_at DATETIME NOT NULL,
+	last_error TEXT,
+	delivered_at DATETIME,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX outbox_pending ON outbox_events (delivered_at, next_attempt_at);
+
+CREATE TABLE webhook_subscriptions (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	url VARCHAR(2048) NOT NULL,
+	secret VARCHAR(255) NOT NULL,
+	event_types TEXT NOT NULL,
+	active BOOLEAN NOT NULL DEFAULT TRUE,
+	consecutive_failures INTEGER NOT NULL DEFAULT 0,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE webhook_deliveries (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	subscription_id INTEGER NOT NULL,
+	event_type VARCHAR(64) NOT NULL,
+	payload TEXT NOT NULL,
+	status VARCHAR(16) NOT NULL DEFAULT 'pending',
+	attempts INTEGER NOT NULL DEFAULT 0,
+	next_attempt_at DATETIME NOT NULL,
+	response_status INTEGER,
+	last_error TEXT,
+	delivered_at DATETIME,
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+CREATE INDEX webhook_deliveries_due ON webhook_deliveries (status, next_attempt_at);
+CREATE INDEX webhook_deliveries_subscription ON webhook_deliveries (subscription_id, id);
+
+CREATE TABLE reservations (
+	id INTEGER PRIMARY KEY AUTOINCREMENT,
+	guest_name VARCHAR(255) NOT NULL,
+	guest_phone VARCHAR(32) NOT NULL,
+	party_size INTEGER NOT NULL,
+	table_number INTEGER NOT NULL,
+	starts_at DATETIME NOT NULL,
+	ends_at DATETIME NOT NULL,
+	status VARCHAR(16) NOT NULL DEFAULT 'booked',
+	created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO tables (number, capacity, zone) VALUES
+	(1, 2, 'main'),
+	(2, 2, 'main'),
+	(3, 4, 'main'),
+	(4, 4, 'terrace'),
+	(5, 6, 'terrace');
@@ -0,0 +1,4 @@
+-- migrations/mysql/0005 for SQLite, which only adds columns with a constant
+-- default.
+ALTER TABLE idempotency_keys
+	ADD COLUMN locked_until DATETIME NOT NULL DEFAULT '1970-01-01 00:00:00';
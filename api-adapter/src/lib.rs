@@ -1,4 +1,5 @@
 pub mod model;
+pub mod notifier;
 pub mod persistence;
 pub mod repository;
 pub mod repository_impl;
@@ -0,0 +1,39 @@
+use api_core::domain::{
+    actor::ActorKind,
+    audit::{AuditAction, AuditRecord},
+};
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct AuditRecordRow {
+    pub id: u32,
+    pub actor_kind: String,
+    pub actor_id: u32,
+    pub action: String,
+    pub table_number: Option<u32>,
+    pub item_id: Option<u32>,
+    pub before_snapshot: Option<String>,
+    pub after_snapshot: Option<String>,
+    pub recorded_at: NaiveDateTime,
+}
+
+impl AuditRecordRow {
+    pub fn from_row(row: AuditRecordRow) -> Result<AuditRecord, String> {
+        Ok(AuditRecord::of(
+            row.id,
+            row.actor_kind.parse::<ActorKind>()?,
+            row.actor_id,
+            row.action.parse::<AuditAction>()?,
+            row.table_number,
+            row.item_id,
+            row.before_snapshot,
+            row.after_snapshot,
+            row.recorded_at,
+        ))
+    }
+
+    pub fn from_rows(rows: Vec<AuditRecordRow>) -> Result<Vec<AuditRecord>, String> {
+        rows.into_iter().map(AuditRecordRow::from_row).collect()
+    }
+}
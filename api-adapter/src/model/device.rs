@@ -0,0 +1,29 @@
+use api_core::domain::device::{Device, DeviceScope};
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct DeviceRow {
+    pub id: u32,
+    pub name: String,
+    pub scope: String,
+    pub table_number: Option<u32>,
+    pub key_hash: String,
+    pub revoked: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl DeviceRow {
+    pub fn from_row(row: DeviceRow) -> Device {
+        // a key whose scope no longer makes sense must not open anything
+        match DeviceScope::parse(&row.scope, row.table_number) {
+            Ok(scope) => Device::of(row.id, row.name, scope, row.key_hash, row.revoked),
+            Err(_) => Device::of(row.id, row.name, DeviceScope::Kitchen, row.key_hash, true),
+        }
+    }
+
+    pub fn from_rows(rows: Vec<DeviceRow>) -> Vec<Device> {
+        rows.into_iter().map(DeviceRow::from_row).collect()
+    }
+}
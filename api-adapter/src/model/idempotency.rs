@@ -0,0 +1,30 @@
+use api_core::domain::idempotency::{IdempotencyRecord, StoredResponse};
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct IdempotencyKeyRow {
+    pub idempotency_key: String,
+    pub fingerprint: String,
+    pub response_status: Option<u16>,
+    pub response_body: Option<String>,
+    pub expires_at: NaiveDateTime,
+    pub locked_until: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl IdempotencyKeyRow {
+    pub fn from_row(row: IdempotencyKeyRow) -> IdempotencyRecord {
+        let response = row.response_status.map(|status| StoredResponse {
+            status,
+            body: row.response_body.unwrap_or_default(),
+        });
+        IdempotencyRecord::of(
+            row.idempotency_key,
+            row.fingerprint,
+            response,
+            row.expires_at,
+            row.locked_until,
+        )
+    }
+}
@@ -1,4 +1,4 @@
-use api_core::domain::item::Item;
+use api_core::domain::item::{Item, ItemStatus, ItemsRevision};
 use chrono::NaiveDateTime;
 use sqlx::FromRow;
 
@@ -10,34 +10,81 @@ pub struct ItemRow {
     pub table_number: u32,
     pub start_cooking_at: NaiveDateTime,
     pub finish_cooking_at: NaiveDateTime,
+    pub status: String,
+    pub actual_start_cooking_at: Option<NaiveDateTime>,
+    pub actual_finish_cooking_at: Option<NaiveDateTime>,
+    pub version: u32,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
 
 impl ItemRow {
-    pub fn from_row(row: ItemRow) -> Item {
-        Item::of(
+    /// Fails on a status the application doesn't know, rather than passing
+    /// the item off as a new order.
+    pub fn from_row(row: ItemRow) -> Result<Item, String> {
+        Ok(Item::of(
             row.id,
             row.name.to_string(),
             row.quantity,
             row.table_number,
             row.start_cooking_at,
             row.finish_cooking_at,
-        )
+            row.status.parse::<ItemStatus>()?,
+            row.actual_start_cooking_at,
+            row.actual_finish_cooking_at,
+            row.version,
+        ))
     }
 
-    pub fn from_rows(rows: Vec<ItemRow>) -> Vec<Item> {
-        rows.iter()
-            .map(|row| {
-                Item::of(
-                    row.id,
-                    row.name.to_string(),
-                    row.quantity,
-                    row.table_number,
-                    row.start_cooking_at,
-                    row.finish_cooking_at,
-                )
-            })
-            .collect()
+    pub fn from_rows(rows: Vec<ItemRow>) -> Result<Vec<Item>, String> {
+        rows.into_iter().map(ItemRow::from_row).collect()
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct ItemsRevisionRow {
+    pub item_count: i64,
+    pub version_sum: u64,
+    pub last_updated_at: Option<NaiveDateTime>,
+}
+
+impl ItemsRevisionRow {
+    pub fn from_row(row: ItemsRevisionRow) -> ItemsRevision {
+        ItemsRevision {
+            item_count: u64::try_from(row.item_count).unwrap_or_default(),
+            version_sum: row.version_sum,
+            last_updated_at: row.last_updated_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Local;
+
+    #[test]
+    fn test_unknown_status_is_an_error() {
+        let now = Local::now().naive_local();
+        let row = |status: &str| ItemRow {
+            id: 1,
+            name: String::from("Ramen"),
+            quantity: 1,
+            table_number: 1,
+            start_cooking_at: now,
+            finish_cooking_at: now,
+            status: status.to_string(),
+            actual_start_cooking_at: None,
+            actual_finish_cooking_at: None,
+            version: 1,
+            created_at: now,
+            updated_at: now,
+        };
+        assert_eq!(
+            ItemRow::from_row(row("cooking")).unwrap().status,
+            ItemStatus::Cooking
+        );
+        assert!(ItemRow::from_row(row("burnt")).is_err());
+        assert!(ItemRow::from_rows(vec![row("ordered"), row("burnt")]).is_err());
     }
 }
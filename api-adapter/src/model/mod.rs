@@ -1 +1,14 @@
+pub mod audit;
+pub mod device;
+pub mod idempotency;
 pub mod item;
+pub mod outbox;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+pub mod reservation;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod staff;
+pub mod table;
+pub mod waitlist;
+pub mod webhook;
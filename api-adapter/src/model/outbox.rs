@@ -0,0 +1,33 @@
+use api_core::domain::{event::DomainEvent, outbox::OutboxEntry};
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct OutboxEventRow {
+    pub id: u64,
+    pub payload: String,
+    pub attempts: u32,
+    pub handled_by: String,
+    pub next_attempt_at: NaiveDateTime,
+}
+
+impl OutboxEventRow {
+    pub fn from_rows(rows: Vec<OutboxEventRow>) -> Vec<OutboxEntry> {
+        rows.into_iter().map(OutboxEventRow::from_row).collect()
+    }
+
+    pub fn from_row(row: OutboxEventRow) -> OutboxEntry {
+        OutboxEntry {
+            id: row.id,
+            event: serde_json::from_str::<DomainEvent>(&row.payload).ok(),
+            attempts: row.attempts,
+            handled_by: row
+                .handled_by
+                .split(',')
+                .filter(|name| !name.is_empty())
+                .map(String::from)
+                .collect(),
+            next_attempt_at: row.next_attempt_at,
+        }
+    }
+}
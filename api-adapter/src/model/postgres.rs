@@ -0,0 +1,343 @@
+//! Rows as PostgreSQL returns them. Every integer column is a BIGINT there,
+//! so these only narrow the numbers into the rows of the other modules, which
+//! turn them into domain objects.
+
+use crate::model::{
+    audit::AuditRecordRow,
+    device::DeviceRow,
+    idempotency::IdempotencyKeyRow,
+    item::{ItemRow, ItemsRevisionRow},
+    outbox::OutboxEventRow,
+    reservation::ReservationRow,
+    staff::StaffRow,
+    table::TableRow,
+    waitlist::WaitlistEntryRow,
+    webhook::{WebhookDeliveryRow, WebhookSubscriptionRow},
+};
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+
+/// Out of range values never come from the application itself; they read as 0.
+fn narrow<T: TryFrom<i64> + Default>(value: i64) -> T {
+    T::try_from(value).unwrap_or_default()
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgItemRow {
+    pub id: i64,
+    pub name: String,
+    pub quantity: i64,
+    pub table_number: i64,
+    pub start_cooking_at: NaiveDateTime,
+    pub finish_cooking_at: NaiveDateTime,
+    pub status: String,
+    pub actual_start_cooking_at: Option<NaiveDateTime>,
+    pub actual_finish_cooking_at: Option<NaiveDateTime>,
+    pub version: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<PgItemRow> for ItemRow {
+    fn from(row: PgItemRow) -> Self {
+        ItemRow {
+            id: narrow(row.id),
+            name: row.name,
+            quantity: narrow(row.quantity),
+            table_number: narrow(row.table_number),
+            start_cooking_at: row.start_cooking_at,
+            finish_cooking_at: row.finish_cooking_at,
+            status: row.status,
+            actual_start_cooking_at: row.actual_start_cooking_at,
+            actual_finish_cooking_at: row.actual_finish_cooking_at,
+            version: narrow(row.version),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgItemsRevisionRow {
+    pub item_count: i64,
+    pub version_sum: i64,
+    pub last_updated_at: Option<NaiveDateTime>,
+}
+
+impl From<PgItemsRevisionRow> for ItemsRevisionRow {
+    fn from(row: PgItemsRevisionRow) -> Self {
+        ItemsRevisionRow {
+            item_count: row.item_count,
+            version_sum: narrow(row.version_sum),
+            last_updated_at: row.last_updated_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgTableRow {
+    pub number: i64,
+    pub capacity: i64,
+    pub zone: String,
+    pub status: String,
+    pub seated_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<PgTableRow> for TableRow {
+    fn from(row: PgTableRow) -> Self {
+        TableRow {
+            number: narrow(row.number),
+            capacity: narrow(row.capacity),
+            zone: row.zone,
+            status: row.status,
+            seated_at: row.seated_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgReservationRow {
+    pub id: i64,
+    pub guest_name: String,
+    pub guest_phone: String,
+    pub party_size: i64,
+    pub table_number: i64,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<PgReservationRow> for ReservationRow {
+    fn from(row: PgReservationRow) -> Self {
+        ReservationRow {
+            id: narrow(row.id),
+            guest_name: row.guest_name,
+            guest_phone: row.guest_phone,
+            party_size: narrow(row.party_size),
+            table_number: narrow(row.table_number),
+            starts_at: row.starts_at,
+            ends_at: row.ends_at,
+            status: row.status,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgWaitlistEntryRow {
+    pub id: i64,
+    pub guest_name: String,
+    pub guest_phone: String,
+    pub party_size: i64,
+    pub quoted_wait_minutes: i64,
+    pub status: String,
+    pub joined_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<PgWaitlistEntryRow> for WaitlistEntryRow {
+    fn from(row: PgWaitlistEntryRow) -> Self {
+        WaitlistEntryRow {
+            id: narrow(row.id),
+            guest_name: row.guest_name,
+            guest_phone: row.guest_phone,
+            party_size: narrow(row.party_size),
+            quoted_wait_minutes: row.quoted_wait_minutes,
+            status: row.status,
+            joined_at: row.joined_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgStaffRow {
+    pub id: i64,
+    pub name: String,
+    pub role: String,
+    pub active: bool,
+    pub password_hash: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<PgStaffRow> for StaffRow {
+    fn from(row: PgStaffRow) -> Self {
+        StaffRow {
+            id: narrow(row.id),
+            name: row.name,
+            role: row.role,
+            active: row.active,
+            password_hash: row.password_hash,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgDeviceRow {
+    pub id: i64,
+    pub name: String,
+    pub scope: String,
+    pub table_number: Option<i64>,
+    pub key_hash: String,
+    pub revoked: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<PgDeviceRow> for DeviceRow {
+    fn from(row: PgDeviceRow) -> Self {
+        DeviceRow {
+            id: narrow(row.id),
+            name: row.name,
+            scope: row.scope,
+            table_number: row.table_number.map(narrow),
+            key_hash: row.key_hash,
+            revoked: row.revoked,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgAuditRecordRow {
+    pub id: i64,
+    pub actor_kind: String,
+    pub actor_id: i64,
+    pub action: String,
+    pub table_number: Option<i64>,
+    pub item_id: Option<i64>,
+    pub before_snapshot: Option<String>,
+    pub after_snapshot: Option<String>,
+    pub recorded_at: NaiveDateTime,
+}
+
+impl From<PgAuditRecordRow> for AuditRecordRow {
+    fn from(row: PgAuditRecordRow) -> Self {
+        AuditRecordRow {
+            id: narrow(row.id),
+            actor_kind: row.actor_kind,
+            actor_id: narrow(row.actor_id),
+            action: row.action,
+            table_number: row.table_number.map(narrow),
+            item_id: row.item_id.map(narrow),
+            before_snapshot: row.before_snapshot,
+            after_snapshot: row.after_snapshot,
+            recorded_at: row.recorded_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgIdempotencyKeyRow {
+    pub idempotency_key: String,
+    pub fingerprint: String,
+    pub response_status: Option<i64>,
+    pub response_body: Option<String>,
+    pub expires_at: NaiveDateTime,
+    pub locked_until: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<PgIdempotencyKeyRow> for IdempotencyKeyRow {
+    fn from(row: PgIdempotencyKeyRow) -> Self {
+        IdempotencyKeyRow {
+            idempotency_key: row.idempotency_key,
+            fingerprint: row.fingerprint,
+            response_status: row.response_status.map(narrow),
+            response_body: row.response_body,
+            expires_at: row.expires_at,
+            locked_until: row.locked_until,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgOutboxEventRow {
+    pub id: i64,
+    pub payload: String,
+    pub attempts: i64,
+    pub handled_by: String,
+    pub next_attempt_at: NaiveDateTime,
+}
+
+impl From<PgOutboxEventRow> for OutboxEventRow {
+    fn from(row: PgOutboxEventRow) -> Self {
+        OutboxEventRow {
+            id: narrow(row.id),
+            payload: row.payload,
+            attempts: narrow(row.attempts),
+            handled_by: row.handled_by,
+            next_attempt_at: row.next_attempt_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgWebhookSubscriptionRow {
+    pub id: i64,
+    pub url: String,
+    pub secret: String,
+    pub event_types: String,
+    pub active: bool,
+    pub consecutive_failures: i64,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<PgWebhookSubscriptionRow> for WebhookSubscriptionRow {
+    fn from(row: PgWebhookSubscriptionRow) -> Self {
+        WebhookSubscriptionRow {
+            id: narrow(row.id),
+            url: row.url,
+            secret: row.secret,
+            event_types: row.event_types,
+            active: row.active,
+            consecutive_failures: narrow(row.consecutive_failures),
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct PgWebhookDeliveryRow {
+    pub id: i64,
+    pub subscription_id: i64,
+    pub event_type: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: i64,
+    pub next_attempt_at: NaiveDateTime,
+    pub response_status: Option<i64>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<NaiveDateTime>,
+}
+
+impl From<PgWebhookDeliveryRow> for WebhookDeliveryRow {
+    fn from(row: PgWebhookDeliveryRow) -> Self {
+        WebhookDeliveryRow {
+            id: narrow(row.id),
+            subscription_id: narrow(row.subscription_id),
+            event_type: row.event_type,
+            payload: row.payload,
+            status: row.status,
+            attempts: narrow(row.attempts),
+            next_attempt_at: row.next_attempt_at,
+            response_status: row.response_status.map(narrow),
+            last_error: row.last_error,
+            delivered_at: row.delivered_at,
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use api_core::domain::reservation::{Reservation, ReservationStatus};
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct ReservationRow {
+    pub id: u32,
+    pub guest_name: String,
+    pub guest_phone: String,
+    pub party_size: u32,
+    pub table_number: u32,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub status: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl ReservationRow {
+    pub fn from_row(row: ReservationRow) -> Result<Reservation, String> {
+        Ok(Reservation::of(
+            row.id,
+            row.guest_name,
+            row.guest_phone,
+            row.party_size,
+            row.table_number,
+            row.starts_at,
+            row.ends_at,
+            row.status.parse::<ReservationStatus>()?,
+        ))
+    }
+
+    pub fn from_rows(rows: Vec<ReservationRow>) -> Result<Vec<Reservation>, String> {
+        rows.into_iter().map(ReservationRow::from_row).collect()
+    }
+}
@@ -0,0 +1,81 @@
+//! Rows SQLite can't return as the other modules declare them. sqlx decodes
+//! SQLite integers into anything up to `u32` but not into `u64`, so these read
+//! the 64-bit columns as `i64` and convert into the rows of the other modules.
+
+use crate::model::{item::ItemsRevisionRow, outbox::OutboxEventRow, webhook::WebhookDeliveryRow};
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+
+/// Ids and sums are never negative, anything else reads as 0.
+fn unsigned(value: i64) -> u64 {
+    u64::try_from(value).unwrap_or_default()
+}
+
+#[derive(FromRow, Debug)]
+pub struct SqliteItemsRevisionRow {
+    pub item_count: i64,
+    pub version_sum: i64,
+    pub last_updated_at: Option<NaiveDateTime>,
+}
+
+impl From<SqliteItemsRevisionRow> for ItemsRevisionRow {
+    fn from(row: SqliteItemsRevisionRow) -> Self {
+        ItemsRevisionRow {
+            item_count: row.item_count,
+            version_sum: unsigned(row.version_sum),
+            last_updated_at: row.last_updated_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct SqliteOutboxEventRow {
+    pub id: i64,
+    pub payload: String,
+    pub attempts: u32,
+    pub handled_by: String,
+    pub next_attempt_at: NaiveDateTime,
+}
+
+impl From<SqliteOutboxEventRow> for OutboxEventRow {
+    fn from(row: SqliteOutboxEventRow) -> Self {
+        OutboxEventRow {
+            id: unsigned(row.id),
+            payload: row.payload,
+            attempts: row.attempts,
+            handled_by: row.handled_by,
+            next_attempt_at: row.next_attempt_at,
+        }
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct SqliteWebhookDeliveryRow {
+    pub id: i64,
+    pub subscription_id: u32,
+    pub event_type: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: u32,
+    pub next_attempt_at: NaiveDateTime,
+    pub response_status: Option<u16>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<NaiveDateTime>,
+}
+
+impl From<SqliteWebhookDeliveryRow> for WebhookDeliveryRow {
+    fn from(row: SqliteWebhookDeliveryRow) -> Self {
+        WebhookDeliveryRow {
+            id: unsigned(row.id),
+            subscription_id: row.subscription_id,
+            event_type: row.event_type,
+            payload: row.payload,
+            status: row.status,
+            attempts: row.attempts,
+            next_attempt_at: row.next_attempt_at,
+            response_status: row.response_status,
+            last_error: row.last_error,
+            delivered_at: row.delivered_at,
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use api_core::domain::staff::{Role, Staff};
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct StaffRow {
+    pub id: u32,
+    pub name: String,
+    pub role: String,
+    pub active: bool,
+    pub password_hash: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl StaffRow {
+    pub fn from_row(row: StaffRow) -> Staff {
+        // an unknown role must never grant more than the least privileged one
+        Staff::of(
+            row.id,
+            row.name,
+            row.role.parse().unwrap_or(Role::Waiter),
+            row.active,
+            row.password_hash,
+        )
+    }
+
+    pub fn from_rows(rows: Vec<StaffRow>) -> Vec<Staff> {
+        rows.into_iter().map(StaffRow::from_row).collect()
+    }
+}
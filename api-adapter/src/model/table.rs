@@ -0,0 +1,30 @@
+use api_core::domain::table::{Table, TableStatus};
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct TableRow {
+    pub number: u32,
+    pub capacity: u32,
+    pub zone: String,
+    pub status: String,
+    pub seated_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl TableRow {
+    pub fn from_row(row: TableRow) -> Result<Table, String> {
+        Ok(Table::of(
+            row.number,
+            row.capacity,
+            row.zone,
+            row.status.parse::<TableStatus>()?,
+            row.seated_at,
+        ))
+    }
+
+    pub fn from_rows(rows: Vec<TableRow>) -> Result<Vec<Table>, String> {
+        rows.into_iter().map(TableRow::from_row).collect()
+    }
+}
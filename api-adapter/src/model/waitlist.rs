@@ -0,0 +1,33 @@
+use api_core::domain::waitlist::{WaitlistEntry, WaitlistStatus};
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct WaitlistEntryRow {
+    pub id: u32,
+    pub guest_name: String,
+    pub guest_phone: String,
+    pub party_size: u32,
+    pub quoted_wait_minutes: i64,
+    pub status: String,
+    pub joined_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl WaitlistEntryRow {
+    pub fn from_row(row: WaitlistEntryRow) -> Result<WaitlistEntry, String> {
+        Ok(WaitlistEntry::of(
+            row.id,
+            row.guest_name,
+            row.guest_phone,
+            row.party_size,
+            row.quoted_wait_minutes,
+            row.status.parse::<WaitlistStatus>()?,
+            row.joined_at,
+        ))
+    }
+
+    pub fn from_rows(rows: Vec<WaitlistEntryRow>) -> Result<Vec<WaitlistEntry>, String> {
+        rows.into_iter().map(WaitlistEntryRow::from_row).collect()
+    }
+}
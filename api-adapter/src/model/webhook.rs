@@ -0,0 +1,77 @@
+use api_core::domain::webhook::{WebhookDelivery, WebhookDeliveryStatus, WebhookSubscription};
+use chrono::NaiveDateTime;
+use sqlx::FromRow;
+
+#[derive(FromRow, Debug)]
+pub struct WebhookSubscriptionRow {
+    pub id: u32,
+    pub url: String,
+    pub secret: String,
+    /// Comma separated event names.
+    pub event_types: String,
+    pub active: bool,
+    pub consecutive_failures: u32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl WebhookSubscriptionRow {
+    pub fn from_row(row: WebhookSubscriptionRow) -> WebhookSubscription {
+        let event_types = row
+            .event_types
+            .split(',')
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect();
+        WebhookSubscription::of(
+            row.id,
+            row.url,
+            row.secret,
+            event_types,
+            row.active,
+            row.consecutive_failures,
+        )
+    }
+
+    pub fn from_rows(rows: Vec<WebhookSubscriptionRow>) -> Vec<WebhookSubscription> {
+        rows.into_iter()
+            .map(WebhookSubscriptionRow::from_row)
+            .collect()
+    }
+}
+
+#[derive(FromRow, Debug)]
+pub struct WebhookDeliveryRow {
+    pub id: u64,
+    pub subscription_id: u32,
+    pub event_type: String,
+    pub payload: String,
+    pub status: String,
+    pub attempts: u32,
+    pub next_attempt_at: NaiveDateTime,
+    pub response_status: Option<u16>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<NaiveDateTime>,
+}
+
+impl WebhookDeliveryRow {
+    pub fn from_row(row: WebhookDeliveryRow) -> WebhookDelivery {
+        WebhookDelivery::of(
+            row.id,
+            row.subscription_id,
+            row.event_type,
+            row.payload,
+            // a delivery in a state we do not know must not be sent again
+            row.status.parse().unwrap_or(WebhookDeliveryStatus::Failed),
+            row.attempts,
+            row.next_attempt_at,
+            row.response_status,
+            row.last_error,
+            row.delivered_at,
+        )
+    }
+
+    pub fn from_rows(rows: Vec<WebhookDeliveryRow>) -> Vec<WebhookDelivery> {
+        rows.into_iter().map(WebhookDeliveryRow::from_row).collect()
+    }
+}
@@ -0,0 +1,138 @@
+use std::{path::PathBuf, sync::Arc};
+
+use api_core::{
+    domain::event::DomainEvent,
+    notifier::{EventPublisher, EventSubscriber, Publication},
+};
+use async_trait::async_trait;
+
+/// Publishes domain events to every registered subscriber in turn. A failing
+/// subscriber does not keep the event from the others; the publication
+/// names the ones that took it, so that the outbox relay only tries the
+/// others again. Subscribers still have to cope with seeing an event more
+/// than once, e.g. when the relay stops before it noted who handled it.
+#[derive(Clone, Default)]
+pub struct EventDispatcher {
+    subscribers: Vec<(String, Arc<dyn EventSubscriber + Send + Sync>)>,
+}
+
+impl EventDispatcher {
+    /// Registers `subscriber` under `name`, which has to stay the same
+    /// across restarts for the events it already handled to be skipped.
+    pub fn with_subscriber<S>(mut self, name: &str, subscriber: S) -> Self
+    where
+        S: EventSubscriber + Send + Sync + 'static,
+    {
+        self.subscribers
+            .push((name.to_string(), Arc::new(subscriber)));
+        self
+    }
+}
+
+/// Where item events go besides the subscribers every server has.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutboxSinks {
+    /// Posted to as JSON.
+    pub webhook_url: Option<String>,
+    /// Appended to as JSON lines.
+    pub file: Option<PathBuf>,
+}
+
+#[async_trait]
+impl EventPublisher for EventDispatcher {
+    async fn publish(
+        &self,
+        event_id: u64,
+        event: &DomainEvent,
+        handled_by: &[String],
+    ) -> Publication {
+        let mut publication = Publication {
+            handled_by: handled_by.to_vec(),
+            failures: Vec::new(),
+        };
+        for (name, subscriber) in &self.subscribers {
+            if handled_by.contains(name) {
+                continue;
+            }
+            match subscriber.handle(event_id, event).await {
+                Ok(()) => publication.handled_by.push(name.clone()),
+                Err(e) => publication.failures.push(format!("{}: {:?}", name, e)),
+            }
+        }
+        publication
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use api_core::{domain::item::Item, error::Error};
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+    use std::sync::Mutex;
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        names: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait]
+    impl EventSubscriber for Recorder {
+        async fn handle(&self, _: u64, event: &DomainEvent) -> Result<(), Error> {
+            self.names.lock().unwrap().push(event.name());
+            Ok(())
+        }
+    }
+
+    struct Failing;
+
+    #[async_trait]
+    impl EventSubscriber for Failing {
+        async fn handle(&self, _: u64, _: &DomainEvent) -> Result<(), Error> {
+            Err(Error::NotificationError)
+        }
+    }
+
+    fn event() -> DomainEvent {
+        let now = Local::now().naive_local();
+        let item = Item::new(String::from("Ramen"), 1, now).unwrap();
+        DomainEvent::ItemAdded {
+            table_number: 1,
+            item,
+            occurred_at: now,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_subscriber_gets_the_event() {
+        let analytics = Recorder::default();
+        let reports = Recorder::default();
+        let dispatcher = EventDispatcher::default()
+            .with_subscriber("analytics", analytics.clone())
+            .with_subscriber("failing", Failing)
+            .with_subscriber("reports", reports.clone());
+
+        let publication = dispatcher.publish(1, &event(), &[]).await;
+        assert_eq!(publication.is_complete(), false);
+        assert_eq!(publication.handled_by, vec!["analytics", "reports"]);
+        assert_eq!(publication.failures, vec!["failing: NotificationError"]);
+        assert_eq!(*analytics.names.lock().unwrap(), vec!["item_added"]);
+        assert_eq!(*reports.names.lock().unwrap(), vec!["item_added"]);
+    }
+
+    #[tokio::test]
+    async fn test_subscribers_that_handled_the_event_are_skipped() {
+        let analytics = Recorder::default();
+        let reports = Recorder::default();
+        let dispatcher = EventDispatcher::default()
+            .with_subscriber("analytics", analytics.clone())
+            .with_subscriber("reports", reports.clone());
+
+        let handled_by = vec![String::from("analytics")];
+        let publication = dispatcher.publish(1, &event(), &handled_by).await;
+        assert_eq!(publication.is_complete(), true);
+        assert_eq!(publication.handled_by, vec!["analytics", "reports"]);
+        assert_eq!(analytics.names.lock().unwrap().len(), 0);
+        assert_eq!(*reports.names.lock().unwrap(), vec!["item_added"]);
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use api_core::{
+    domain::{event::DomainEvent, item_event::ItemEvent},
+    error::Error,
+    notifier::EventSubscriber,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use tokio::sync::broadcast;
+
+/// An item event with the id the bus gave it, so that a subscriber that lost
+/// its connection can tell where to pick up again.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PublishedItemEvent {
+    pub id: u64,
+    pub event: ItemEvent,
+}
+
+/// In-process fan-out of item events to every live subscriber, fed with the
+/// item events among the published domain events. The last
+/// `capacity` events are kept for subscribers coming back after a
+/// disconnect; live subscribers that fall further behind than that miss the
+/// oldest ones.
+#[derive(Clone)]
+pub struct ItemEventBus {
+    sender: broadcast::Sender<PublishedItemEvent>,
+    recent: Arc<Mutex<RecentEvents>>,
+}
+
+struct RecentEvents {
+    next_id: u64,
+    capacity: usize,
+    events: VecDeque<PublishedItemEvent>,
+}
+
+impl ItemEventBus {
+    pub const DEFAULT_CAPACITY: usize = 256;
+
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        // ids start from the startup time so that they keep growing across
+        // restarts and stale ids from before a restart are never reused
+        let next_id = u64::try_from(Utc::now().timestamp_millis()).unwrap_or_default() * 1000;
+        ItemEventBus {
+            sender,
+            recent: Arc::new(Mutex::new(RecentEvents {
+                next_id,
+                capacity,
+                events: VecDeque::with_capacity(capacity),
+            })),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<PublishedItemEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Subscribes and hands back the kept events published after
+    /// `last_event_id`, with no gap or overlap between the two.
+    pub fn subscribe_after(
+        &self,
+        last_event_id: u64,
+    ) -> (
+        Vec<PublishedItemEvent>,
+        broadcast::Receiver<PublishedItemEvent>,
+    ) {
+        let recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        let missed = recent
+            .events
+            .iter()
+            .filter(|published| published.id > last_event_id)
+            .cloned()
+            .collect();
+        (missed, self.sender.subscribe())
+    }
+}
+
+impl Default for ItemEventBus {
+    fn default() -> Self {
+        ItemEventBus::new(ItemEventBus::DEFAULT_CAPACITY)
+    }
+}
+
+impl ItemEventBus {
+    pub fn publish(&self, event: ItemEvent) {
+        let mut recent = self.recent.lock().unwrap_or_else(|e| e.into_inner());
+        let published = PublishedItemEvent {
+            id: recent.next_id,
+            event,
+        };
+        recent.next_id += 1;
+        if recent.events.len() == recent.capacity {
+            recent.events.pop_front();
+        }
+        recent.events.push_back(published.clone());
+        // sending only fails when nobody is subscribed
+        let _ = self.sender.send(published);
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for ItemEventBus {
+    async fn handle(&self, _: u64, event: &DomainEvent) -> Result<(), Error> {
+        if let Some(item_event) = ItemEvent::from_domain(event) {
+            self.publish(item_event);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use api_core::domain::{item::Item, item_event::ItemEventKind};
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn test_publish_reaches_every_subscriber() {
+        let bus = ItemEventBus::default();
+        let now = Local::now().naive_local();
+        let item = Item::new(String::from("Ramen"), 1, now).unwrap();
+
+        let mut kitchen = bus.subscribe();
+        let mut tablet = bus.subscribe();
+        let event = DomainEvent::ItemAdded {
+            table_number: 2,
+            item,
+            occurred_at: now,
+        };
+        bus.handle(1, &event).await.unwrap();
+        let event = ItemEvent::from_domain(&event).unwrap();
+        assert_eq!(kitchen.recv().await.unwrap().event, event);
+        assert_eq!(tablet.recv().await.unwrap().event, event);
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_after_replays_missed_events() {
+        let bus = ItemEventBus::new(2);
+        let now = Local::now().naive_local();
+        let item = Item::new(String::from("Ramen"), 1, now).unwrap();
+        for table_number in 1..=3 {
+            bus.publish(ItemEvent::of(
+                ItemEventKind::Added,
+                table_number,
+                item.clone(),
+                now,
+            ));
+        }
+
+        // only the last two events are kept
+        let (kept, _) = bus.subscribe_after(0);
+        let tables: Vec<u32> = kept.iter().map(|p| p.event.table_number).collect();
+        assert_eq!(tables, vec![2, 3]);
+        assert_eq!(kept[1].id, kept[0].id + 1);
+
+        let (missed, _) = bus.subscribe_after(kept[0].id);
+        assert_eq!(missed.len(), 1);
+        assert_eq!(missed[0].event.table_number, 3);
+    }
+}
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use api_core::{domain::event::DomainEvent, error::Error, notifier::EventSubscriber};
+use async_trait::async_trait;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt};
+
+/// Appends every domain event to a file as one line of JSON,
+/// `{"event_id": <outbox id>, "event": {...}}`. An event only counts as
+/// handled once its line is on disk.
+#[derive(Clone)]
+pub struct FileSink {
+    path: PathBuf,
+}
+
+impl FileSink {
+    pub fn new(path: PathBuf) -> Self {
+        FileSink { path }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for FileSink {
+    async fn handle(&self, event_id: u64, event: &DomainEvent) -> Result<(), Error> {
+        let line = serde_json::json!({ "event_id": event_id, "event": event });
+        let mut line = serde_json::to_string(&line).map_err(|_| Error::EventDeliveryError)?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|_| Error::EventDeliveryError)?;
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|_| Error::EventDeliveryError)?;
+        file.flush().await.map_err(|_| Error::EventDeliveryError)?;
+        file.sync_data()
+            .await
+            .map_err(|_| Error::EventDeliveryError)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use api_core::domain::item::Item;
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn test_events_are_appended_as_json_lines() {
+        let path = std::env::temp_dir().join(format!("outbox-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let sink = FileSink::new(path.clone());
+
+        let now = Local::now().naive_local();
+        let item = Item::new(String::from("Ramen"), 1, now).unwrap();
+        let event = DomainEvent::ItemAdded {
+            table_number: 1,
+            item,
+            occurred_at: now,
+        };
+        sink.handle(1, &event).await.unwrap();
+        // a retry writes the same id again
+        sink.handle(1, &event).await.unwrap();
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        let lines = written.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 2);
+        let line: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(line["event_id"], 1);
+        assert_eq!(
+            serde_json::from_value::<DomainEvent>(line["event"].clone()).unwrap(),
+            event
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,34 @@
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use api_core::{domain::waitlist::WaitlistEntry, error::Error, notifier::WaitlistNotifier};
+use async_trait::async_trait;
+
+/// Writes guest notifications to a log instead of sending them, for local
+/// runs and deployments without an SMS gateway.
+#[derive(Clone)]
+pub struct LoggingNotifier {
+    sink: Arc<Mutex<dyn Write + Send>>,
+}
+
+impl LoggingNotifier {
+    pub fn new(sink: impl Write + Send + 'static) -> Self {
+        LoggingNotifier {
+            sink: Arc::new(Mutex::new(sink)),
+        }
+    }
+}
+
+#[async_trait]
+impl WaitlistNotifier for LoggingNotifier {
+    async fn notify(&self, entry: &WaitlistEntry, message: String) -> Result<(), Error> {
+        let mut sink = self.sink.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(
+            sink,
+            "[notify] {} ({}): {}",
+            entry.guest_name, entry.guest_phone, message
+        )
+        .and_then(|_| sink.flush())
+        .map_err(|_| Error::NotificationError)
+    }
+}
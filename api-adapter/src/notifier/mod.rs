@@ -0,0 +1,6 @@
+pub mod dispatcher;
+pub mod event_bus;
+pub mod file;
+pub mod logging;
+pub mod waitlist;
+pub mod webhook;
@@ -0,0 +1,160 @@
+use api_core::{
+    domain::event::DomainEvent,
+    error::Error,
+    notifier::{EventSubscriber, WaitlistNotifier},
+    repository::waitlist_repository::WaitlistRepository,
+};
+use async_trait::async_trait;
+
+/// Tells guests on the waitlist how long they are quoted to wait, and calls
+/// them in once they have a table.
+#[derive(Clone)]
+pub struct WaitlistMessenger<R, N> {
+    repository: R,
+    notifier: N,
+}
+
+impl<R, N> WaitlistMessenger<R, N> {
+    pub fn new(repository: R, notifier: N) -> Self {
+        WaitlistMessenger {
+            repository,
+            notifier,
+        }
+    }
+}
+
+#[async_trait]
+impl<R, N> EventSubscriber for WaitlistMessenger<R, N>
+where
+    R: WaitlistRepository + Send + Sync,
+    N: WaitlistNotifier + Send + Sync,
+{
+    async fn handle(&self, _: u64, event: &DomainEvent) -> Result<(), Error> {
+        match event {
+            DomainEvent::WaitlistJoined { entry_id, .. } => {
+                let entry = self.repository.find_one(*entry_id).await?;
+                let message = format!(
+                    "You're on our waitlist for a party of {}. Estimated wait: {} minutes.",
+                    entry.party_size, entry.quoted_wait_minutes
+                );
+                self.notifier.notify(&entry, message).await
+            }
+            DomainEvent::WaitlistCalled {
+                entry_id,
+                table_number,
+                ..
+            } => {
+                let entry = self.repository.find_one(*entry_id).await?;
+                let message = format!(
+                    "Your table is ready! Please come to table {}.",
+                    table_number
+                );
+                self.notifier.notify(&entry, message).await
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::notifier::logging::LoggingNotifier;
+    use api_core::domain::{audit::AuditRecord, table::Table, waitlist::WaitlistEntry};
+    use chrono::{Local, NaiveDateTime};
+    use pretty_assertions::assert_eq;
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    struct OneEntry(WaitlistEntry);
+
+    #[async_trait]
+    impl WaitlistRepository for OneEntry {
+        async fn find_one(&self, entry_id: u32) -> Result<WaitlistEntry, Error> {
+            match self.0.id {
+                Some(id) if id == entry_id => Ok(self.0.clone()),
+                _ => Err(Error::WaitlistEntryNotFoundError),
+            }
+        }
+
+        async fn find_waiting(&self) -> Result<Vec<WaitlistEntry>, Error> {
+            Ok(vec![self.0.clone()])
+        }
+
+        async fn create(&self, _entry: WaitlistEntry, _audit: AuditRecord) -> Result<u64, Error> {
+            Err(Error::WaitlistEntryInsertionError)
+        }
+
+        async fn update(&self, _entry: WaitlistEntry, _audit: AuditRecord) -> Result<(), Error> {
+            Err(Error::WaitlistEntryUpdateError)
+        }
+
+        async fn seat(
+            &self,
+            _entry: WaitlistEntry,
+            _table: Table,
+            _now: NaiveDateTime,
+            _audit: AuditRecord,
+        ) -> Result<(), Error> {
+            Err(Error::WaitlistEntryUpdateError)
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_guests_are_told_and_called_in() {
+        let now = Local::now().naive_local();
+        let mut entry =
+            WaitlistEntry::new(String::from("Sato"), String::from("080-0000-0000"), 2, now)
+                .unwrap();
+        entry.id = Some(7);
+        entry.quoted_wait_minutes = 15;
+        let buffer = Buffer::default();
+        let messenger =
+            WaitlistMessenger::new(OneEntry(entry), LoggingNotifier::new(buffer.clone()));
+
+        let joined = DomainEvent::WaitlistJoined {
+            entry_id: 7,
+            occurred_at: now,
+        };
+        let called = DomainEvent::WaitlistCalled {
+            entry_id: 7,
+            table_number: 3,
+            occurred_at: now,
+        };
+        messenger.handle(1, &joined).await.unwrap();
+        messenger.handle(2, &called).await.unwrap();
+        let closed = DomainEvent::TableClosed {
+            table_number: 3,
+            occurred_at: now,
+        };
+        messenger.handle(3, &closed).await.unwrap();
+
+        let written = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            written.lines().collect::<Vec<&str>>(),
+            vec![
+                "[notify] Sato (080-0000-0000): You're on our waitlist for a party of 2. Estimated wait: 15 minutes.",
+                "[notify] Sato (080-0000-0000): Your table is ready! Please come to table 3.",
+            ]
+        );
+
+        let unknown = DomainEvent::WaitlistJoined {
+            entry_id: 8,
+            occurred_at: now,
+        };
+        assert_eq!(messenger.handle(4, &unknown).await.is_err(), true);
+    }
+}
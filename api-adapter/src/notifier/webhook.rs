@@ -0,0 +1,266 @@
+use std::time::Duration;
+
+use api_core::{
+    domain::{
+        event::DomainEvent,
+        webhook::{WebhookDelivery, WebhookSubscription},
+    },
+    error::Error,
+    notifier::{EventSubscriber, WebhookSender},
+    repository::webhook_repository::WebhookRepository,
+};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const SIGNATURE_HEADER: &str = "x-webhook-signature";
+pub const EVENT_HEADER: &str = "x-webhook-event";
+pub const DELIVERY_HEADER: &str = "x-webhook-delivery";
+
+/// Posts every domain event as JSON to a fixed URL, with its outbox id in
+/// `x-webhook-delivery`. Anything but a 2xx answer, or none within
+/// `HttpWebhookSender::TIMEOUT`, counts as a failed delivery.
+#[derive(Clone)]
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl WebhookSink {
+    pub fn new(url: String) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(HttpWebhookSender::TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        WebhookSink { client, url }
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for WebhookSink {
+    async fn handle(&self, event_id: u64, event: &DomainEvent) -> Result<(), Error> {
+        self.client
+            .post(&self.url)
+            .header(EVENT_HEADER, event.name())
+            .header(DELIVERY_HEADER, event_id.to_string())
+            .json(event)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map(|_| ())
+            .map_err(|_| Error::EventDeliveryError)
+    }
+}
+
+/// Queues a delivery for every webhook subscription that wants the event.
+/// The deliveries themselves are made later by the webhook use case. When
+/// the event is handled again, the subscriptions that have their delivery
+/// already get no second one.
+#[derive(Clone)]
+pub struct WebhookScheduler<R> {
+    repository: R,
+}
+
+impl<R> WebhookScheduler<R> {
+    pub fn new(repository: R) -> Self {
+        WebhookScheduler { repository }
+    }
+}
+
+#[async_trait]
+impl<R: WebhookRepository + Send + Sync> EventSubscriber for WebhookScheduler<R> {
+    async fn handle(&self, event_id: u64, event: &DomainEvent) -> Result<(), Error> {
+        let payload = serde_json::to_string(event).map_err(|_| Error::WebhookStoreError)?;
+        for subscription in self.repository.find_all().await? {
+            if let (Some(id), true) = (subscription.id, subscription.wants(event)) {
+                let delivery = WebhookDelivery::new(
+                    id,
+                    event.name().to_string(),
+                    payload.clone(),
+                    event.occurred_at(),
+                );
+                self.repository.create_delivery(event_id, delivery).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sends webhook deliveries over HTTP. The body is signed with HMAC-SHA256
+/// under the subscription's secret and the hex digest is sent as
+/// `x-webhook-signature: sha256=<digest>`, so receivers can check where a
+/// request came from.
+#[derive(Clone)]
+pub struct HttpWebhookSender {
+    client: reqwest::Client,
+}
+
+impl HttpWebhookSender {
+    pub const TIMEOUT: Duration = Duration::from_secs(10);
+}
+
+impl Default for HttpWebhookSender {
+    fn default() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(HttpWebhookSender::TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        HttpWebhookSender { client }
+    }
+}
+
+#[async_trait]
+impl WebhookSender for HttpWebhookSender {
+    async fn send(
+        &self,
+        subscription: &WebhookSubscription,
+        delivery: &WebhookDelivery,
+    ) -> Result<u16, String> {
+        let signature = sign(&subscription.secret, &delivery.payload);
+        self.client
+            .post(&subscription.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &delivery.event_type)
+            .header(
+                DELIVERY_HEADER,
+                delivery.id.map(|id| id.to_string()).unwrap_or_default(),
+            )
+            .header(SIGNATURE_HEADER, format!("sha256={}", signature))
+            .body(delivery.payload.clone())
+            .send()
+            .await
+            .map(|response| response.status().as_u16())
+            .map_err(|e| e.to_string())
+    }
+}
+
+pub fn sign(secret: &str, payload: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(payload.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    /// A local HTTP server that answers one request with `status` and hands
+    /// back the request it got.
+    async fn stand_in(status: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !is_complete(&request) {
+                let read = socket.read(&mut buffer).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buffer[..read]);
+            }
+            let response = format!("HTTP/1.1 {}\r\ncontent-length: 0\r\n\r\n", status);
+            socket.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+        (url, handle)
+    }
+
+    fn is_complete(request: &[u8]) -> bool {
+        let text = String::from_utf8_lossy(request);
+        match text.split_once("\r\n\r\n") {
+            Some((head, body)) => {
+                let length = head
+                    .lines()
+                    .filter_map(|line| line.split_once(':'))
+                    .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+                    .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+                    .unwrap_or(0);
+                body.len() >= length
+            }
+            None => false,
+        }
+    }
+
+    fn header<'a>(request: &'a str, name: &str) -> Option<&'a str> {
+        request
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim())
+    }
+
+    fn webhook(url: String) -> (WebhookSubscription, WebhookDelivery) {
+        let subscription = WebhookSubscription::of(1, url, String::from("secret"), vec![], true, 0);
+        let delivery = WebhookDelivery::new(
+            1,
+            String::from("table_closed"),
+            String::from(r#"{"type":"table_closed","table_number":1}"#),
+            Local::now().naive_local(),
+        );
+        (subscription, delivery)
+    }
+
+    #[test]
+    fn test_signature_is_hmac_sha256() {
+        // RFC 4231, test case 2
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delivery_is_signed() {
+        let (url, received) = stand_in("202 Accepted").await;
+        let (subscription, delivery) = webhook(url);
+
+        let sender = HttpWebhookSender::default();
+        assert_eq!(sender.send(&subscription, &delivery).await, Ok(202));
+
+        let request = received.await.unwrap();
+        let expected = format!("sha256={}", sign("secret", &delivery.payload));
+        assert_eq!(header(&request, SIGNATURE_HEADER), Some(expected.as_str()));
+        assert_eq!(header(&request, EVENT_HEADER), Some("table_closed"));
+        assert_eq!(request.ends_with(&delivery.payload), true);
+    }
+
+    #[tokio::test]
+    async fn test_sink_names_the_outbox_event() {
+        let (url, received) = stand_in("200 OK").await;
+        let event = DomainEvent::TableClosed {
+            table_number: 1,
+            occurred_at: Local::now().naive_local(),
+        };
+        WebhookSink::new(url).handle(7, &event).await.unwrap();
+
+        let request = received.await.unwrap();
+        assert_eq!(header(&request, DELIVERY_HEADER), Some("7"));
+        assert_eq!(header(&request, EVENT_HEADER), Some("table_closed"));
+    }
+
+    #[tokio::test]
+    async fn test_error_answers_and_refused_connections() {
+        let (url, received) = stand_in("503 Service Unavailable").await;
+        let (subscription, delivery) = webhook(url);
+        let sender = HttpWebhookSender::default();
+        assert_eq!(sender.send(&subscription, &delivery).await, Ok(503));
+        received.await.unwrap();
+
+        // nothing listens on the port once the stand-in is gone
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks", listener.local_addr().unwrap());
+        drop(listener);
+        let (subscription, delivery) = webhook(url);
+        assert_eq!(sender.send(&subscription, &delivery).await.is_err(), true);
+    }
+}
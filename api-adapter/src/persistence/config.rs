@@ -0,0 +1,433 @@
+//! How the backends size their pool and reach their database at startup.
+
+use sqlx::{migrate::MigrateError, pool::PoolOptions, Database, Pool};
+use std::{env, fmt, str::FromStr, time::Duration};
+
+/// The longest the startup retries wait between two attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Whether and how the connection to the database server is encrypted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlsMode {
+    Disabled,
+    Preferred,
+    Required,
+    VerifyCa,
+    VerifyIdentity,
+}
+
+impl TlsMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TlsMode::Disabled => "disabled",
+            TlsMode::Preferred => "preferred",
+            TlsMode::Required => "required",
+            TlsMode::VerifyCa => "verify-ca",
+            TlsMode::VerifyIdentity => "verify-identity",
+        }
+    }
+}
+
+impl fmt::Display for TlsMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TlsMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "disabled" => Ok(TlsMode::Disabled),
+            "preferred" => Ok(TlsMode::Preferred),
+            "required" => Ok(TlsMode::Required),
+            "verify-ca" => Ok(TlsMode::VerifyCa),
+            "verify-identity" => Ok(TlsMode::VerifyIdentity),
+            _ => Err(String::from(
+                "expected one of disabled, preferred, required, verify-ca, verify-identity",
+            )),
+        }
+    }
+}
+
+/// Which pool a read goes to. Without a replica every read goes to the primary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadFrom {
+    Primary,
+    Replica,
+}
+
+impl ReadFrom {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadFrom::Primary => "primary",
+            ReadFrom::Replica => "replica",
+        }
+    }
+}
+
+impl fmt::Display for ReadFrom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ReadFrom {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "primary" => Ok(ReadFrom::Primary),
+            "replica" => Ok(ReadFrom::Replica),
+            _ => Err(String::from("expected primary or replica")),
+        }
+    }
+}
+
+/// Where each read of the items goes. Writes, and the reads a write builds
+/// on, always go to the primary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemReadRouting {
+    pub find_all: ReadFrom,
+    pub find_one: ReadFrom,
+    /// Listings are tagged with the revision, so it is best read from where
+    /// `find_all` reads: a revision ahead of the listing lets pollers keep a
+    /// stale one.
+    pub find_revision: ReadFrom,
+}
+
+impl Default for ItemReadRouting {
+    fn default() -> Self {
+        ItemReadRouting {
+            find_all: ReadFrom::Replica,
+            find_one: ReadFrom::Replica,
+            find_revision: ReadFrom::Replica,
+        }
+    }
+}
+
+/// The connection settings every backend shares.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DbConfig {
+    pub url: String,
+    pub max_connections: u32,
+    /// Connections the pool keeps open even when nothing uses them.
+    pub min_idle: u32,
+    /// How long a query waits for a connection before it fails.
+    pub acquire_timeout: Duration,
+    /// `None` keeps idle connections open for good.
+    pub idle_timeout: Option<Duration>,
+    /// Prepared statements kept per connection.
+    pub statement_cache_capacity: usize,
+    /// `None` leaves it to the url, e.g. `?ssl-mode=required`.
+    pub tls_mode: Option<TlsMode>,
+    /// How many more times the first connection is tried when the database
+    /// can't be reached, e.g. because it is still starting up.
+    pub connect_retries: u32,
+    /// The wait before the first retry, doubled for every one after it.
+    pub retry_backoff: Duration,
+    /// A read replica of the database, connected with the same settings.
+    /// Only MySQL can use one.
+    pub replica_url: Option<String>,
+    pub item_reads: ItemReadRouting,
+}
+
+impl DbConfig {
+    pub fn new(url: String) -> DbConfig {
+        DbConfig {
+            url,
+            max_connections: 10,
+            min_idle: 0,
+            acquire_timeout: Duration::from_secs(30),
+            idle_timeout: Some(Duration::from_secs(600)),
+            statement_cache_capacity: 100,
+            tls_mode: None,
+            connect_retries: 5,
+            retry_backoff: Duration::from_millis(500),
+            replica_url: None,
+            item_reads: ItemReadRouting::default(),
+        }
+    }
+
+    /// Reads the url from `url_key`, or takes `default_url` when that isn't
+    /// set, and the pool settings from the `DATABASE_*` variables. Settings
+    /// left unset keep the defaults of `new`.
+    pub fn from_env(url_key: &str, default_url: Option<&str>) -> Result<DbConfig, DbError> {
+        DbConfig::from_vars(url_key, default_url, |key| env::var(key).ok())
+    }
+
+    fn from_vars(
+        url_key: &str,
+        default_url: Option<&str>,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<DbConfig, DbError> {
+        let url = var(url_key)
+            .or_else(|| default_url.map(String::from))
+            .ok_or_else(|| DbError::config(url_key, "is not set"))?;
+        let mut config = DbConfig::new(url);
+
+        if let Some(value) = setting(&var, "DATABASE_MAX_CONNECTIONS")? {
+            config.max_connections = value;
+        }
+        if let Some(value) = setting(&var, "DATABASE_MIN_IDLE")? {
+            config.min_idle = value;
+        }
+        if let Some(secs) = setting(&var, "DATABASE_ACQUIRE_TIMEOUT_SECS")? {
+            config.acquire_timeout = Duration::from_secs(secs);
+        }
+        if let Some(secs) = setting(&var, "DATABASE_IDLE_TIMEOUT_SECS")? {
+            // 0 turns the timeout off
+            config.idle_timeout = Some(Duration::from_secs(secs)).filter(|t| !t.is_zero());
+        }
+        if let Some(value) = setting(&var, "DATABASE_STATEMENT_CACHE_CAPACITY")? {
+            config.statement_cache_capacity = value;
+        }
+        if let Some(value) = setting(&var, "DATABASE_TLS_MODE")? {
+            config.tls_mode = Some(value);
+        }
+        if let Some(value) = setting(&var, "DATABASE_CONNECT_RETRIES")? {
+            config.connect_retries = value;
+        }
+        if let Some(millis) = setting(&var, "DATABASE_RETRY_BACKOFF_MS")? {
+            config.retry_backoff = Duration::from_millis(millis);
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Rejects settings the pool can't work with, naming the `DATABASE_*`
+    /// variable of the offending one.
+    pub fn validate(&self) -> Result<(), DbError> {
+        if self.max_connections == 0 {
+            return Err(DbError::config(
+                "DATABASE_MAX_CONNECTIONS",
+                "must be at least 1",
+            ));
+        }
+        if self.min_idle > self.max_connections {
+            return Err(DbError::config(
+                "DATABASE_MIN_IDLE",
+                format!("must not exceed the pool size ({})", self.max_connections),
+            ));
+        }
+        if self.acquire_timeout.is_zero() {
+            return Err(DbError::config(
+                "DATABASE_ACQUIRE_TIMEOUT_SECS",
+                "must be at least 1",
+            ));
+        }
+        Ok(())
+    }
+
+    /// For the backends that have no use for a replica.
+    #[cfg(any(feature = "postgres", feature = "sqlite"))]
+    pub(crate) fn reject_replica(&self) -> Result<(), DbError> {
+        match self.replica_url {
+            Some(_) => Err(DbError::config(
+                "DATABASE_REPLICA_URL",
+                "is only supported on MySQL",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// The pool sized as configured. It only connects once it is used.
+    pub(crate) fn pool_options<DB: Database>(&self) -> PoolOptions<DB> {
+        PoolOptions::new()
+            .max_connections(self.max_connections)
+            .min_connections(self.min_idle)
+            .connect_timeout(self.acquire_timeout)
+            .idle_timeout(self.idle_timeout)
+    }
+}
+
+fn setting<T>(var: &impl Fn(&str) -> Option<String>, key: &str) -> Result<Option<T>, DbError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    var(key)
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|e| DbError::config(key, format!("`{}` is not valid: {}", value, e)))
+        })
+        .transpose()
+}
+
+/// Why the database could not be opened.
+#[derive(Debug)]
+pub enum DbError {
+    /// A setting that can't be used, with the key it was read from.
+    Config { key: String, message: String },
+    /// The url can't be parsed, or names options the backend doesn't know.
+    InvalidUrl(sqlx::Error),
+    /// The database refused the connection or still couldn't be reached
+    /// after the retries.
+    Connect { attempts: u32, source: sqlx::Error },
+    /// A database that is migrated when it is opened couldn't be.
+    Migrate(MigrateError),
+}
+
+impl DbError {
+    fn config(key: &str, message: impl Into<String>) -> DbError {
+        DbError::Config {
+            key: key.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::Config { key, message } => write!(f, "{} {}", key, message),
+            DbError::InvalidUrl(source) => write!(f, "the database url is not valid: {}", source),
+            DbError::Connect { attempts, source } => write!(
+                f,
+                "could not connect to the database after {} attempt(s): {}",
+                attempts, source
+            ),
+            DbError::Migrate(source) => {
+                write!(f, "the migrations could not be applied: {}", source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+/// Waits for `pool` to hand out its first connection, retrying with a
+/// doubling backoff as long as the database can't be reached. Any other
+/// failure, like rejected credentials, is returned right away.
+pub(crate) async fn connect_with_retry<DB: Database>(
+    config: &DbConfig,
+    pool: &Pool<DB>,
+) -> Result<(), DbError> {
+    let mut backoff = config.retry_backoff;
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match pool.acquire().await {
+            Ok(_) => return Ok(()),
+            Err(e) if attempts <= config.connect_retries && is_unreachable(&e) => {
+                tracing::warn!(
+                    "database connection attempt {} of {} failed: {}, retrying in {:?}",
+                    attempts,
+                    config.connect_retries + 1,
+                    e,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+            Err(source) => return Err(DbError::Connect { attempts, source }),
+        }
+    }
+}
+
+fn is_unreachable(e: &sqlx::Error) -> bool {
+    matches!(e, sqlx::Error::Io(_) | sqlx::Error::PoolTimedOut)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn from_vars(vars: &[(&str, &str)]) -> Result<DbConfig, DbError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        DbConfig::from_vars("DATABASE_URL", None, |key| vars.get(key).cloned())
+    }
+
+    fn offending_key(result: Result<DbConfig, DbError>) -> String {
+        match result {
+            Err(DbError::Config { key, .. }) => key,
+            other => panic!("expected a config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unset_settings_keep_their_defaults() {
+        let config = from_vars(&[("DATABASE_URL", "mysql://localhost/restaurant")]).unwrap();
+        assert_eq!(
+            config,
+            DbConfig::new(String::from("mysql://localhost/restaurant"))
+        );
+    }
+
+    #[test]
+    fn test_settings_are_read_from_the_environment() {
+        let config = from_vars(&[
+            ("DATABASE_URL", "mysql://localhost/restaurant"),
+            ("DATABASE_MAX_CONNECTIONS", "20"),
+            ("DATABASE_MIN_IDLE", "2"),
+            ("DATABASE_ACQUIRE_TIMEOUT_SECS", "5"),
+            ("DATABASE_IDLE_TIMEOUT_SECS", "0"),
+            ("DATABASE_STATEMENT_CACHE_CAPACITY", "0"),
+            ("DATABASE_TLS_MODE", "verify-ca"),
+            ("DATABASE_CONNECT_RETRIES", "0"),
+            ("DATABASE_RETRY_BACKOFF_MS", "100"),
+        ])
+        .unwrap();
+
+        assert_eq!(config.max_connections, 20);
+        assert_eq!(config.min_idle, 2);
+        assert_eq!(config.acquire_timeout, Duration::from_secs(5));
+        assert_eq!(config.idle_timeout, None);
+        assert_eq!(config.statement_cache_capacity, 0);
+        assert_eq!(config.tls_mode, Some(TlsMode::VerifyCa));
+        assert_eq!(config.connect_retries, 0);
+        assert_eq!(config.retry_backoff, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_errors_name_the_offending_key() {
+        assert_eq!(offending_key(from_vars(&[])), "DATABASE_URL");
+
+        let url = ("DATABASE_URL", "mysql://localhost/restaurant");
+        assert_eq!(
+            offending_key(from_vars(&[url, ("DATABASE_MAX_CONNECTIONS", "ten")])),
+            "DATABASE_MAX_CONNECTIONS"
+        );
+        assert_eq!(
+            offending_key(from_vars(&[url, ("DATABASE_MAX_CONNECTIONS", "0")])),
+            "DATABASE_MAX_CONNECTIONS"
+        );
+        assert_eq!(
+            offending_key(from_vars(&[
+                url,
+                ("DATABASE_MAX_CONNECTIONS", "2"),
+                ("DATABASE_MIN_IDLE", "3")
+            ])),
+            "DATABASE_MIN_IDLE"
+        );
+        assert_eq!(
+            offending_key(from_vars(&[url, ("DATABASE_TLS_MODE", "always")])),
+            "DATABASE_TLS_MODE"
+        );
+    }
+
+    #[cfg(feature = "mysql")]
+    #[tokio::test]
+    async fn test_an_unreachable_database_is_retried_then_reported() {
+        // nothing listens on port 1, so every attempt is refused
+        let mut config = DbConfig::new(String::from("mysql://root@127.0.0.1:1/restaurant"));
+        config.acquire_timeout = Duration::from_millis(100);
+        config.connect_retries = 2;
+        config.retry_backoff = Duration::from_millis(10);
+        let pool = config
+            .pool_options::<sqlx::MySql>()
+            .connect_lazy(&config.url)
+            .unwrap();
+
+        let result = connect_with_retry(&config, &pool).await;
+        assert!(matches!(result, Err(DbError::Connect { attempts: 3, .. })));
+    }
+}
@@ -1 +1,13 @@
+pub mod config;
+pub mod migration;
+#[cfg(feature = "mysql")]
 pub mod mysql;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+pub enum Env {
+    Production,
+    Test,
+}
@@ -0,0 +1,60 @@
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Pool, Sqlite};
+use std::str::FromStr;
+
+use super::config::{connect_with_retry, DbConfig, DbError};
+use super::migration::SQLITE_MIGRATOR;
+use super::Env;
+
+/// A SQLite database file, or with `sqlite::memory:` one that lives as long as
+/// its pool does.
+///
+/// Unlike the server backends it is migrated as soon as it is opened, since an
+/// in-memory database starts out empty every time.
+#[derive(Clone)]
+pub struct SqliteDb {
+    pub pool: Pool<Sqlite>,
+}
+
+impl SqliteDb {
+    /// Opens `SQLITE_DATABASE_URL`, `restaurant.db` by default, or for the
+    /// tests `TEST_SQLITE_DATABASE_URL`, a new in-memory database by default.
+    pub async fn new(env: Env) -> Result<SqliteDb, DbError> {
+        let config = match env {
+            Env::Production => {
+                DbConfig::from_env("SQLITE_DATABASE_URL", Some("sqlite://restaurant.db"))?
+            }
+            Env::Test => DbConfig::from_env("TEST_SQLITE_DATABASE_URL", Some("sqlite::memory:"))?,
+        };
+        SqliteDb::connect(&config).await
+    }
+
+    /// Opens the database as configured; a file has no use for `tls_mode`.
+    pub async fn connect(config: &DbConfig) -> Result<SqliteDb, DbError> {
+        config.reject_replica()?;
+        let options = SqliteConnectOptions::from_str(&config.url)
+            .map_err(DbError::InvalidUrl)?
+            .create_if_missing(true)
+            .statement_cache_capacity(config.statement_cache_capacity);
+        let pool_options = if is_in_memory(&config.url) {
+            // every connection to it would open a database of its own, so the
+            // pool keeps exactly one for good
+            config
+                .pool_options()
+                .max_connections(1)
+                .min_connections(1)
+                .idle_timeout(None)
+                .max_lifetime(None)
+        } else {
+            config.pool_options()
+        };
+        let pool = pool_options.connect_lazy_with(options);
+        connect_with_retry(config, &pool).await?;
+        SQLITE_MIGRATOR.run(&pool).await.map_err(DbError::Migrate)?;
+        Ok(SqliteDb { pool })
+    }
+}
+
+fn is_in_memory(url: &str) -> bool {
+    url.contains(":memory:") || url.contains("mode=memory")
+}
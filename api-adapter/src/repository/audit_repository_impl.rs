@@ -0,0 +1,86 @@
+use crate::model::audit::AuditRecordRow;
+use crate::persistence::mysql::Db;
+use api_core::{
+    domain::audit::{AuditFilter, AuditRecord},
+    error::Error,
+    repository::audit_repository::AuditRepository,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use sqlx::{MySql, Transaction};
+
+#[derive(new, Clone)]
+pub struct AuditRepositoryImpl {
+    db: Db,
+}
+
+/// Writes the audit record of a change as part of `tx`, the transaction
+/// making it, so that neither is kept without the other.
+pub(crate) async fn record(
+    tx: &mut Transaction<'_, MySql>,
+    record: &AuditRecord,
+) -> Result<u64, Error> {
+    let done = sqlx::query!(
+        r#"
+    INSERT INTO audit_records (actor_kind, actor_id, action, table_number, item_id, before_snapshot, after_snapshot)
+    VALUES ( ?, ?, ?, ?, ?, ?, ? )
+            "#,
+        record.actor_kind.as_str(),
+        record.actor_id,
+        record.action.as_str(),
+        record.table_number,
+        record.item_id,
+        record.before.as_deref(),
+        record.after.as_deref()
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| Error::AuditInsertionError)?;
+    Ok(done.last_insert_id())
+}
+
+#[async_trait]
+impl AuditRepository for AuditRepositoryImpl {
+    async fn append(&self, audit: AuditRecord) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::AuditInsertionError)?;
+        let id = record(&mut tx, &audit).await?;
+        tx.commit().await.map_err(|_| Error::AuditInsertionError)?;
+        Ok(id)
+    }
+
+    async fn find(&self, filter: AuditFilter) -> Result<Vec<AuditRecord>, Error> {
+        let actor_kind = filter.actor_kind.map(|kind| kind.as_str());
+        let result = sqlx::query_as!(
+            AuditRecordRow,
+            r#"SELECT id, actor_kind, actor_id, action, table_number, item_id, before_snapshot, after_snapshot, recorded_at
+          FROM audit_records
+          WHERE (? IS NULL OR table_number = ?)
+            AND (? IS NULL OR item_id = ?)
+            AND (? IS NULL OR actor_kind = ?)
+            AND (? IS NULL OR actor_id = ?)
+          ORDER BY id DESC
+          LIMIT ?"#,
+            filter.table_number,
+            filter.table_number,
+            filter.item_id,
+            filter.item_id,
+            actor_kind,
+            actor_kind,
+            filter.actor_id,
+            filter.actor_id,
+            filter.limit
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => AuditRecordRow::from_rows(rows).map_err(|_| Error::AuditQueryError),
+            Err(_) => Err(Error::AuditQueryError),
+        }
+    }
+}
@@ -0,0 +1,569 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use api_core::{
+    domain::{
+        audit::AuditRecord,
+        item::{Item, ItemsRevision},
+        item_transfer::ItemTransfer,
+        table::Table,
+    },
+    error::Error,
+    repository::item_repository::ItemRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+/// How long the listings are kept, and of how many tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ItemCacheConfig {
+    /// Changes made through the same repository are seen at once, so this
+    /// only bounds how long changes made elsewhere, e.g. by another server,
+    /// go unseen. Zero turns the cache off.
+    pub ttl: Duration,
+    /// With this many tables cached, the listing cached longest ago makes
+    /// room for the next one.
+    pub max_tables: usize,
+    /// For this long after a table's items change, its listing is read but
+    /// not kept: a replica lagging behind may not have the change yet, and
+    /// the stale listing would otherwise be served for all of `ttl`.
+    pub settle: Duration,
+}
+
+impl Default for ItemCacheConfig {
+    fn default() -> Self {
+        ItemCacheConfig {
+            ttl: Duration::from_secs(2),
+            max_tables: 256,
+            settle: Duration::from_secs(1),
+        }
+    }
+}
+
+impl ItemCacheConfig {
+    pub fn disabled() -> Self {
+        ItemCacheConfig {
+            ttl: Duration::ZERO,
+            ..ItemCacheConfig::default()
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero() && self.max_tables > 0
+    }
+}
+
+/// What the cache did since it was created.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ItemCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Listings dropped to make room, not counting the expired ones.
+    pub evictions: u64,
+}
+
+/// Keeps each table's item listing in memory in front of another
+/// `ItemRepository`, so that polling tablets don't all reach the database.
+///
+/// The revision is cached along with the listing and read before it, so the
+/// `ETag` a listing is served with is never ahead of the listing itself.
+/// Every change of a table's items drops its listing.
+#[derive(Clone)]
+pub struct CachedItemRepository<R> {
+    inner: R,
+    config: ItemCacheConfig,
+    listings: Arc<Mutex<Listings>>,
+    counters: Arc<Counters>,
+}
+
+#[derive(Default)]
+struct Listings {
+    entries: HashMap<u32, Listing>,
+    /// Bumped whenever a table's items change, so that a listing read while
+    /// they were changing isn't kept.
+    generations: HashMap<u32, u64>,
+    /// When each table's items last changed, to hold its listing back for
+    /// `ItemCacheConfig::settle`.
+    changed_at: HashMap<u32, Instant>,
+}
+
+struct Listing {
+    items: Vec<Item>,
+    revision: ItemsRevision,
+    cached_at: Instant,
+}
+
+#[derive(Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl<R> CachedItemRepository<R> {
+    pub fn new(inner: R, config: ItemCacheConfig) -> Self {
+        CachedItemRepository {
+            inner,
+            config,
+            listings: Arc::new(Mutex::new(Listings::default())),
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    pub fn stats(&self) -> ItemCacheStats {
+        ItemCacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            evictions: self.counters.evictions.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Looks the table's listing up, counting a hit or a miss.
+    fn cached<T>(&self, table_number: u32, pick: impl FnOnce(&Listing) -> T) -> Option<T> {
+        let mut listings = self.listings.lock().unwrap_or_else(|e| e.into_inner());
+        let found = match listings.entries.get(&table_number) {
+            Some(listing) if listing.cached_at.elapsed() < self.config.ttl => Some(pick(listing)),
+            Some(_) => {
+                listings.entries.remove(&table_number);
+                None
+            }
+            None => None,
+        };
+        let counter = match found {
+            Some(_) => &self.counters.hits,
+            None => &self.counters.misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    fn generation(&self, table_number: u32) -> u64 {
+        let listings = self.listings.lock().unwrap_or_else(|e| e.into_inner());
+        listings
+            .generations
+            .get(&table_number)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Keeps a listing read at `generation`, unless the table's items have
+    /// changed since or too recently before.
+    fn store(&self, table_number: u32, generation: u64, items: &[Item], revision: ItemsRevision) {
+        let mut listings = self.listings.lock().unwrap_or_else(|e| e.into_inner());
+        if listings
+            .generations
+            .get(&table_number)
+            .copied()
+            .unwrap_or_default()
+            != generation
+        {
+            return;
+        }
+        if listings
+            .changed_at
+            .get(&table_number)
+            .is_some_and(|changed_at| changed_at.elapsed() < self.config.settle)
+        {
+            return;
+        }
+        if !listings.entries.contains_key(&table_number)
+            && listings.entries.len() >= self.config.max_tables
+        {
+            // expired listings go first, then the one cached longest ago
+            let ttl = self.config.ttl;
+            listings
+                .entries
+                .retain(|_, listing| listing.cached_at.elapsed() < ttl);
+            if listings.entries.len() >= self.config.max_tables {
+                let oldest = listings
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, listing)| listing.cached_at)
+                    .map(|(table_number, _)| *table_number);
+                if let Some(oldest) = oldest {
+                    listings.entries.remove(&oldest);
+                    self.counters.evictions.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        listings.entries.insert(
+            table_number,
+            Listing {
+                items: items.to_vec(),
+                revision,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+
+    fn invalidate(&self, table_numbers: &[u32]) {
+        let mut listings = self.listings.lock().unwrap_or_else(|e| e.into_inner());
+        for table_number in table_numbers {
+            listings.entries.remove(table_number);
+            *listings.generations.entry(*table_number).or_default() += 1;
+            listings.changed_at.insert(*table_number, Instant::now());
+        }
+    }
+}
+
+impl<R: ItemRepository> CachedItemRepository<R> {
+    async fn load(&self, table_number: u32) -> Result<(Vec<Item>, ItemsRevision), Error> {
+        let generation = self.generation(table_number);
+        let revision = self.inner.find_revision(table_number).await?;
+        let items = self.inner.find_all(table_number).await?;
+        self.store(table_number, generation, &items, revision);
+        Ok((items, revision))
+    }
+}
+
+#[async_trait]
+impl<R: ItemRepository + Send + Sync> ItemRepository for CachedItemRepository<R> {
+    async fn find_all(&self, table_number: u32) -> Result<Vec<Item>, Error> {
+        if !self.config.is_enabled() {
+            return self.inner.find_all(table_number).await;
+        }
+        match self.cached(table_number, |listing| listing.items.clone()) {
+            Some(items) => Ok(items),
+            None => self.load(table_number).await.map(|(items, _)| items),
+        }
+    }
+
+    async fn find_one(&self, table_number: u32, item_id: u32) -> Result<Item, Error> {
+        self.inner.find_one(table_number, item_id).await
+    }
+
+    async fn find_one_for_write(&self, table_number: u32, item_id: u32) -> Result<Item, Error> {
+        self.inner.find_one_for_write(table_number, item_id).await
+    }
+
+    async fn find_revision(&self, table_number: u32) -> Result<ItemsRevision, Error> {
+        if !self.config.is_enabled() {
+            return self.inner.find_revision(table_number).await;
+        }
+        match self.cached(table_number, |listing| listing.revision) {
+            Some(revision) => Ok(revision),
+            None => self.load(table_number).await.map(|(_, revision)| revision),
+        }
+    }
+
+    async fn create(
+        &self,
+        table_number: u32,
+        item: Item,
+        audit: AuditRecord,
+    ) -> Result<u64, Error> {
+        let result = self.inner.create(table_number, item, audit).await;
+        self.invalidate(&[table_number]);
+        result
+    }
+
+    async fn update(
+        &self,
+        table_number: u32,
+        item: Item,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let result = self.inner.update(table_number, item, now, audit).await;
+        self.invalidate(&[table_number]);
+        result
+    }
+
+    async fn delete(
+        &self,
+        table_number: u32,
+        item: Item,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let result = self.inner.delete(table_number, item, now, audit).await;
+        self.invalidate(&[table_number]);
+        result
+    }
+
+    async fn transfer(
+        &self,
+        transfer: ItemTransfer,
+        seated_table: Option<Table>,
+        cleared_table: Option<Table>,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<u64, Error> {
+        let table_numbers = [transfer.from_table_number, transfer.to_table_number];
+        let result = self
+            .inner
+            .transfer(transfer, seated_table, cleared_table, now, audit)
+            .await;
+        self.invalidate(&table_numbers);
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use api_core::domain::audit::AuditAction;
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+
+    /// Items in memory, counting the listings read from it.
+    #[derive(Default)]
+    struct InMemoryItems {
+        items: Mutex<Vec<Item>>,
+        listings_read: AtomicU64,
+    }
+
+    #[async_trait]
+    impl ItemRepository for InMemoryItems {
+        async fn find_all(&self, table_number: u32) -> Result<Vec<Item>, Error> {
+            self.listings_read.fetch_add(1, Ordering::Relaxed);
+            let items = self.items.lock().unwrap();
+            Ok(items
+                .iter()
+                .filter(|item| item.table_number == Some(table_number))
+                .cloned()
+                .collect())
+        }
+
+        async fn find_one(&self, table_number: u32, item_id: u32) -> Result<Item, Error> {
+            let items = self.items.lock().unwrap();
+            items
+                .iter()
+                .find(|item| item.table_number == Some(table_number) && item.id == Some(item_id))
+                .cloned()
+                .ok_or(Error::ItemNotFoundError)
+        }
+
+        async fn find_one_for_write(&self, table_number: u32, item_id: u32) -> Result<Item, Error> {
+            self.find_one(table_number, item_id).await
+        }
+
+        async fn find_revision(&self, table_number: u32) -> Result<ItemsRevision, Error> {
+            let items = self.items.lock().unwrap();
+            let items: Vec<&Item> = items
+                .iter()
+                .filter(|item| item.table_number == Some(table_number))
+                .collect();
+            Ok(ItemsRevision {
+                item_count: items.len() as u64,
+                version_sum: items.iter().map(|item| u64::from(item.version)).sum(),
+                last_updated_at: None,
+            })
+        }
+
+        async fn create(
+            &self,
+            table_number: u32,
+            mut item: Item,
+            _audit: AuditRecord,
+        ) -> Result<u64, Error> {
+            let mut items = self.items.lock().unwrap();
+            let id = items.len() as u32 + 1;
+            item.id = Some(id);
+            item.table_number = Some(table_number);
+            items.push(item);
+            Ok(u64::from(id))
+        }
+
+        async fn update(
+            &self,
+            table_number: u32,
+            item: Item,
+            _now: NaiveDateTime,
+            _audit: AuditRecord,
+        ) -> Result<(), Error> {
+            let mut items = self.items.lock().unwrap();
+            let stored = items
+                .iter_mut()
+                .find(|stored| stored.table_number == Some(table_number) && stored.id == item.id)
+                .ok_or(Error::ItemNotFoundError)?;
+            *stored = Item {
+                version: item.version + 1,
+                ..item
+            };
+            Ok(())
+        }
+
+        async fn delete(
+            &self,
+            table_number: u32,
+            item: Item,
+            _now: NaiveDateTime,
+            _audit: AuditRecord,
+        ) -> Result<(), Error> {
+            let mut items = self.items.lock().unwrap();
+            items.retain(|stored| {
+                !(stored.table_number == Some(table_number) && stored.id == item.id)
+            });
+            Ok(())
+        }
+
+        async fn transfer(
+            &self,
+            transfer: ItemTransfer,
+            _seated_table: Option<Table>,
+            _cleared_table: Option<Table>,
+            _now: NaiveDateTime,
+            _audit: AuditRecord,
+        ) -> Result<u64, Error> {
+            let mut items = self.items.lock().unwrap();
+            let mut moved = 0;
+            for item in items
+                .iter_mut()
+                .filter(|item| item.table_number == Some(transfer.from_table_number))
+            {
+                item.table_number = Some(transfer.to_table_number);
+                moved += 1;
+            }
+            Ok(moved)
+        }
+    }
+
+    fn cached(config: ItemCacheConfig) -> CachedItemRepository<InMemoryItems> {
+        CachedItemRepository::new(InMemoryItems::default(), config)
+    }
+
+    fn audit(action: AuditAction) -> AuditRecord {
+        AuditRecord::by_system(action, Some(1), None, None, None)
+    }
+
+    fn ramen() -> Item {
+        Item::new(String::from("Ramen"), 1, Local::now().naive_local()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_listings_are_served_from_the_cache() {
+        let repo = cached(ItemCacheConfig::default());
+        // added by another server, so the listing needn't settle
+        repo.inner
+            .create(1, ramen(), audit(AuditAction::ItemAdded))
+            .await
+            .unwrap();
+
+        assert_eq!(repo.find_revision(1).await.unwrap().item_count, 1);
+        assert_eq!(repo.find_all(1).await.unwrap().len(), 1);
+        assert_eq!(repo.find_all(1).await.unwrap().len(), 1);
+
+        assert_eq!(repo.inner.listings_read.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            repo.stats(),
+            ItemCacheStats {
+                hits: 2,
+                misses: 1,
+                evictions: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_changes_drop_the_listing() {
+        let repo = cached(ItemCacheConfig::default());
+        let now = Local::now().naive_local();
+        let id = repo
+            .create(1, ramen(), audit(AuditAction::ItemAdded))
+            .await
+            .unwrap() as u32;
+        assert_eq!(repo.find_all(1).await.unwrap().len(), 1);
+
+        repo.create(1, ramen(), audit(AuditAction::ItemAdded))
+            .await
+            .unwrap();
+        assert_eq!(repo.find_all(1).await.unwrap().len(), 2);
+
+        let item = repo.find_one(1, id).await.unwrap();
+        repo.update(1, item.clone(), now, audit(AuditAction::ItemStatusChanged))
+            .await
+            .unwrap();
+        assert_eq!(repo.find_all(1).await.unwrap()[0].version, 2);
+
+        let item = repo.find_one(1, id).await.unwrap();
+        repo.delete(1, item, now, audit(AuditAction::ItemDeleted))
+            .await
+            .unwrap();
+        assert_eq!(repo.find_all(1).await.unwrap().len(), 1);
+
+        assert_eq!(repo.find_all(2).await.unwrap().len(), 0);
+        repo.transfer(
+            ItemTransfer::merge(1, 2).unwrap(),
+            None,
+            None,
+            now,
+            audit(AuditAction::TablesMerged),
+        )
+        .await
+        .unwrap();
+        assert_eq!(repo.find_all(1).await.unwrap().len(), 0);
+        assert_eq!(repo.find_all(2).await.unwrap().len(), 1);
+
+        assert_eq!(repo.stats().hits, 0);
+    }
+
+    #[tokio::test]
+    async fn test_listings_read_right_after_a_change_are_not_kept() {
+        let repo = cached(ItemCacheConfig {
+            settle: Duration::from_millis(50),
+            ..ItemCacheConfig::default()
+        });
+        repo.create(1, ramen(), audit(AuditAction::ItemAdded))
+            .await
+            .unwrap();
+        repo.find_all(1).await.unwrap();
+        repo.find_all(1).await.unwrap();
+        assert_eq!(repo.inner.listings_read.load(Ordering::Relaxed), 2);
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        repo.find_all(1).await.unwrap();
+        repo.find_all(1).await.unwrap();
+        assert_eq!(repo.inner.listings_read.load(Ordering::Relaxed), 3);
+        assert_eq!(repo.stats().hits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_listings_expire() {
+        let repo = cached(ItemCacheConfig {
+            ttl: Duration::from_millis(20),
+            ..ItemCacheConfig::default()
+        });
+        repo.find_all(1).await.unwrap();
+        repo.find_all(1).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        repo.find_all(1).await.unwrap();
+
+        assert_eq!(repo.inner.listings_read.load(Ordering::Relaxed), 2);
+        assert_eq!(repo.stats().misses, 2);
+    }
+
+    #[tokio::test]
+    async fn test_the_oldest_listing_makes_room() {
+        let repo = cached(ItemCacheConfig {
+            max_tables: 2,
+            ..ItemCacheConfig::default()
+        });
+        for table_number in [1, 2, 3] {
+            repo.find_all(table_number).await.unwrap();
+        }
+        repo.find_all(3).await.unwrap();
+        repo.find_all(1).await.unwrap();
+
+        assert_eq!(
+            repo.stats(),
+            ItemCacheStats {
+                hits: 1,
+                misses: 4,
+                evictions: 2
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_a_disabled_cache_reads_through() {
+        let repo = cached(ItemCacheConfig::disabled());
+        repo.find_all(1).await.unwrap();
+        repo.find_all(1).await.unwrap();
+
+        assert_eq!(repo.inner.listings_read.load(Ordering::Relaxed), 2);
+        assert_eq!(repo.stats(), ItemCacheStats::default());
+    }
+}
@@ -0,0 +1,131 @@
+use crate::model::device::DeviceRow;
+use crate::persistence::mysql::Db;
+use crate::repository::audit_repository_impl::record;
+use api_core::{
+    domain::{audit::AuditRecord, device::Device},
+    error::Error,
+    repository::device_repository::DeviceRepository,
+};
+use async_trait::async_trait;
+use derive_new::new;
+
+#[derive(new, Clone)]
+pub struct DeviceRepositoryImpl {
+    db: Db,
+}
+
+#[async_trait]
+impl DeviceRepository for DeviceRepositoryImpl {
+    async fn find_all(&self) -> Result<Vec<Device>, Error> {
+        let result = sqlx::query_as!(
+            DeviceRow,
+            r#"SELECT id, name, scope, table_number, key_hash, revoked as `revoked: bool`, created_at, updated_at
+          FROM devices
+          ORDER BY id"#
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(DeviceRow::from_rows(rows)),
+            Err(_) => Err(Error::DeviceNotFoundError),
+        }
+    }
+
+    async fn find_one(&self, device_id: u32) -> Result<Device, Error> {
+        let result = sqlx::query_as!(
+            DeviceRow,
+            r#"SELECT id, name, scope, table_number, key_hash, revoked as `revoked: bool`, created_at, updated_at
+          FROM devices
+          WHERE id = ?"#,
+            device_id
+        )
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(DeviceRow::from_row(row)),
+            Err(_) => Err(Error::DeviceNotFoundError),
+        }
+    }
+
+    async fn find_by_key_hash(&self, key_hash: &str) -> Result<Device, Error> {
+        let result = sqlx::query_as!(
+            DeviceRow,
+            r#"SELECT id, name, scope, table_number, key_hash, revoked as `revoked: bool`, created_at, updated_at
+          FROM devices
+          WHERE key_hash = ?"#,
+            key_hash
+        )
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(DeviceRow::from_row(row)),
+            Err(_) => Err(Error::DeviceNotFoundError),
+        }
+    }
+
+    async fn create(&self, mut device: Device, mut audit: AuditRecord) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::DeviceInsertionError)?;
+
+        let done = sqlx::query!(
+            r#"
+    INSERT INTO devices (name, scope, table_number, key_hash, revoked)
+    VALUES ( ?, ?, ?, ?, ? )
+            "#,
+            device.name.as_str(),
+            device.scope.as_str(),
+            device.scope.table_number(),
+            device.key_hash.as_str(),
+            device.revoked
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::DeviceInsertionError)?;
+        let device_id = done.last_insert_id();
+
+        device.id = u32::try_from(device_id).ok();
+        audit.after = serde_json::to_string(&device).ok();
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::DeviceInsertionError)?;
+        Ok(device_id)
+    }
+
+    async fn update(&self, device: Device, audit: AuditRecord) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::DeviceUpdateError)?;
+
+        let done = sqlx::query!(
+            r#"UPDATE devices
+          SET name = ?, scope = ?, table_number = ?, key_hash = ?, revoked = ?, updated_at = CURRENT_TIMESTAMP
+          WHERE id = ?"#,
+            device.name,
+            device.scope.as_str(),
+            device.scope.table_number(),
+            device.key_hash,
+            device.revoked,
+            device.id
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::DeviceUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::DeviceNotFoundError);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::DeviceUpdateError)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use crate::model::idempotency::IdempotencyKeyRow;
+use crate::persistence::mysql::Db;
+use api_core::{
+    domain::idempotency::{IdempotencyRecord, StoredResponse},
+    error::Error,
+    repository::idempotency_repository::IdempotencyRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+
+#[derive(new, Clone)]
+pub struct IdempotencyRepositoryImpl {
+    db: Db,
+}
+
+#[async_trait]
+impl IdempotencyRepository for IdempotencyRepositoryImpl {
+    async fn find_one(&self, key: &str) -> Result<Option<IdempotencyRecord>, Error> {
+        let result = sqlx::query_as!(
+            IdempotencyKeyRow,
+            r#"SELECT idempotency_key, fingerprint, response_status as `response_status: u16`, response_body, expires_at, locked_until, created_at
+          FROM idempotency_keys
+          WHERE idempotency_key = ?"#,
+            key
+        )
+        .fetch_optional(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(row.map(IdempotencyKeyRow::from_row)),
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+
+    async fn create(&self, record: IdempotencyRecord) -> Result<(), Error> {
+        let result = sqlx::query!(
+            r#"
+    INSERT INTO idempotency_keys (idempotency_key, fingerprint, expires_at, locked_until)
+    VALUES ( ?, ?, ?, ? )
+            "#,
+            record.key,
+            record.fingerprint,
+            record.expires_at,
+            record.locked_until
+        )
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            // the primary key is taken: another request with this key won the race
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("23000") => {
+                Err(Error::IdempotentRequestInProgress)
+            }
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+
+    async fn reclaim(
+        &self,
+        key: &str,
+        now: NaiveDateTime,
+        locked_until: NaiveDateTime,
+    ) -> Result<(), Error> {
+        let result = sqlx::query!(
+            r#"UPDATE idempotency_keys
+          SET locked_until = ?
+          WHERE idempotency_key = ? AND response_status IS NULL AND locked_until <= ?"#,
+            locked_until,
+            key,
+            now
+        )
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(done) if done.rows_affected() > 0 => Ok(()),
+            Ok(_) => Err(Error::IdempotentRequestInProgress),
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+
+    async fn complete(&self, key: &str, response: StoredResponse) -> Result<(), Error> {
+        let result = sqlx::query!(
+            r#"UPDATE idempotency_keys
+          SET response_status = ?, response_body = ?
+          WHERE idempotency_key = ?"#,
+            response.status,
+            response.body,
+            key
+        )
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        let result = sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE idempotency_key = ?",
+            key
+        )
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+
+    async fn delete_expired(&self, now: NaiveDateTime) -> Result<u64, Error> {
+        let result = sqlx::query!("DELETE FROM idempotency_keys WHERE expires_at <= ?", now)
+            .execute(&self.db.pool)
+            .await;
+
+        match result {
+            Ok(done) => Ok(done.rows_affected()),
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+}
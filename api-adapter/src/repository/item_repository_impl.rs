@@ -1,207 +1,485 @@
-use crate::model::item::ItemRow;
-use crate::persistence::mysql::Db;
-use api_core::{domain::item::Item, error::Error, repository::item_repository::ItemRepository};
+use crate::model::item::{ItemRow, ItemsRevisionRow};
+use crate::persistence::{config::ItemReadRouting, mysql::Db};
+use crate::repository::audit_repository_impl::record;
+use crate::repository::outbox_repository_impl::enqueue;
+use crate::repository::table_repository_impl::{change_status, seat_free_table};
+use api_core::{
+    domain::{
+        audit::AuditRecord,
+        event::DomainEvent,
+        item::{Item, ItemsRevision},
+        item_transfer::ItemTransfer,
+        table::{Table, TableStatus},
+    },
+    error::Error,
+    repository::item_repository::ItemRepository,
+};
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 use derive_new::new;
+use sqlx::{mysql::MySqlDatabaseError, MySql, Pool};
 
+/// The items on MySQL. With a replica configured the reads are routed as
+/// `reads` says.
 #[derive(new, Clone)]
 pub struct ItemRepositoryImpl {
     db: Db,
+    #[new(default)]
+    reads: ItemReadRouting,
+}
+
+impl ItemRepositoryImpl {
+    pub fn with_reads(db: Db, reads: ItemReadRouting) -> Self {
+        Self { db, reads }
+    }
 }
 
 #[async_trait]
 impl ItemRepository for ItemRepositoryImpl {
     async fn find_all(&self, table_number: u32) -> Result<Vec<Item>, Error> {
         let result = sqlx::query_as!(
-          ItemRow,
-          r#"SELECT id, name, quantity, table_number, start_cooking_at, finish_cooking_at, created_at, updated_at
+            ItemRow,
+            r#"SELECT id, name, quantity, table_number, start_cooking_at, finish_cooking_at, status,
+          actual_start_cooking_at, actual_finish_cooking_at, version, created_at, updated_at
           FROM items
           WHERE table_number = ?
           "#,
-          table_number
-        ).fetch_all(&self.db.pool)
+            table_number
+        )
+        .fetch_all(self.db.reader(self.reads.find_all))
         .await;
 
-        if result.is_err() {
-            Err(Error::ItemNotFoundError)
-        } else {
-            Ok(ItemRow::from_rows(result.unwrap()))
+        match result {
+            Ok(rows) => ItemRow::from_rows(rows).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::ItemNotFoundError),
         }
     }
     async fn find_one(&self, table_number: u32, item_id: u32) -> Result<Item, Error> {
+        self.find_one_in(self.db.reader(self.reads.find_one), table_number, item_id)
+            .await
+    }
+    async fn find_one_for_write(&self, table_number: u32, item_id: u32) -> Result<Item, Error> {
+        self.find_one_in(&self.db.pool, table_number, item_id).await
+    }
+    async fn find_revision(&self, table_number: u32) -> Result<ItemsRevision, Error> {
         let result = sqlx::query_as!(
-        ItemRow,
-        r#"SELECT id, name, quantity, table_number, start_cooking_at, finish_cooking_at, created_at, updated_at
+            ItemsRevisionRow,
+            r#"SELECT COUNT(*) as `item_count: i64`,
+          CAST(COALESCE(SUM(version), 0) AS UNSIGNED) as `version_sum: u64`,
+          CONVERT_TZ(MAX(updated_at), @@session.time_zone, '+00:00') as `last_updated_at?`
           FROM items
-          WHERE id = ? AND table_number = ?"#,
-        item_id,
-        table_number
-      ).fetch_one(&self.db.pool)
-      .await;
+          WHERE table_number = ?"#,
+            table_number
+        )
+        .fetch_one(self.db.reader(self.reads.find_revision))
+        .await;
 
-        if result.is_err() {
-            Err(Error::ItemNotFoundError)
-        } else {
-            Ok(ItemRow::from_row(result.unwrap()))
+        match result {
+            Ok(row) => Ok(ItemsRevisionRow::from_row(row)),
+            Err(_) => Err(Error::ItemNotFoundError),
         }
     }
-    async fn create(&self, table_number: u32, item: Item) -> Result<u64, Error> {
-        let res = sqlx::query!(
+    async fn create(
+        &self,
+        table_number: u32,
+        mut item: Item,
+        mut audit: AuditRecord,
+    ) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ItemInsertionError)?;
+
+        let id = sqlx::query!(
             r#"
     INSERT INTO items (name, quantity, table_number, start_cooking_at, finish_cooking_at)
     VALUES ( ?, ?, ?, ?, ? )
             "#,
-            item.name,
+            item.name.as_str(),
             item.quantity,
             table_number,
             item.start_cooking_at,
             item.finish_cooking_at
         )
-        .execute(&self.db.pool)
-        .await;
+        .execute(&mut tx)
+        .await
+        .map_err(|e| unknown_table_or(e, Error::ItemInsertionError))?
+        .last_insert_id();
 
-        if res.is_err() {
-            Err(Error::ItemInsertionError)
-        } else {
-            Ok(res.unwrap().last_insert_id())
+        item.id = u32::try_from(id).ok();
+        item.table_number = Some(table_number);
+        audit.item_id = item.id;
+        audit.after = serde_json::to_string(&item).ok();
+        record(&mut tx, &audit).await?;
+        let occurred_at = item.start_cooking_at;
+        let event = DomainEvent::ItemAdded {
+            table_number,
+            item,
+            occurred_at,
+        };
+        enqueue(&mut tx, &event).await?;
+
+        tx.commit().await.map_err(|_| Error::ItemInsertionError)?;
+        Ok(id)
+    }
+
+    async fn update(
+        &self,
+        table_number: u32,
+        mut item: Item,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ItemUpdateError)?;
+
+        let done = sqlx::query!(
+            r#"UPDATE items
+          SET name = ?, quantity = ?, status = ?, actual_start_cooking_at = ?, actual_finish_cooking_at = ?,
+              version = version + 1, updated_at = CURRENT_TIMESTAMP
+          WHERE table_number = ? AND id = ? AND version = ?"#,
+            item.name.as_str(),
+            item.quantity,
+            item.status.as_str(),
+            item.actual_start_cooking_at,
+            item.actual_finish_cooking_at,
+            table_number,
+            item.id,
+            item.version
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::ItemUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(self.stale_or_missing(table_number, item.id).await);
         }
+
+        item.version += 1;
+        let event = DomainEvent::item_status_changed(table_number, item, now);
+        enqueue(&mut tx, &event).await?;
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::ItemUpdateError)?;
+        Ok(())
     }
 
-    async fn delete(&self, table_number: u32, item_id: u32) -> Result<(), Error> {
-        let result = sqlx::query!(
+    async fn delete(
+        &self,
+        table_number: u32,
+        item: Item,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ItemDeletionError)?;
+
+        let done = sqlx::query!(
             r#"DELETE FROM items
-          WHERE table_number = ? AND id = ?"#,
+          WHERE table_number = ? AND id = ? AND version = ?"#,
             table_number,
-            item_id
+            item.id,
+            item.version
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::ItemDeletionError)?;
+        if done.rows_affected() == 0 {
+            return Err(self.stale_or_missing(table_number, item.id).await);
+        }
+
+        let event = DomainEvent::ItemCancelled {
+            table_number,
+            item,
+            occurred_at: now,
+        };
+        enqueue(&mut tx, &event).await?;
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::ItemDeletionError)?;
+        Ok(())
+    }
+
+    async fn transfer(
+        &self,
+        transfer: ItemTransfer,
+        seated_table: Option<Table>,
+        cleared_table: Option<Table>,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ItemTransferError)?;
+
+        let mut moved_items = 0;
+        match &transfer.item_ids {
+            None => {
+                moved_items = sqlx::query!(
+                    r#"UPDATE items
+          SET table_number = ?, version = version + 1, updated_at = CURRENT_TIMESTAMP
+          WHERE table_number = ?"#,
+                    transfer.to_table_number,
+                    transfer.from_table_number
+                )
+                .execute(&mut tx)
+                .await
+                .map_err(|e| unknown_table_or(e, Error::ItemTransferError))?
+                .rows_affected();
+            }
+            Some(item_ids) => {
+                for item_id in item_ids {
+                    let done = sqlx::query!(
+                        r#"UPDATE items
+          SET table_number = ?, version = version + 1, updated_at = CURRENT_TIMESTAMP
+          WHERE table_number = ? AND id = ?"#,
+                        transfer.to_table_number,
+                        transfer.from_table_number,
+                        item_id
+                    )
+                    .execute(&mut tx)
+                    .await
+                    .map_err(|e| unknown_table_or(e, Error::ItemTransferError))?;
+                    // every selected item has to belong to the source table,
+                    // dropping the transaction rolls back what was moved so far
+                    if done.rows_affected() == 0 {
+                        return Err(Error::ItemNotFoundError);
+                    }
+                    moved_items += done.rows_affected();
+                }
+            }
+        }
+
+        let item_ids = transfer.item_ids.as_ref().map(|ids| {
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        });
+        sqlx::query!(
+            r#"
+    INSERT INTO item_transfers (kind, from_table_number, to_table_number, item_ids, moved_items)
+    VALUES ( ?, ?, ?, ?, ? )
+            "#,
+            transfer.kind.as_str(),
+            transfer.from_table_number,
+            transfer.to_table_number,
+            item_ids,
+            moved_items
         )
-        .execute(&self.db.pool)
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::ItemTransferError)?;
+
+        let event = DomainEvent::ItemsMoved {
+            transfer,
+            moved_items,
+            occurred_at: now,
+        };
+        enqueue(&mut tx, &event).await?;
+        record(&mut tx, &audit).await?;
+        if let Some(table) = seated_table {
+            seat_free_table(&mut tx, &table, now).await?;
+        }
+        if let Some(table) = cleared_table {
+            change_status(&mut tx, &table, TableStatus::Seated, now).await?;
+        }
+
+        tx.commit().await.map_err(|_| Error::ItemTransferError)?;
+        Ok(moved_items)
+    }
+}
+
+impl ItemRepositoryImpl {
+    async fn find_one_in(
+        &self,
+        pool: &Pool<MySql>,
+        table_number: u32,
+        item_id: u32,
+    ) -> Result<Item, Error> {
+        let result = sqlx::query_as!(
+            ItemRow,
+            r#"SELECT id, name, quantity, table_number, start_cooking_at, finish_cooking_at, status,
+          actual_start_cooking_at, actual_finish_cooking_at, version, created_at, updated_at
+          FROM items
+          WHERE id = ? AND table_number = ?"#,
+            item_id,
+            table_number
+        )
+        .fetch_one(pool)
         .await;
 
-        if result.is_err() {
-            Err(Error::ItemDeletionError)
-        } else {
-            Ok(())
+        match result {
+            Ok(row) => ItemRow::from_row(row).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::ItemNotFoundError),
+        }
+    }
+
+    /// Tells why a guarded write touched no row: the item is gone or it has
+    /// moved on to another version.
+    async fn stale_or_missing(&self, table_number: u32, item_id: Option<u32>) -> Error {
+        match item_id {
+            Some(item_id) if self.find_one_for_write(table_number, item_id).await.is_ok() => {
+                Error::ItemVersionMismatch
+            }
+            _ => Error::ItemNotFoundError,
         }
     }
 }
 
+/// Items must belong to a table that exists; the foreign key rejects the rest
+/// with MySQL's `ER_NO_REFERENCED_ROW_2`.
+fn unknown_table_or(e: sqlx::Error, otherwise: Error) -> Error {
+    match e {
+        sqlx::Error::Database(e)
+            if e.try_downcast_ref::<MySqlDatabaseError>()
+                .map(|e| e.number())
+                == Some(1452) =>
+        {
+            Error::UnknownTable
+        }
+        _ => otherwise,
+    }
+}
+
 #[cfg(test)]
 mod test {
-    impl ItemRepositoryImpl {
-        async fn setup(&self) {
-            sqlx::query!("INSERT INTO tables VALUES (), (), (), (), ()")
-                .execute(&self.db.pool)
+    use super::*;
+    use crate::persistence::{
+        config::{DbConfig, ReadFrom},
+        mysql::Env,
+    };
+    use crate::repository::audit_repository_impl::AuditRepositoryImpl;
+    use crate::repository::item_repository_suite::{item_repository_suite, ItemRepositoryFixture};
+    use crate::repository::outbox_repository_impl::OutboxRepositoryImpl;
+    use crate::repository::table_repository_impl::TableRepositoryImpl;
+    use api_core::domain::audit::AuditAction;
+    use chrono::Local;
+    use std::time::Duration;
+
+    fn audit(action: AuditAction) -> AuditRecord {
+        AuditRecord::by_system(action, None, None, None, None)
+    }
+
+    pub(crate) struct Fixture {
+        items: ItemRepositoryImpl,
+        outbox: OutboxRepositoryImpl,
+        tables: TableRepositoryImpl,
+        audits: AuditRepositoryImpl,
+        db: Db,
+    }
+
+    #[async_trait]
+    impl ItemRepositoryFixture for Fixture {
+        type Items = ItemRepositoryImpl;
+        type Outbox = OutboxRepositoryImpl;
+        type Tables = TableRepositoryImpl;
+        type Audits = AuditRepositoryImpl;
+
+        async fn set_up() -> Self {
+            let db = Db::new(Env::Test).await.unwrap();
+            sqlx::query!("INSERT IGNORE INTO tables (number) VALUES (1), (2), (3), (4), (5)")
+                .execute(&db.pool)
                 .await
                 .unwrap();
+            let fixture = Fixture {
+                items: ItemRepositoryImpl::new(db.clone()),
+                outbox: OutboxRepositoryImpl::new(db.clone()),
+                tables: TableRepositoryImpl::new(db.clone()),
+                audits: AuditRepositoryImpl::new(db.clone()),
+                db,
+            };
+            fixture.tear_down().await;
+            fixture
         }
 
         async fn tear_down(&self) {
-            sqlx::query!("DELETE FROM tables")
+            sqlx::query!("DELETE FROM items")
                 .execute(&self.db.pool)
                 .await
                 .unwrap();
-            sqlx::query!("DELETE FROM items")
+            sqlx::query!("DELETE FROM item_transfers")
                 .execute(&self.db.pool)
                 .await
                 .unwrap();
-        }
-    }
-
-    use super::*;
-    use crate::persistence::mysql::Env;
-    use chrono::Local;
-    use pretty_assertions::assert_eq;
-    use serial_test::serial;
-
-    #[tokio::test]
-    #[serial]
-    async fn test_insert_and_query_item() {
-        let db = Db::new(Env::Test).await;
-        let repo = ItemRepositoryImpl::new(db);
-        repo.setup().await;
-        repo.tear_down().await;
-
-        let item = Item::new(String::from("Yakisoba"), 10, Local::now().naive_local()).unwrap();
-        let table_number = 1;
-        let db_item_id = repo.create(table_number, item.clone()).await.unwrap();
-        let item_id = u32::try_from(db_item_id).unwrap();
-
-        let acutual_data = repo
-            .find_one(table_number, u32::try_from(item_id).unwrap())
-            .await
-            .unwrap();
-
-        assert_eq!(acutual_data.id.unwrap(), item_id);
-        assert_eq!(acutual_data.name, item.name);
-        assert_eq!(acutual_data.quantity, item.quantity);
-        assert_eq!(acutual_data.table_number.unwrap(), table_number);
-
-        repo.tear_down().await;
-    }
-
-    #[tokio::test]
-    #[serial]
-    async fn test_insert_and_query_multiple_items() {
-        let db = Db::new(Env::Test).await;
-        let repo = ItemRepositoryImpl::new(db);
-        repo.setup().await;
-        repo.tear_down().await;
-
-        let items = vec![
-            Item::new(String::from("Yakisoba"), 10, Local::now().naive_local()).unwrap(),
-            Item::new(String::from("Takoyaki"), 20, Local::now().naive_local()).unwrap(),
-        ];
-        let table_number = 1;
-
-        for item in items {
-            repo.create(table_number, item)
+            sqlx::query!("DELETE FROM outbox_events")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("DELETE FROM audit_records")
+                .execute(&self.db.pool)
                 .await
-                .expect("db error occured");
+                .unwrap();
+            sqlx::query!("UPDATE tables SET status = 'free', seated_at = NULL WHERE number <= 5")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
         }
 
-        let expected_items = vec![("Yakisoba".to_string(), 10), ("Takoyaki".to_string(), 20)];
-        let acutual_data = repo.find_all(table_number).await.unwrap();
+        fn items(&self) -> &Self::Items {
+            &self.items
+        }
 
-        assert_eq!(acutual_data.len(), 2);
+        fn outbox(&self) -> &Self::Outbox {
+            &self.outbox
+        }
 
-        for (e, a) in expected_items.iter().zip(acutual_data.iter()) {
-            assert_eq!(e.0, a.name);
-            assert_eq!(e.1, a.quantity);
+        fn tables(&self) -> &Self::Tables {
+            &self.tables
         }
 
-        repo.tear_down().await;
+        fn audits(&self) -> &Self::Audits {
+            &self.audits
+        }
     }
 
-    #[tokio::test]
-    #[serial]
-    async fn test_insert_and_delete() {
-        let db = Db::new(Env::Test).await;
-        let repo = ItemRepositoryImpl::new(db);
-        repo.setup().await;
-        repo.tear_down().await;
-
-        let items = vec![
-            Item::new(String::from("Yakisoba"), 10, Local::now().naive_local()).unwrap(),
-            Item::new(String::from("Takoyaki"), 20, Local::now().naive_local()).unwrap(),
-        ];
-        let table_number = 1;
-
-        let mut item_id = 0;
-        for item in items {
-            item_id = repo
-                .create(table_number, item)
-                .await
-                .expect("db error occured");
-        }
+    item_repository_suite!(Fixture);
 
-        let result = repo
-            .delete(table_number, u32::try_from(item_id).unwrap())
-            .await;
-        assert_eq!(result.is_ok(), true);
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn test_reads_are_routed_as_configured() {
+        let fixture = Fixture::set_up().await;
+        // a replica nothing listens on, so every read routed to it fails
+        let replica = DbConfig::new(String::new())
+            .pool_options()
+            .connect_timeout(Duration::from_millis(100))
+            .connect_lazy("mysql://root@127.0.0.1:1/restaurant")
+            .unwrap();
+        let db = Db {
+            replica: Some(replica),
+            ..fixture.db.clone()
+        };
+        let items = ItemRepositoryImpl::with_reads(
+            db,
+            ItemReadRouting {
+                find_all: ReadFrom::Primary,
+                find_one: ReadFrom::Replica,
+                find_revision: ReadFrom::Primary,
+            },
+        );
+        let item = Item::new(String::from("Yakisoba"), 1, Local::now().naive_local()).unwrap();
+        let id = items
+            .create(1, item, audit(AuditAction::ItemAdded))
+            .await
+            .unwrap() as u32;
 
-        let query_res = repo.find_all(table_number).await.unwrap();
-        assert_eq!(query_res.len(), 1);
+        assert_eq!(items.find_all(1).await.unwrap().len(), 1);
+        assert_eq!(items.find_revision(1).await.unwrap().item_count, 1);
+        assert!(items.find_one(1, id).await.is_err());
+        assert_eq!(items.find_one_for_write(1, id).await.unwrap().id, Some(id));
 
-        repo.tear_down().await;
+        fixture.tear_down().await;
     }
 }
@@ -0,0 +1,511 @@
+//! The integration tests every `ItemRepository` implementation has to pass.
+//! A backend implements `ItemRepositoryFixture` against its test database and
+//! declares the tests with `item_repository_suite!`.
+
+use api_core::{
+    domain::{
+        audit::{AuditAction, AuditFilter, AuditRecord},
+        item::{Item, ItemStatus},
+        item_transfer::ItemTransfer,
+        table::TableStatus,
+    },
+    error::Error,
+    repository::{
+        audit_repository::AuditRepository, item_repository::ItemRepository,
+        outbox_repository::OutboxRepository, table_repository::TableRepository,
+    },
+};
+use async_trait::async_trait;
+use chrono::{Duration, Local, Utc};
+use pretty_assertions::assert_eq;
+
+#[async_trait]
+pub(crate) trait ItemRepositoryFixture: Sized {
+    type Items: ItemRepository + Sync;
+    type Outbox: OutboxRepository + Sync;
+    type Tables: TableRepository + Sync;
+    type Audits: AuditRepository + Sync;
+
+    /// Connects to the test database, with tables 1 to 5 present and free and
+    /// no items, transfers, outbox events or audit records left over.
+    async fn set_up() -> Self;
+    async fn tear_down(&self);
+    fn items(&self) -> &Self::Items;
+    fn outbox(&self) -> &Self::Outbox;
+    fn tables(&self) -> &Self::Tables;
+    fn audits(&self) -> &Self::Audits;
+}
+
+/// The audit record the use cases would file for a change to the table.
+fn audit(action: AuditAction, table_number: u32) -> AuditRecord {
+    AuditRecord::by_system(action, Some(table_number), None, None, None)
+}
+
+/// Declares a `#[tokio::test]` for every test of the suite, run against the
+/// given `ItemRepositoryFixture`.
+macro_rules! item_repository_suite {
+    ($fixture:ty) => {
+        $crate::repository::item_repository_suite::item_repository_suite!(
+            $fixture,
+            test_insert_and_query_item,
+            test_insert_and_query_multiple_items,
+            test_update_item_status,
+            test_revision_changes_with_items,
+            test_transfer_items,
+            test_transfer_saves_the_tables_it_changes,
+            test_items_need_an_existing_table,
+            test_insert_and_delete,
+            test_changes_leave_events_in_the_outbox,
+            test_changes_leave_audit_records
+        );
+    };
+    ($fixture:ty, $($test:ident),+) => {
+        $(
+            #[tokio::test]
+            #[serial_test::serial]
+            async fn $test() {
+                $crate::repository::item_repository_suite::$test::<$fixture>().await;
+            }
+        )+
+    };
+}
+pub(crate) use item_repository_suite;
+
+pub(crate) async fn test_insert_and_query_item<F: ItemRepositoryFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.items();
+
+    let item = Item::new(String::from("Yakisoba"), 10, Local::now().naive_local()).unwrap();
+    let table_number = 1;
+    let db_item_id = repo
+        .create(
+            table_number,
+            item.clone(),
+            audit(AuditAction::ItemAdded, table_number),
+        )
+        .await
+        .unwrap();
+    let item_id = u32::try_from(db_item_id).unwrap();
+
+    let acutual_data = repo.find_one(table_number, item_id).await.unwrap();
+
+    assert_eq!(acutual_data.id.unwrap(), item_id);
+    assert_eq!(acutual_data.name, item.name);
+    assert_eq!(acutual_data.quantity, item.quantity);
+    assert_eq!(acutual_data.table_number.unwrap(), table_number);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_insert_and_query_multiple_items<F: ItemRepositoryFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.items();
+
+    let items = vec![
+        Item::new(String::from("Yakisoba"), 10, Local::now().naive_local()).unwrap(),
+        Item::new(String::from("Takoyaki"), 20, Local::now().naive_local()).unwrap(),
+    ];
+    let table_number = 1;
+
+    for item in items {
+        repo.create(
+            table_number,
+            item,
+            audit(AuditAction::ItemAdded, table_number),
+        )
+        .await
+        .expect("db error occured");
+    }
+
+    let expected_items = [("Yakisoba".to_string(), 10), ("Takoyaki".to_string(), 20)];
+    let acutual_data = repo.find_all(table_number).await.unwrap();
+
+    assert_eq!(acutual_data.len(), 2);
+
+    for (e, a) in expected_items.iter().zip(acutual_data.iter()) {
+        assert_eq!(e.0, a.name);
+        assert_eq!(e.1, a.quantity);
+    }
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_update_item_status<F: ItemRepositoryFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.items();
+
+    let now = Local::now().naive_local();
+    let item = Item::new(String::from("Yakisoba"), 10, now).unwrap();
+    let table_number = 1;
+    let item_id = u32::try_from(
+        repo.create(
+            table_number,
+            item,
+            audit(AuditAction::ItemAdded, table_number),
+        )
+        .await
+        .unwrap(),
+    )
+    .unwrap();
+
+    let mut item = repo.find_one(table_number, item_id).await.unwrap();
+    assert_eq!(item.status, ItemStatus::Ordered);
+    item.transition_to(ItemStatus::Cooking, now).unwrap();
+    repo.update(
+        table_number,
+        item.clone(),
+        now,
+        audit(AuditAction::ItemStatusChanged, table_number),
+    )
+    .await
+    .unwrap();
+
+    let actual_data = repo.find_one(table_number, item_id).await.unwrap();
+    assert_eq!(actual_data.status, ItemStatus::Cooking);
+    assert_eq!(actual_data.actual_start_cooking_at.is_some(), true);
+    assert_eq!(actual_data.actual_finish_cooking_at, None);
+    assert_eq!(actual_data.version, item.version + 1);
+
+    // the copy read before the update is stale now
+    item.transition_to(ItemStatus::Ready, now).unwrap();
+    assert_eq!(
+        repo.update(
+            table_number,
+            item.clone(),
+            now,
+            audit(AuditAction::ItemStatusChanged, table_number)
+        )
+        .await
+        .is_err(),
+        true
+    );
+    assert_eq!(
+        repo.delete(
+            table_number,
+            item,
+            now,
+            audit(AuditAction::ItemDeleted, table_number)
+        )
+        .await
+        .is_err(),
+        true
+    );
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_revision_changes_with_items<F: ItemRepositoryFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.items();
+
+    let empty = repo.find_revision(1).await.unwrap();
+    assert_eq!(empty.item_count, 0);
+    assert_eq!(empty.last_updated_at, None);
+
+    let now = Local::now().naive_local();
+    let item = Item::new(String::from("Yakisoba"), 1, now).unwrap();
+    let item_id = u32::try_from(
+        repo.create(1, item, audit(AuditAction::ItemAdded, 1))
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    let added = repo.find_revision(1).await.unwrap();
+    assert_eq!(added.item_count, 1);
+    let age = Utc::now().naive_utc() - added.last_updated_at.unwrap();
+    assert_eq!(age.num_seconds().abs() < 60, true);
+
+    let mut item = repo.find_one(1, item_id).await.unwrap();
+    item.transition_to(ItemStatus::Cooking, now).unwrap();
+    repo.update(1, item, now, audit(AuditAction::ItemStatusChanged, 1))
+        .await
+        .unwrap();
+    assert_eq!(repo.find_revision(1).await.unwrap() == added, false);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_transfer_items<F: ItemRepositoryFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.items();
+
+    let now = Local::now().naive_local();
+    let mut item_ids = Vec::new();
+    for name in ["Yakisoba", "Takoyaki", "Ramen"] {
+        let item = Item::new(String::from(name), 1, now).unwrap();
+        let item_id = repo
+            .create(1, item, audit(AuditAction::ItemAdded, 1))
+            .await
+            .unwrap();
+        item_ids.push(u32::try_from(item_id).unwrap());
+    }
+
+    let transfer = ItemTransfer::move_items(1, 2, Some(vec![item_ids[0]])).unwrap();
+    assert_eq!(
+        repo.transfer(transfer, None, None, now, audit(AuditAction::ItemsMoved, 1))
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(repo.find_all(1).await.unwrap().len(), 2);
+    assert_eq!(repo.find_all(2).await.unwrap().len(), 1);
+
+    // an item of another table rolls the whole transfer back
+    let transfer = ItemTransfer::move_items(1, 2, Some(vec![item_ids[1], item_ids[0]])).unwrap();
+    assert_eq!(
+        repo.transfer(transfer, None, None, now, audit(AuditAction::ItemsMoved, 1))
+            .await
+            .is_err(),
+        true
+    );
+    assert_eq!(repo.find_all(1).await.unwrap().len(), 2);
+
+    let transfer = ItemTransfer::merge(1, 2).unwrap();
+    assert_eq!(
+        repo.transfer(transfer, None, None, now, audit(AuditAction::ItemsMoved, 1))
+            .await
+            .unwrap(),
+        2
+    );
+    assert_eq!(repo.find_all(1).await.unwrap().len(), 0);
+    assert_eq!(repo.find_all(2).await.unwrap().len(), 3);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_transfer_saves_the_tables_it_changes<F: ItemRepositoryFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.items();
+    let tables = fixture.tables();
+
+    let now = Local::now().naive_local();
+    let mut from_table = tables.find_one(1).await.unwrap();
+    from_table.seat(now).unwrap();
+    tables
+        .seat(
+            from_table.clone(),
+            now,
+            audit(AuditAction::TableSeated, from_table.number),
+        )
+        .await
+        .unwrap();
+    let item = Item::new(String::from("Yakisoba"), 1, now).unwrap();
+    repo.create(1, item, audit(AuditAction::ItemAdded, 1))
+        .await
+        .unwrap();
+
+    let mut to_table = tables.find_one(2).await.unwrap();
+    to_table.seat(now).unwrap();
+    from_table
+        .transition_to(TableStatus::NeedsCleaning, now)
+        .unwrap();
+    let transfer = ItemTransfer::move_items(1, 2, None).unwrap();
+    let moved_items = repo
+        .transfer(
+            transfer,
+            Some(to_table.clone()),
+            Some(from_table),
+            now,
+            audit(AuditAction::ItemsMoved, 1),
+        )
+        .await
+        .unwrap();
+    assert_eq!(moved_items, 1);
+    assert_eq!(
+        tables.find_one(1).await.unwrap().status,
+        TableStatus::NeedsCleaning
+    );
+    assert_eq!(
+        tables.find_one(2).await.unwrap().status,
+        TableStatus::Seated
+    );
+
+    // a destination seated in the meantime rolls the whole transfer back
+    let transfer = ItemTransfer::move_items(2, 3, None).unwrap();
+    let mut taken_table = tables.find_one(3).await.unwrap();
+    taken_table.seat(now).unwrap();
+    tables
+        .seat(
+            taken_table.clone(),
+            now,
+            audit(AuditAction::TableSeated, taken_table.number),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(
+        repo.transfer(
+            transfer,
+            Some(taken_table),
+            None,
+            now,
+            audit(AuditAction::ItemsMoved, 1)
+        )
+        .await,
+        Err(Error::TableNotAvailable)
+    ));
+    assert_eq!(repo.find_all(2).await.unwrap().len(), 1);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_items_need_an_existing_table<F: ItemRepositoryFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.items();
+
+    let now = Local::now().naive_local();
+    let item = Item::new(String::from("Yakisoba"), 1, now).unwrap();
+    assert!(matches!(
+        repo.create(999, item.clone(), audit(AuditAction::ItemAdded, 999))
+            .await,
+        Err(Error::UnknownTable)
+    ));
+
+    repo.create(1, item, audit(AuditAction::ItemAdded, 1))
+        .await
+        .unwrap();
+    let transfer = ItemTransfer::merge(1, 999).unwrap();
+    assert!(matches!(
+        repo.transfer(transfer, None, None, now, audit(AuditAction::ItemsMoved, 1))
+            .await,
+        Err(Error::UnknownTable)
+    ));
+    assert_eq!(repo.find_all(1).await.unwrap().len(), 1);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_insert_and_delete<F: ItemRepositoryFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.items();
+
+    let items = vec![
+        Item::new(String::from("Yakisoba"), 10, Local::now().naive_local()).unwrap(),
+        Item::new(String::from("Takoyaki"), 20, Local::now().naive_local()).unwrap(),
+    ];
+    let table_number = 1;
+
+    let mut item_id = 0;
+    for item in items {
+        item_id = repo
+            .create(
+                table_number,
+                item,
+                audit(AuditAction::ItemAdded, table_number),
+            )
+            .await
+            .expect("db error occured");
+    }
+
+    let item = repo
+        .find_one(table_number, u32::try_from(item_id).unwrap())
+        .await
+        .unwrap();
+    let result = repo
+        .delete(
+            table_number,
+            item,
+            Local::now().naive_local(),
+            audit(AuditAction::ItemDeleted, table_number),
+        )
+        .await;
+    assert_eq!(result.is_ok(), true);
+
+    let query_res = repo.find_all(table_number).await.unwrap();
+    assert_eq!(query_res.len(), 1);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_changes_leave_events_in_the_outbox<F: ItemRepositoryFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.items();
+    let outbox = fixture.outbox();
+
+    let now = Local::now().naive_local();
+    let later = now + Duration::minutes(1);
+    let item = Item::new(String::from("Yakisoba"), 1, now).unwrap();
+    let item_id = u32::try_from(
+        repo.create(1, item, audit(AuditAction::ItemAdded, 1))
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+
+    let mut item = repo.find_one(1, item_id).await.unwrap();
+    item.transition_to(ItemStatus::Cooking, now).unwrap();
+    repo.update(
+        1,
+        item.clone(),
+        now,
+        audit(AuditAction::ItemStatusChanged, 1),
+    )
+    .await
+    .unwrap();
+    // a refused change leaves no event behind
+    assert_eq!(
+        repo.update(1, item, now, audit(AuditAction::ItemStatusChanged, 1))
+            .await
+            .is_err(),
+        true
+    );
+
+    let names = outbox
+        .find_pending(later, 10)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.event.unwrap().name())
+        .collect::<Vec<&str>>();
+    assert_eq!(names, vec!["item_added", "item_status_changed"]);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_changes_leave_audit_records<F: ItemRepositoryFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.items();
+    let audits = fixture.audits();
+
+    let now = Local::now().naive_local();
+    let item = Item::new(String::from("Yakisoba"), 1, now).unwrap();
+    let item_id = u32::try_from(
+        repo.create(1, item, audit(AuditAction::ItemAdded, 1))
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+
+    let mut item = repo.find_one(1, item_id).await.unwrap();
+    item.transition_to(ItemStatus::Cooking, now).unwrap();
+    repo.update(
+        1,
+        item.clone(),
+        now,
+        audit(AuditAction::ItemStatusChanged, 1),
+    )
+    .await
+    .unwrap();
+    // a refused change leaves no record behind
+    assert_eq!(
+        repo.update(1, item, now, audit(AuditAction::ItemStatusChanged, 1))
+            .await
+            .is_err(),
+        true
+    );
+
+    let filter = AuditFilter::new(Some(1), None, None, None, None).unwrap();
+    let records = audits.find(filter).await.unwrap();
+    let actions = records
+        .iter()
+        .map(|record| record.action)
+        .collect::<Vec<AuditAction>>();
+    assert_eq!(
+        actions,
+        vec![AuditAction::ItemStatusChanged, AuditAction::ItemAdded]
+    );
+    // the record of the new item names it and holds what was added
+    assert_eq!(records[1].item_id, Some(item_id));
+    assert_eq!(records[1].after.is_some(), true);
+
+    fixture.tear_down().await;
+}
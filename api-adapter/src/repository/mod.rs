@@ -1 +1,29 @@
+#[cfg(feature = "mysql")]
+pub mod audit_repository_impl;
+pub mod cached_item_repository;
+#[cfg(feature = "mysql")]
+pub mod device_repository_impl;
+#[cfg(feature = "mysql")]
+pub mod idempotency_repository_impl;
+#[cfg(feature = "mysql")]
 pub mod item_repository_impl;
+#[cfg(test)]
+pub(crate) mod item_repository_suite;
+#[cfg(feature = "mysql")]
+pub mod outbox_repository_impl;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(test)]
+pub(crate) mod repository_suite;
+#[cfg(feature = "mysql")]
+pub mod reservation_repository_impl;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "mysql")]
+pub mod staff_repository_impl;
+#[cfg(feature = "mysql")]
+pub mod table_repository_impl;
+#[cfg(feature = "mysql")]
+pub mod waitlist_repository_impl;
+#[cfg(feature = "mysql")]
+pub mod webhook_repository_impl;
@@ -0,0 +1,109 @@
+use crate::model::outbox::OutboxEventRow;
+use crate::persistence::mysql::Db;
+use api_core::{
+    domain::{event::DomainEvent, outbox::OutboxEntry},
+    error::Error,
+    repository::outbox_repository::OutboxRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+use sqlx::{MySql, Transaction};
+
+#[derive(new, Clone)]
+pub struct OutboxRepositoryImpl {
+    db: Db,
+}
+
+/// Stores `event` for the relay as part of `tx`, so that it is kept exactly
+/// when the change it describes is.
+pub(crate) async fn enqueue(
+    tx: &mut Transaction<'_, MySql>,
+    event: &DomainEvent,
+) -> Result<(), Error> {
+    let payload = serde_json::to_string(event).map_err(|_| Error::OutboxStoreError)?;
+    let occurred_at = event.occurred_at();
+    sqlx::query!(
+        r#"
+    INSERT INTO outbox_events (event_type, payload, occurred_at, next_attempt_at)
+    VALUES ( ?, ?, ?, ? )
+            "#,
+        event.name(),
+        payload,
+        occurred_at,
+        occurred_at
+    )
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| Error::OutboxStoreError)?;
+    Ok(())
+}
+
+#[async_trait]
+impl OutboxRepository for OutboxRepositoryImpl {
+    async fn find_pending(
+        &self,
+        now: NaiveDateTime,
+        limit: u32,
+    ) -> Result<Vec<OutboxEntry>, Error> {
+        let result = sqlx::query_as!(
+            OutboxEventRow,
+            r#"SELECT id, payload, attempts, handled_by, next_attempt_at
+          FROM outbox_events
+          WHERE delivered_at IS NULL AND next_attempt_at <= ?
+          ORDER BY id
+          LIMIT ?"#,
+            now,
+            limit
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(OutboxEventRow::from_rows(rows)),
+            Err(_) => Err(Error::OutboxStoreError),
+        }
+    }
+
+    async fn mark_delivered(&self, id: u64, now: NaiveDateTime) -> Result<(), Error> {
+        let result = sqlx::query!(
+            r#"UPDATE outbox_events
+          SET delivered_at = ?, last_error = NULL
+          WHERE id = ?"#,
+            now,
+            id
+        )
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Error::OutboxStoreError),
+        }
+    }
+
+    async fn mark_failed(
+        &self,
+        id: u64,
+        error: String,
+        handled_by: &[String],
+        next_attempt_at: NaiveDateTime,
+    ) -> Result<(), Error> {
+        let result = sqlx::query!(
+            r#"UPDATE outbox_events
+          SET attempts = attempts + 1, last_error = ?, handled_by = ?, next_attempt_at = ?
+          WHERE id = ?"#,
+            error,
+            handled_by.join(","),
+            next_attempt_at,
+            id
+        )
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Error::OutboxStoreError),
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::model::{audit::AuditRecordRow, postgres::PgAuditRecordRow};
+use crate::persistence::postgres::PgDb;
+use api_core::{
+    domain::audit::{AuditFilter, AuditRecord},
+    error::Error,
+    repository::audit_repository::AuditRepository,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use sqlx::{Postgres, Transaction};
+
+#[derive(new, Clone)]
+pub struct PgAuditRepositoryImpl {
+    db: PgDb,
+}
+
+/// Writes the audit record of a change as part of `tx`, the transaction
+/// making it, so that neither is kept without the other.
+pub(crate) async fn record(
+    tx: &mut Transaction<'_, Postgres>,
+    record: &AuditRecord,
+) -> Result<u64, Error> {
+    let id = sqlx::query_scalar::<_, i64>(
+        r#"
+    INSERT INTO audit_records (actor_kind, actor_id, action, table_number, item_id, before_snapshot, after_snapshot)
+    VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+    RETURNING id
+            "#,
+    )
+    .bind(record.actor_kind.as_str())
+    .bind(i64::from(record.actor_id))
+    .bind(record.action.as_str())
+    .bind(record.table_number.map(i64::from))
+    .bind(record.item_id.map(i64::from))
+    .bind(&record.before)
+    .bind(&record.after)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| Error::AuditInsertionError)?;
+    u64::try_from(id).map_err(|_| Error::AuditInsertionError)
+}
+
+#[async_trait]
+impl AuditRepository for PgAuditRepositoryImpl {
+    async fn append(&self, audit: AuditRecord) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::AuditInsertionError)?;
+        let id = record(&mut tx, &audit).await?;
+        tx.commit().await.map_err(|_| Error::AuditInsertionError)?;
+        Ok(id)
+    }
+
+    async fn find(&self, filter: AuditFilter) -> Result<Vec<AuditRecord>, Error> {
+        let actor_kind = filter.actor_kind.map(|kind| kind.as_str());
+        let result = sqlx::query_as::<_, PgAuditRecordRow>(
+            r#"SELECT id, actor_kind, actor_id, action, table_number, item_id, before_snapshot, after_snapshot, recorded_at
+          FROM audit_records
+          WHERE ($1::BIGINT IS NULL OR table_number = $1)
+            AND ($2::BIGINT IS NULL OR item_id = $2)
+            AND ($3::TEXT IS NULL OR actor_kind = $3)
+            AND ($4::BIGINT IS NULL OR actor_id = $4)
+          ORDER BY id DESC
+          LIMIT $5"#,
+        )
+        .bind(filter.table_number.map(i64::from))
+        .bind(filter.item_id.map(i64::from))
+        .bind(actor_kind)
+        .bind(filter.actor_id.map(i64::from))
+        .bind(i64::from(filter.limit))
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => {
+                AuditRecordRow::from_rows(rows.into_iter().map(AuditRecordRow::from).collect())
+                    .map_err(|_| Error::AuditQueryError)
+            }
+            Err(_) => Err(Error::AuditQueryError),
+        }
+    }
+}
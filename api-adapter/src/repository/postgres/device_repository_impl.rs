@@ -0,0 +1,131 @@
+use crate::model::{device::DeviceRow, postgres::PgDeviceRow};
+use crate::persistence::postgres::PgDb;
+use crate::repository::postgres::audit_repository_impl::record;
+use api_core::{
+    domain::{audit::AuditRecord, device::Device},
+    error::Error,
+    repository::device_repository::DeviceRepository,
+};
+use async_trait::async_trait;
+use derive_new::new;
+
+#[derive(new, Clone)]
+pub struct PgDeviceRepositoryImpl {
+    db: PgDb,
+}
+
+#[async_trait]
+impl DeviceRepository for PgDeviceRepositoryImpl {
+    async fn find_all(&self) -> Result<Vec<Device>, Error> {
+        let result = sqlx::query_as::<_, PgDeviceRow>(
+            r#"SELECT id, name, scope, table_number, key_hash, revoked, created_at, updated_at
+          FROM devices
+          ORDER BY id"#,
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(DeviceRow::from_rows(
+                rows.into_iter().map(DeviceRow::from).collect(),
+            )),
+            Err(_) => Err(Error::DeviceNotFoundError),
+        }
+    }
+
+    async fn find_one(&self, device_id: u32) -> Result<Device, Error> {
+        let result = sqlx::query_as::<_, PgDeviceRow>(
+            r#"SELECT id, name, scope, table_number, key_hash, revoked, created_at, updated_at
+          FROM devices
+          WHERE id = $1"#,
+        )
+        .bind(i64::from(device_id))
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(DeviceRow::from_row(row.into())),
+            Err(_) => Err(Error::DeviceNotFoundError),
+        }
+    }
+
+    async fn find_by_key_hash(&self, key_hash: &str) -> Result<Device, Error> {
+        let result = sqlx::query_as::<_, PgDeviceRow>(
+            r#"SELECT id, name, scope, table_number, key_hash, revoked, created_at, updated_at
+          FROM devices
+          WHERE key_hash = $1"#,
+        )
+        .bind(key_hash)
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(DeviceRow::from_row(row.into())),
+            Err(_) => Err(Error::DeviceNotFoundError),
+        }
+    }
+
+    async fn create(&self, mut device: Device, mut audit: AuditRecord) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::DeviceInsertionError)?;
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+    INSERT INTO devices (name, scope, table_number, key_hash, revoked)
+    VALUES ( $1, $2, $3, $4, $5 )
+    RETURNING id
+            "#,
+        )
+        .bind(&device.name)
+        .bind(device.scope.as_str())
+        .bind(device.scope.table_number().map(i64::from))
+        .bind(&device.key_hash)
+        .bind(device.revoked)
+        .fetch_one(&mut tx)
+        .await
+        .map_err(|_| Error::DeviceInsertionError)?;
+        let device_id = u64::try_from(id).map_err(|_| Error::DeviceInsertionError)?;
+
+        device.id = u32::try_from(device_id).ok();
+        audit.after = serde_json::to_string(&device).ok();
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::DeviceInsertionError)?;
+        Ok(device_id)
+    }
+
+    async fn update(&self, device: Device, audit: AuditRecord) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::DeviceUpdateError)?;
+
+        let done = sqlx::query(
+            r#"UPDATE devices
+          SET name = $1, scope = $2, table_number = $3, key_hash = $4, revoked = $5, updated_at = CURRENT_TIMESTAMP
+          WHERE id = $6"#,
+        )
+        .bind(device.name)
+        .bind(device.scope.as_str())
+        .bind(device.scope.table_number().map(i64::from))
+        .bind(device.key_hash)
+        .bind(device.revoked)
+        .bind(device.id.map(i64::from))
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::DeviceUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::DeviceNotFoundError);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::DeviceUpdateError)?;
+        Ok(())
+    }
+}
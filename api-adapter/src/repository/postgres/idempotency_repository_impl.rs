@@ -0,0 +1,124 @@
+use crate::model::{idempotency::IdempotencyKeyRow, postgres::PgIdempotencyKeyRow};
+use crate::persistence::postgres::PgDb;
+use api_core::{
+    domain::idempotency::{IdempotencyRecord, StoredResponse},
+    error::Error,
+    repository::idempotency_repository::IdempotencyRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+
+#[derive(new, Clone)]
+pub struct PgIdempotencyRepositoryImpl {
+    db: PgDb,
+}
+
+#[async_trait]
+impl IdempotencyRepository for PgIdempotencyRepositoryImpl {
+    async fn find_one(&self, key: &str) -> Result<Option<IdempotencyRecord>, Error> {
+        let result = sqlx::query_as::<_, PgIdempotencyKeyRow>(
+            r#"SELECT idempotency_key, fingerprint, response_status, response_body, expires_at, locked_until, created_at
+          FROM idempotency_keys
+          WHERE idempotency_key = $1"#,
+        )
+        .bind(key)
+        .fetch_optional(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(row.map(|row| IdempotencyKeyRow::from_row(row.into()))),
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+
+    async fn create(&self, record: IdempotencyRecord) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+    INSERT INTO idempotency_keys (idempotency_key, fingerprint, expires_at, locked_until)
+    VALUES ( $1, $2, $3, $4 )
+            "#,
+        )
+        .bind(record.key)
+        .bind(record.fingerprint)
+        .bind(record.expires_at)
+        .bind(record.locked_until)
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            // unique_violation: another request with this key won the race
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("23505") => {
+                Err(Error::IdempotentRequestInProgress)
+            }
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+
+    async fn reclaim(
+        &self,
+        key: &str,
+        now: NaiveDateTime,
+        locked_until: NaiveDateTime,
+    ) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"UPDATE idempotency_keys
+          SET locked_until = $1
+          WHERE idempotency_key = $2 AND response_status IS NULL AND locked_until <= $3"#,
+        )
+        .bind(locked_until)
+        .bind(key)
+        .bind(now)
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(done) if done.rows_affected() > 0 => Ok(()),
+            Ok(_) => Err(Error::IdempotentRequestInProgress),
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+
+    async fn complete(&self, key: &str, response: StoredResponse) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"UPDATE idempotency_keys
+          SET response_status = $1, response_body = $2
+          WHERE idempotency_key = $3"#,
+        )
+        .bind(i64::from(response.status))
+        .bind(response.body)
+        .bind(key)
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), Error> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE idempotency_key = $1")
+            .bind(key)
+            .execute(&self.db.pool)
+            .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+
+    async fn delete_expired(&self, now: NaiveDateTime) -> Result<u64, Error> {
+        let result = sqlx::query("DELETE FROM idempotency_keys WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.db.pool)
+            .await;
+
+        match result {
+            Ok(done) => Ok(done.rows_affected()),
+            Err(_) => Err(Error::IdempotencyStoreError),
+        }
+    }
+}
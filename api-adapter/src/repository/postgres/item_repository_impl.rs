@@ -0,0 +1,410 @@
+use crate::model::{
+    item::{ItemRow, ItemsRevisionRow},
+    postgres::{PgItemRow, PgItemsRevisionRow},
+};
+use crate::persistence::postgres::PgDb;
+use crate::repository::postgres::audit_repository_impl::record;
+use crate::repository::postgres::outbox_repository_impl::enqueue;
+use crate::repository::postgres::table_repository_impl::{change_status, seat_free_table};
+use api_core::{
+    domain::{
+        audit::AuditRecord,
+        event::DomainEvent,
+        item::{Item, ItemsRevision},
+        item_transfer::ItemTransfer,
+        table::{Table, TableStatus},
+    },
+    error::Error,
+    repository::item_repository::ItemRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+
+#[derive(new, Clone)]
+pub struct PgItemRepositoryImpl {
+    db: PgDb,
+}
+
+#[async_trait]
+impl ItemRepository for PgItemRepositoryImpl {
+    async fn find_all(&self, table_number: u32) -> Result<Vec<Item>, Error> {
+        let result = sqlx::query_as::<_, PgItemRow>(
+            r#"SELECT id, name, quantity, table_number, start_cooking_at, finish_cooking_at, status,
+          actual_start_cooking_at, actual_finish_cooking_at, version, created_at, updated_at
+          FROM items
+          WHERE table_number = $1
+          ORDER BY id"#,
+        )
+        .bind(i64::from(table_number))
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => ItemRow::from_rows(rows.into_iter().map(ItemRow::from).collect())
+                .map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::ItemNotFoundError),
+        }
+    }
+
+    async fn find_one(&self, table_number: u32, item_id: u32) -> Result<Item, Error> {
+        let result = sqlx::query_as::<_, PgItemRow>(
+            r#"SELECT id, name, quantity, table_number, start_cooking_at, finish_cooking_at, status,
+          actual_start_cooking_at, actual_finish_cooking_at, version, created_at, updated_at
+          FROM items
+          WHERE id = $1 AND table_number = $2"#,
+        )
+        .bind(i64::from(item_id))
+        .bind(i64::from(table_number))
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => ItemRow::from_row(row.into()).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::ItemNotFoundError),
+        }
+    }
+
+    async fn find_one_for_write(&self, table_number: u32, item_id: u32) -> Result<Item, Error> {
+        self.find_one(table_number, item_id).await
+    }
+
+    async fn find_revision(&self, table_number: u32) -> Result<ItemsRevision, Error> {
+        let result = sqlx::query_as::<_, PgItemsRevisionRow>(
+            r#"SELECT COUNT(*) AS item_count,
+          CAST(COALESCE(SUM(version), 0) AS BIGINT) AS version_sum,
+          MAX(updated_at) AT TIME ZONE current_setting('TimeZone') AT TIME ZONE 'UTC'
+            AS last_updated_at -- stamped in the session's time zone
+          FROM items
+          WHERE table_number = $1"#,
+        )
+        .bind(i64::from(table_number))
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(ItemsRevisionRow::from_row(row.into())),
+            Err(_) => Err(Error::ItemNotFoundError),
+        }
+    }
+
+    async fn create(
+        &self,
+        table_number: u32,
+        mut item: Item,
+        mut audit: AuditRecord,
+    ) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ItemInsertionError)?;
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+    INSERT INTO items (name, quantity, table_number, start_cooking_at, finish_cooking_at)
+    VALUES ( $1, $2, $3, $4, $5 )
+    RETURNING id
+            "#,
+        )
+        .bind(item.name.as_str())
+        .bind(i64::from(item.quantity))
+        .bind(i64::from(table_number))
+        .bind(item.start_cooking_at)
+        .bind(item.finish_cooking_at)
+        .fetch_one(&mut tx)
+        .await
+        .map_err(|e| unknown_table_or(e, Error::ItemInsertionError))?;
+
+        item.id = u32::try_from(id).ok();
+        item.table_number = Some(table_number);
+        audit.item_id = item.id;
+        audit.after = serde_json::to_string(&item).ok();
+        record(&mut tx, &audit).await?;
+        let occurred_at = item.start_cooking_at;
+        let event = DomainEvent::ItemAdded {
+            table_number,
+            item,
+            occurred_at,
+        };
+        enqueue(&mut tx, &event).await?;
+
+        tx.commit().await.map_err(|_| Error::ItemInsertionError)?;
+        u64::try_from(id).map_err(|_| Error::ItemInsertionError)
+    }
+
+    async fn update(
+        &self,
+        table_number: u32,
+        mut item: Item,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ItemUpdateError)?;
+
+        let done = sqlx::query(
+            r#"UPDATE items
+          SET name = $1, quantity = $2, status = $3, actual_start_cooking_at = $4, actual_finish_cooking_at = $5,
+              version = version + 1, updated_at = CURRENT_TIMESTAMP
+          WHERE table_number = $6 AND id = $7 AND version = $8"#,
+        )
+        .bind(item.name.as_str())
+        .bind(i64::from(item.quantity))
+        .bind(item.status.as_str())
+        .bind(item.actual_start_cooking_at)
+        .bind(item.actual_finish_cooking_at)
+        .bind(i64::from(table_number))
+        .bind(item.id.map(i64::from))
+        .bind(i64::from(item.version))
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::ItemUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(self.stale_or_missing(table_number, item.id).await);
+        }
+
+        item.version += 1;
+        let event = DomainEvent::item_status_changed(table_number, item, now);
+        enqueue(&mut tx, &event).await?;
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::ItemUpdateError)?;
+        Ok(())
+    }
+
+    async fn delete(
+        &self,
+        table_number: u32,
+        item: Item,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ItemDeletionError)?;
+
+        let done = sqlx::query(
+            r#"DELETE FROM items
+          WHERE table_number = $1 AND id = $2 AND version = $3"#,
+        )
+        .bind(i64::from(table_number))
+        .bind(item.id.map(i64::from))
+        .bind(i64::from(item.version))
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::ItemDeletionError)?;
+        if done.rows_affected() == 0 {
+            return Err(self.stale_or_missing(table_number, item.id).await);
+        }
+
+        let event = DomainEvent::ItemCancelled {
+            table_number,
+            item,
+            occurred_at: now,
+        };
+        enqueue(&mut tx, &event).await?;
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::ItemDeletionError)?;
+        Ok(())
+    }
+
+    async fn transfer(
+        &self,
+        transfer: ItemTransfer,
+        seated_table: Option<Table>,
+        cleared_table: Option<Table>,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ItemTransferError)?;
+
+        let mut moved_items = 0;
+        match &transfer.item_ids {
+            None => {
+                moved_items = sqlx::query(
+                    r#"UPDATE items
+          SET table_number = $1, version = version + 1, updated_at = CURRENT_TIMESTAMP
+          WHERE table_number = $2"#,
+                )
+                .bind(i64::from(transfer.to_table_number))
+                .bind(i64::from(transfer.from_table_number))
+                .execute(&mut tx)
+                .await
+                .map_err(|e| unknown_table_or(e, Error::ItemTransferError))?
+                .rows_affected();
+            }
+            Some(item_ids) => {
+                for item_id in item_ids {
+                    let done = sqlx::query(
+                        r#"UPDATE items
+          SET table_number = $1, version = version + 1, updated_at = CURRENT_TIMESTAMP
+          WHERE table_number = $2 AND id = $3"#,
+                    )
+                    .bind(i64::from(transfer.to_table_number))
+                    .bind(i64::from(transfer.from_table_number))
+                    .bind(i64::from(*item_id))
+                    .execute(&mut tx)
+                    .await
+                    .map_err(|e| unknown_table_or(e, Error::ItemTransferError))?;
+                    // every selected item has to belong to the source table,
+                    // dropping the transaction rolls back what was moved so far
+                    if done.rows_affected() == 0 {
+                        return Err(Error::ItemNotFoundError);
+                    }
+                    moved_items += done.rows_affected();
+                }
+            }
+        }
+
+        let item_ids = transfer.item_ids.as_ref().map(|ids| {
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        });
+        sqlx::query(
+            r#"
+    INSERT INTO item_transfers (kind, from_table_number, to_table_number, item_ids, moved_items)
+    VALUES ( $1, $2, $3, $4, $5 )
+            "#,
+        )
+        .bind(transfer.kind.as_str())
+        .bind(i64::from(transfer.from_table_number))
+        .bind(i64::from(transfer.to_table_number))
+        .bind(item_ids)
+        .bind(i64::try_from(moved_items).unwrap_or(i64::MAX))
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::ItemTransferError)?;
+
+        let event = DomainEvent::ItemsMoved {
+            transfer,
+            moved_items,
+            occurred_at: now,
+        };
+        enqueue(&mut tx, &event).await?;
+        record(&mut tx, &audit).await?;
+        if let Some(table) = seated_table {
+            seat_free_table(&mut tx, &table, now).await?;
+        }
+        if let Some(table) = cleared_table {
+            change_status(&mut tx, &table, TableStatus::Seated, now).await?;
+        }
+
+        tx.commit().await.map_err(|_| Error::ItemTransferError)?;
+        Ok(moved_items)
+    }
+}
+
+impl PgItemRepositoryImpl {
+    /// Tells why a guarded write touched no row: the item is gone or it has
+    /// moved on to another version.
+    async fn stale_or_missing(&self, table_number: u32, item_id: Option<u32>) -> Error {
+        match item_id {
+            Some(item_id) if self.find_one(table_number, item_id).await.is_ok() => {
+                Error::ItemVersionMismatch
+            }
+            _ => Error::ItemNotFoundError,
+        }
+    }
+}
+
+/// Items must belong to a table that exists; the foreign key rejects the rest
+/// with `foreign_key_violation`.
+fn unknown_table_or(e: sqlx::Error, otherwise: Error) -> Error {
+    match e {
+        sqlx::Error::Database(e) if e.code().as_deref() == Some("23503") => Error::UnknownTable,
+        _ => otherwise,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::persistence::Env;
+    use crate::repository::item_repository_suite::{item_repository_suite, ItemRepositoryFixture};
+    use crate::repository::postgres::audit_repository_impl::PgAuditRepositoryImpl;
+    use crate::repository::postgres::outbox_repository_impl::PgOutboxRepositoryImpl;
+    use crate::repository::postgres::table_repository_impl::PgTableRepositoryImpl;
+
+    pub(crate) struct Fixture {
+        items: PgItemRepositoryImpl,
+        outbox: PgOutboxRepositoryImpl,
+        tables: PgTableRepositoryImpl,
+        audits: PgAuditRepositoryImpl,
+        db: PgDb,
+    }
+
+    #[async_trait]
+    impl ItemRepositoryFixture for Fixture {
+        type Items = PgItemRepositoryImpl;
+        type Outbox = PgOutboxRepositoryImpl;
+        type Tables = PgTableRepositoryImpl;
+        type Audits = PgAuditRepositoryImpl;
+
+        async fn set_up() -> Self {
+            let db = PgDb::new(Env::Test).await.unwrap();
+            sqlx::query(
+                "INSERT INTO tables (number) VALUES (1), (2), (3), (4), (5) ON CONFLICT DO NOTHING",
+            )
+            .execute(&db.pool)
+            .await
+            .unwrap();
+            let fixture = Fixture {
+                items: PgItemRepositoryImpl::new(db.clone()),
+                outbox: PgOutboxRepositoryImpl::new(db.clone()),
+                tables: PgTableRepositoryImpl::new(db.clone()),
+                audits: PgAuditRepositoryImpl::new(db.clone()),
+                db,
+            };
+            fixture.tear_down().await;
+            fixture
+        }
+
+        async fn tear_down(&self) {
+            for table in ["items", "item_transfers", "outbox_events", "audit_records"] {
+                sqlx::query(&format!("DELETE FROM {}", table))
+                    .execute(&self.db.pool)
+                    .await
+                    .unwrap();
+            }
+            sqlx::query("UPDATE tables SET status = 'free', seated_at = NULL WHERE number <= 5")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+        }
+
+        fn items(&self) -> &Self::Items {
+            &self.items
+        }
+
+        fn outbox(&self) -> &Self::Outbox {
+            &self.outbox
+        }
+
+        fn tables(&self) -> &Self::Tables {
+            &self.tables
+        }
+
+        fn audits(&self) -> &Self::Audits {
+            &self.audits
+        }
+    }
+
+    item_repository_suite!(Fixture);
+}
@@ -0,0 +1,14 @@
+//! The repositories on PostgreSQL. Its queries are not checked at compile time
+//! like the MySQL ones, since `sqlx::query!` can only check against the one
+//! database `DATABASE_URL` points to.
+
+pub mod audit_repository_impl;
+pub mod device_repository_impl;
+pub mod idempotency_repository_impl;
+pub mod item_repository_impl;
+pub mod outbox_repository_impl;
+pub mod reservation_repository_impl;
+pub mod staff_repository_impl;
+pub mod table_repository_impl;
+pub mod waitlist_repository_impl;
+pub mod webhook_repository_impl;
@@ -0,0 +1,134 @@
+use crate::model::{postgres::PgTableRow, table::TableRow};
+use crate::persistence::postgres::PgDb;
+use crate::repository::postgres::audit_repository_impl::record;
+use crate::repository::postgres::outbox_repository_impl::enqueue;
+use api_core::{
+    domain::{
+        audit::AuditRecord,
+        event::DomainEvent,
+        table::{Table, TableStatus},
+    },
+    error::Error,
+    repository::table_repository::TableRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+use sqlx::{Postgres, Transaction};
+
+#[derive(new, Clone)]
+pub struct PgTableRepositoryImpl {
+    db: PgDb,
+}
+
+/// Saves a newly seated table within `tx`, provided nobody has seated it in
+/// the meantime.
+pub(crate) async fn seat_free_table(
+    tx: &mut Transaction<'_, Postgres>,
+    table: &Table,
+    now: NaiveDateTime,
+) -> Result<(), Error> {
+    change_status(tx, table, TableStatus::Free, now).await
+}
+
+/// Saves the table's new status within `tx`, provided it still has the
+/// `previous` one. Fails with `TableNotAvailable` otherwise.
+pub(crate) async fn change_status(
+    tx: &mut Transaction<'_, Postgres>,
+    table: &Table,
+    previous: TableStatus,
+    now: NaiveDateTime,
+) -> Result<(), Error> {
+    let done = sqlx::query(
+        r#"UPDATE tables
+          SET status = $1, seated_at = $2, updated_at = CURRENT_TIMESTAMP
+          WHERE number = $3 AND status = $4"#,
+    )
+    .bind(table.status.as_str())
+    .bind(table.seated_at)
+    .bind(i64::from(table.number))
+    .bind(previous.as_str())
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| Error::TableUpdateError)?;
+    if done.rows_affected() == 0 {
+        return Err(Error::TableNotAvailable);
+    }
+
+    let event = DomainEvent::table_status_changed(table.number, table.status, now);
+    enqueue(tx, &event).await
+}
+
+#[async_trait]
+impl TableRepository for PgTableRepositoryImpl {
+    async fn find_all(&self) -> Result<Vec<Table>, Error> {
+        let result = sqlx::query_as::<_, PgTableRow>(
+            r#"SELECT number, capacity, zone, status, seated_at, created_at, updated_at
+          FROM tables
+          ORDER BY number"#,
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => TableRow::from_rows(rows.into_iter().map(TableRow::from).collect())
+                .map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::TableNotFoundError),
+        }
+    }
+
+    async fn find_one(&self, table_number: u32) -> Result<Table, Error> {
+        let result = sqlx::query_as::<_, PgTableRow>(
+            r#"SELECT number, capacity, zone, status, seated_at, created_at, updated_at
+          FROM tables
+          WHERE number = $1"#,
+        )
+        .bind(i64::from(table_number))
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => TableRow::from_row(row.into()).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::TableNotFoundError),
+        }
+    }
+
+    async fn update(
+        &self,
+        table: Table,
+        previous: TableStatus,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::TableUpdateError)?;
+
+        change_status(&mut tx, &table, previous, now).await?;
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::TableUpdateError)?;
+        Ok(())
+    }
+
+    async fn seat(
+        &self,
+        table: Table,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::TableUpdateError)?;
+        seat_free_table(&mut tx, &table, now).await?;
+        record(&mut tx, &audit).await?;
+        tx.commit().await.map_err(|_| Error::TableUpdateError)?;
+        Ok(())
+    }
+}
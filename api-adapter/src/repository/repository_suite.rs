@@ -0,0 +1,684 @@
+//! The integration tests the repositories other than the item one have to pass
+//! on every backend, including how each maps the errors of its database. A
+//! backend implements `RepositoriesFixture` against its test database and
+//! declares the tests with `repository_suite!`.
+
+use api_core::{
+    domain::{
+        actor::{Actor, ActorKind},
+        audit::{AuditAction, AuditFilter, AuditRecord},
+        device::{Device, DeviceScope},
+        idempotency::{IdempotencyPolicy, IdempotencyRecord, StoredResponse},
+        item::Item,
+        reservation::{Reservation, ReservationStatus},
+        staff::{Role, Staff},
+        table::TableStatus,
+        waitlist::WaitlistEntry,
+        webhook::{WebhookDelivery, WebhookDeliveryStatus, WebhookPolicy, WebhookSubscription},
+    },
+    error::Error,
+    repository::{
+        audit_repository::AuditRepository, device_repository::DeviceRepository,
+        idempotency_repository::IdempotencyRepository, item_repository::ItemRepository,
+        outbox_repository::OutboxRepository, reservation_repository::ReservationRepository,
+        staff_repository::StaffRepository, table_repository::TableRepository,
+        waitlist_repository::WaitlistRepository, webhook_repository::WebhookRepository,
+        Repositories,
+    },
+};
+use async_trait::async_trait;
+use chrono::{Duration, Local, NaiveDateTime, Timelike};
+use pretty_assertions::assert_eq;
+
+#[async_trait]
+pub(crate) trait RepositoriesFixture: Sized {
+    type Repositories: Repositories;
+
+    /// Connects to the test database, with tables 1 to 5 present and free and
+    /// none of the rows the suite adds left over: reservations, waitlist
+    /// entries, devices, audit records, idempotency keys, outbox events,
+    /// webhooks, items and the staff named `test-*`.
+    async fn set_up() -> Self;
+    async fn tear_down(&self);
+    fn repositories(&self) -> &Self::Repositories;
+}
+
+fn audit(action: AuditAction) -> AuditRecord {
+    AuditRecord::by_system(action, None, None, None, None)
+}
+
+/// Now, as precisely as every backend stores it: MySQL's DATETIME drops the
+/// fraction of a second.
+fn now() -> NaiveDateTime {
+    Local::now().naive_local().with_nanosecond(0).unwrap()
+}
+
+/// Declares a `#[tokio::test]` for every test of the suite, run against the
+/// given `RepositoriesFixture`.
+macro_rules! repository_suite {
+    ($fixture:ty) => {
+        $crate::repository::repository_suite::repository_suite!(
+            $fixture,
+            test_query_and_seat_table,
+            test_seat_taken_table_is_refused,
+            test_status_changed_meanwhile_is_kept,
+            test_insert_and_cancel_reservation,
+            test_overlapping_booking_is_refused,
+            test_seated_reservation_is_not_changed,
+            test_waiting_queue_order,
+            test_insert_and_deactivate_staff,
+            test_staff_names_are_unique,
+            test_insert_and_revoke_device,
+            test_append_and_filter_audit_records,
+            test_reserve_and_complete_key,
+            test_reclaim_only_after_the_lease_runs_out,
+            test_delete_expired_keys,
+            test_pending_events_until_delivered,
+            test_subscription_and_delivery_log
+        );
+    };
+    ($fixture:ty, $($test:ident),+) => {
+        $(
+            #[tokio::test]
+            #[serial_test::serial]
+            async fn $test() {
+                $crate::repository::repository_suite::$test::<$fixture>().await;
+            }
+        )+
+    };
+}
+pub(crate) use repository_suite;
+
+pub(crate) async fn test_query_and_seat_table<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().table_repository();
+
+    let mut table = repo.find_one(4).await.unwrap();
+    assert_eq!(table.capacity, 4);
+    assert_eq!(table.zone, "terrace");
+    assert_eq!(table.status, TableStatus::Free);
+
+    let now = now();
+    table.seat(now).unwrap();
+    repo.update(
+        table,
+        TableStatus::Free,
+        now,
+        audit(AuditAction::TableSeated),
+    )
+    .await
+    .unwrap();
+    let seated_table = repo.find_one(4).await.unwrap();
+    assert_eq!(seated_table.status, TableStatus::Seated);
+    assert_eq!(seated_table.seated_at, Some(now));
+    assert!(matches!(
+        repo.find_one(999).await,
+        Err(Error::TableNotFoundError)
+    ));
+
+    let tables = repo.find_all().await.unwrap();
+    assert_eq!(tables.iter().filter(|t| t.number <= 5).count(), 5);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_seat_taken_table_is_refused<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().table_repository();
+
+    let now = now();
+    let mut table = repo.find_one(1).await.unwrap();
+    table.seat(now).unwrap();
+    repo.seat(table.clone(), now, audit(AuditAction::TableSeated))
+        .await
+        .unwrap();
+
+    let seated_again = repo.seat(table, now, audit(AuditAction::TableSeated)).await;
+    assert_eq!(matches!(seated_again, Err(Error::TableNotAvailable)), true);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_status_changed_meanwhile_is_kept<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().table_repository();
+
+    // read by a manual status change and by a seating at once, the seating
+    // getting in first
+    let now = now();
+    let mut changed_by_hand = repo.find_one(1).await.unwrap();
+    let mut seated = changed_by_hand.clone();
+    seated.seat(now).unwrap();
+    repo.seat(seated, now, audit(AuditAction::TableSeated))
+        .await
+        .unwrap();
+
+    changed_by_hand
+        .transition_to(TableStatus::Seated, now + Duration::minutes(1))
+        .unwrap();
+    let changed = repo
+        .update(
+            changed_by_hand,
+            TableStatus::Free,
+            now,
+            audit(AuditAction::TableStatusChanged),
+        )
+        .await;
+    assert!(matches!(changed, Err(Error::TableNotAvailable)));
+    assert_eq!(repo.find_one(1).await.unwrap().seated_at, Some(now));
+
+    fixture.tear_down().await;
+}
+
+fn booking(party_size: u32, starts_at: NaiveDateTime) -> Reservation {
+    Reservation::new(
+        String::from("Tanaka"),
+        String::from("090-0000-0000"),
+        party_size,
+        4,
+        starts_at,
+        starts_at + Duration::hours(2),
+    )
+    .unwrap()
+}
+
+pub(crate) async fn test_insert_and_cancel_reservation<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().reservation_repository();
+
+    let starts_at = now() + Duration::days(1);
+    let reservation = booking(4, starts_at);
+    let reservation_id = u32::try_from(
+        repo.create(reservation.clone(), audit(AuditAction::ReservationMade))
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+
+    let mut actual_data = repo.find_one(reservation_id).await.unwrap();
+    assert_eq!(actual_data.guest_name, reservation.guest_name);
+    assert_eq!(actual_data.party_size, reservation.party_size);
+    assert_eq!(actual_data.starts_at, starts_at);
+    assert_eq!(actual_data.status, ReservationStatus::Booked);
+
+    actual_data.cancel().unwrap();
+    repo.update(actual_data, audit(AuditAction::ReservationCancelled))
+        .await
+        .unwrap();
+
+    let reservations = repo.find_by_table(4).await.unwrap();
+    assert_eq!(reservations.len(), 1);
+    assert_eq!(reservations[0].status, ReservationStatus::Cancelled);
+
+    let booked = repo
+        .find_booked_between(starts_at, starts_at + Duration::hours(1))
+        .await
+        .unwrap();
+    assert_eq!(booked.len(), 0);
+    assert!(matches!(
+        repo.find_one(reservation_id + 1).await,
+        Err(Error::ReservationNotFoundError)
+    ));
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_overlapping_booking_is_refused<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().reservation_repository();
+
+    let starts_at = now() + Duration::days(1);
+    repo.create(booking(2, starts_at), audit(AuditAction::ReservationMade))
+        .await
+        .unwrap();
+
+    let overlapping = repo
+        .create(
+            booking(2, starts_at + Duration::hours(1)),
+            audit(AuditAction::ReservationMade),
+        )
+        .await;
+    assert_eq!(matches!(overlapping, Err(Error::ReservationConflict)), true);
+    let later = repo
+        .create(
+            booking(2, starts_at + Duration::hours(2)),
+            audit(AuditAction::ReservationMade),
+        )
+        .await;
+    assert_eq!(later.is_ok(), true);
+    assert_eq!(repo.find_by_table(4).await.unwrap().len(), 2);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_seated_reservation_is_not_changed<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().reservation_repository();
+    let tables = fixture.repositories().table_repository();
+
+    let now = now();
+    let reservation_id = u32::try_from(
+        repo.create(booking(2, now), audit(AuditAction::ReservationMade))
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    // read by a cancel and a seat at once, the seat getting in first
+    let mut cancelled = repo.find_one(reservation_id).await.unwrap();
+    let mut seated = cancelled.clone();
+    let mut table = tables.find_one(4).await.unwrap();
+    seated.seat().unwrap();
+    table.seat(now).unwrap();
+    repo.seat(seated, table, now, audit(AuditAction::ReservationSeated))
+        .await
+        .unwrap();
+
+    cancelled.cancel().unwrap();
+    assert!(matches!(
+        repo.update(cancelled, audit(AuditAction::ReservationCancelled))
+            .await,
+        Err(Error::InvalidReservationStatusTransition)
+    ));
+    let modified = repo.find_one(reservation_id).await.unwrap();
+    assert_eq!(modified.status, ReservationStatus::Seated);
+    assert!(matches!(
+        repo.update(
+            Reservation {
+                party_size: 3,
+                status: ReservationStatus::Booked,
+                ..modified
+            },
+            audit(AuditAction::ReservationModified)
+        )
+        .await,
+        Err(Error::InvalidReservationStatusTransition)
+    ));
+    assert_eq!(
+        repo.find_one(reservation_id).await.unwrap().status,
+        ReservationStatus::Seated
+    );
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_waiting_queue_order<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().waitlist_repository();
+
+    let now = now();
+    let later = WaitlistEntry::new(
+        String::from("Suzuki"),
+        String::from("080-1111-1111"),
+        4,
+        now + Duration::minutes(5),
+    )
+    .unwrap();
+    let earlier =
+        WaitlistEntry::new(String::from("Sato"), String::from("080-0000-0000"), 2, now).unwrap();
+    repo.create(later, audit(AuditAction::WaitlistJoined))
+        .await
+        .unwrap();
+    let earlier_id = u32::try_from(
+        repo.create(earlier, audit(AuditAction::WaitlistJoined))
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+
+    let waiting = repo.find_waiting().await.unwrap();
+    assert_eq!(waiting.len(), 2);
+    assert_eq!(waiting[0].guest_name, "Sato");
+
+    let mut entry = repo.find_one(earlier_id).await.unwrap();
+    entry.seat().unwrap();
+    repo.update(entry, audit(AuditAction::WaitlistSeated))
+        .await
+        .unwrap();
+
+    let waiting = repo.find_waiting().await.unwrap();
+    assert_eq!(waiting.len(), 1);
+    assert_eq!(waiting[0].guest_name, "Suzuki");
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_insert_and_deactivate_staff<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().staff_repository();
+
+    let staff = Staff::new(String::from("test-kenji"), Role::Cook).unwrap();
+    let staff_id = u32::try_from(
+        repo.create(staff, audit(AuditAction::StaffAdded))
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+
+    let mut actual_data = repo.find_one(staff_id).await.unwrap();
+    assert_eq!(actual_data.name, "test-kenji");
+    assert_eq!(actual_data.role, Role::Cook);
+    assert_eq!(actual_data.active, true);
+
+    actual_data.active = false;
+    repo.update(actual_data, audit(AuditAction::StaffDeactivated))
+        .await
+        .unwrap();
+    assert_eq!(repo.find_one(staff_id).await.unwrap().active, false);
+
+    let by_name = repo.find_by_name("test-kenji").await.unwrap();
+    assert_eq!(by_name.id, Some(staff_id));
+    assert!(matches!(
+        repo.find_by_name("test-nobody").await,
+        Err(Error::StaffNotFoundError)
+    ));
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_staff_names_are_unique<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().staff_repository();
+
+    let staff = Staff::new(String::from("test-kenji"), Role::Cook).unwrap();
+    repo.create(staff.clone(), audit(AuditAction::StaffAdded))
+        .await
+        .unwrap();
+
+    let taken = repo.create(staff, audit(AuditAction::StaffAdded)).await;
+    assert!(matches!(taken, Err(Error::StaffNameTaken)));
+    assert_eq!(
+        repo.find_all()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|s| s.name == "test-kenji")
+            .count(),
+        1
+    );
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_insert_and_revoke_device<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().device_repository();
+
+    let device = Device::new(
+        String::from("test-tablet"),
+        DeviceScope::Table(2),
+        String::from("test-hash"),
+    )
+    .unwrap();
+    let device_id = u32::try_from(
+        repo.create(device, audit(AuditAction::DeviceKeyIssued))
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+
+    let mut actual_data = repo.find_by_key_hash("test-hash").await.unwrap();
+    assert_eq!(actual_data.id, Some(device_id));
+    assert_eq!(actual_data.scope, DeviceScope::Table(2));
+    assert_eq!(actual_data.revoked, false);
+
+    actual_data.revoke();
+    repo.update(actual_data, audit(AuditAction::DeviceKeyRevoked))
+        .await
+        .unwrap();
+    assert_eq!(repo.find_one(device_id).await.unwrap().revoked, true);
+    assert!(matches!(
+        repo.find_by_key_hash("test-unknown").await,
+        Err(Error::DeviceNotFoundError)
+    ));
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_append_and_filter_audit_records<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().audit_repository();
+
+    let waiter = Actor::Staff(Staff::of(
+        3,
+        String::from("waiter"),
+        Role::Waiter,
+        true,
+        None,
+    ));
+    let cook = Actor::Staff(Staff::of(4, String::from("cook"), Role::Cook, true, None));
+    let added = AuditRecord::new(
+        &waiter,
+        AuditAction::ItemAdded,
+        Some(3),
+        Some(1),
+        None,
+        Some(String::from(r#"{"name":"Sushi"}"#)),
+    )
+    .unwrap();
+    let deleted = AuditRecord::new(
+        &cook,
+        AuditAction::ItemDeleted,
+        Some(3),
+        Some(1),
+        Some(String::from(r#"{"name":"Sushi"}"#)),
+        None,
+    )
+    .unwrap();
+    repo.append(added).await.unwrap();
+    repo.append(deleted).await.unwrap();
+
+    let by_table = AuditFilter::new(Some(3), None, None, None, None).unwrap();
+    let actual_data = repo.find(by_table).await.unwrap();
+    assert_eq!(actual_data.len(), 2);
+    assert_eq!(actual_data[0].action, AuditAction::ItemDeleted);
+
+    let by_actor = AuditFilter::new(Some(3), None, Some(ActorKind::Staff), Some(3), None).unwrap();
+    let actual_data = repo.find(by_actor).await.unwrap();
+    assert_eq!(actual_data.len(), 1);
+    assert_eq!(actual_data[0].action, AuditAction::ItemAdded);
+    assert_eq!(actual_data[0].recorded_at.is_some(), true);
+
+    fixture.tear_down().await;
+}
+
+fn idempotency_record(policy: &IdempotencyPolicy, now: NaiveDateTime) -> IdempotencyRecord {
+    IdempotencyRecord::new(String::from("test-key"), String::from("hash"), policy, now).unwrap()
+}
+
+pub(crate) async fn test_reserve_and_complete_key<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().idempotency_repository();
+
+    let record = idempotency_record(&IdempotencyPolicy::default(), now());
+    repo.create(record.clone()).await.unwrap();
+    // the unique key violation of each backend means the same
+    assert!(matches!(
+        repo.create(record).await,
+        Err(Error::IdempotentRequestInProgress)
+    ));
+
+    let pending = repo.find_one("test-key").await.unwrap().unwrap();
+    assert_eq!(pending.response, None);
+
+    let response = StoredResponse {
+        status: 201,
+        body: String::from(r#"{"item_id":1}"#),
+    };
+    repo.complete("test-key", response.clone()).await.unwrap();
+    let completed = repo.find_one("test-key").await.unwrap().unwrap();
+    assert_eq!(completed.response, Some(response));
+    assert_eq!(repo.find_one("test-unknown").await.unwrap(), None);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_reclaim_only_after_the_lease_runs_out<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().idempotency_repository();
+
+    let policy = IdempotencyPolicy::default();
+    let now = now();
+    repo.create(idempotency_record(&policy, now)).await.unwrap();
+
+    let held = repo.reclaim("test-key", now, now + policy.lease).await;
+    assert!(matches!(held, Err(Error::IdempotentRequestInProgress)));
+
+    let later = now + policy.lease;
+    repo.reclaim("test-key", later, later + policy.lease)
+        .await
+        .unwrap();
+    let reclaimed = repo.find_one("test-key").await.unwrap().unwrap();
+    assert_eq!(reclaimed.is_locked(later), true);
+    let again = repo.reclaim("test-key", later, later + policy.lease).await;
+    assert!(matches!(again, Err(Error::IdempotentRequestInProgress)));
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_delete_expired_keys<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().idempotency_repository();
+
+    let record = idempotency_record(&IdempotencyPolicy::default(), now());
+    let expires_at = record.expires_at;
+    repo.create(record).await.unwrap();
+
+    assert_eq!(
+        repo.delete_expired(expires_at - Duration::seconds(1))
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(repo.delete_expired(expires_at).await.unwrap(), 1);
+    assert_eq!(repo.find_one("test-key").await.unwrap(), None);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_pending_events_until_delivered<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().outbox_repository();
+
+    let now = now();
+    let item = Item::new(String::from("Ramen"), 1, now).unwrap();
+    fixture
+        .repositories()
+        .item_repository()
+        .create(1, item, audit(AuditAction::ItemAdded))
+        .await
+        .unwrap();
+
+    let pending = repo.find_pending(now, 10).await.unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(
+        pending[0].event.as_ref().map(|e| e.name()),
+        Some("item_added")
+    );
+    assert_eq!(pending[0].attempts, 0);
+    assert_eq!(pending[0].handled_by, Vec::<String>::new());
+
+    // a failed event waits for its next attempt
+    let retry_at = now + Duration::minutes(1);
+    let handled_by = vec![String::from("item_events"), String::from("webhooks")];
+    repo.mark_failed(
+        pending[0].id,
+        String::from("timeout"),
+        &handled_by,
+        retry_at,
+    )
+    .await
+    .unwrap();
+    assert_eq!(repo.find_pending(now, 10).await.unwrap().len(), 0);
+    let retried = repo.find_pending(retry_at, 10).await.unwrap();
+    assert_eq!(retried[0].attempts, 1);
+    assert_eq!(retried[0].handled_by, handled_by);
+
+    repo.mark_delivered(retried[0].id, retry_at).await.unwrap();
+    assert_eq!(repo.find_pending(retry_at, 10).await.unwrap().len(), 0);
+
+    fixture.tear_down().await;
+}
+
+pub(crate) async fn test_subscription_and_delivery_log<F: RepositoriesFixture>() {
+    let fixture = F::set_up().await;
+    let repo = fixture.repositories().webhook_repository();
+
+    let subscription = WebhookSubscription::new(
+        String::from("http://127.0.0.1:9/hooks"),
+        String::from("test-secret"),
+        vec![String::from("table_closed"), String::from("item_served")],
+    )
+    .unwrap();
+    let subscription_id = u32::try_from(
+        repo.create(subscription, audit(AuditAction::WebhookCreated))
+            .await
+            .unwrap(),
+    )
+    .unwrap();
+    let mut subscription = repo.find_one(subscription_id).await.unwrap();
+    assert_eq!(
+        subscription.event_types,
+        vec!["table_closed", "item_served"]
+    );
+    assert_eq!(subscription.secret, "test-secret");
+
+    let now = now();
+    let delivery = WebhookDelivery::new(
+        subscription_id,
+        String::from("table_closed"),
+        String::from("{}"),
+        now,
+    );
+    let delivery_id = repo.create_delivery(1, delivery.clone()).await.unwrap();
+    assert_eq!(delivery_id.is_some(), true);
+    // the same event handled again
+    assert_eq!(repo.create_delivery(1, delivery).await.unwrap(), None);
+    let mut due = repo.find_due_deliveries(now, 10).await.unwrap();
+    assert_eq!(due.len(), 1);
+    assert_eq!(due[0].id, delivery_id);
+
+    let policy = WebhookPolicy::default();
+    let mut delivery = due.remove(0);
+    delivery.record_attempt(Err(String::from("refused")), &policy, now);
+    repo.update_delivery(delivery).await.unwrap();
+    assert_eq!(repo.find_due_deliveries(now, 10).await.unwrap().len(), 0);
+
+    let log = repo.find_deliveries(subscription_id, 10).await.unwrap();
+    assert_eq!(log[0].status, WebhookDeliveryStatus::Pending);
+    assert_eq!(log[0].attempts, 1);
+    assert_eq!(log[0].last_error, Some(String::from("refused")));
+
+    subscription.record_failure(&policy);
+    repo.update(subscription, audit(AuditAction::WebhookDisabled))
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.find_one(subscription_id)
+            .await
+            .unwrap()
+            .consecutive_failures,
+        1
+    );
+
+    repo.delete(subscription_id, audit(AuditAction::WebhookDeleted))
+        .await
+        .unwrap();
+    assert!(matches!(
+        repo.find_one(subscription_id).await,
+        Err(Error::WebhookNotFoundError)
+    ));
+    assert!(matches!(
+        repo.delete(subscription_id, audit(AuditAction::WebhookDeleted))
+            .await,
+        Err(Error::WebhookNotFoundError)
+    ));
+    assert_eq!(
+        repo.find_deliveries(subscription_id, 10)
+            .await
+            .unwrap()
+            .len(),
+        0
+    );
+
+    fixture.tear_down().await;
+}
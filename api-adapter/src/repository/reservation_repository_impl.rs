@@ -0,0 +1,244 @@
+use crate::model::{reservation::ReservationRow, table::TableRow};
+use crate::persistence::mysql::Db;
+use crate::repository::audit_repository_impl::record;
+use crate::repository::table_repository_impl::seat_free_table;
+use api_core::{
+    domain::{
+        audit::AuditRecord,
+        reservation::{Reservation, ReservationStatus},
+        table::Table,
+    },
+    error::Error,
+    repository::reservation_repository::ReservationRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+use sqlx::{MySql, Transaction};
+
+#[derive(new, Clone)]
+pub struct ReservationRepositoryImpl {
+    db: Db,
+}
+
+/// Locks the reservation's table for the rest of `tx`, then checks that the
+/// party fits it and that no other booking for it overlaps.
+async fn ensure_bookable(
+    tx: &mut Transaction<'_, MySql>,
+    reservation: &Reservation,
+) -> Result<(), Error> {
+    let row = sqlx::query_as!(
+        TableRow,
+        r#"SELECT number, capacity, zone, status, seated_at, created_at, updated_at
+          FROM tables
+          WHERE number = ?
+          FOR UPDATE"#,
+        reservation.table_number
+    )
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| Error::TableNotFoundError)?;
+    let table = TableRow::from_row(row).map_err(|_| Error::InvalidStoredRecord)?;
+    if !reservation.fits(&table) {
+        return Err(Error::TableCapacityExceeded);
+    }
+
+    let rows = sqlx::query_as!(
+        ReservationRow,
+        r#"SELECT id, guest_name, guest_phone, party_size, table_number, starts_at, ends_at, status,
+          created_at, updated_at
+          FROM reservations
+          WHERE table_number = ? AND status = 'booked'"#,
+        reservation.table_number
+    )
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|_| Error::ReservationNotFoundError)?;
+    let booked = ReservationRow::from_rows(rows).map_err(|_| Error::InvalidStoredRecord)?;
+    if booked.iter().any(|other| reservation.conflicts_with(other)) {
+        return Err(Error::ReservationConflict);
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl ReservationRepository for ReservationRepositoryImpl {
+    async fn find_one(&self, reservation_id: u32) -> Result<Reservation, Error> {
+        let result = sqlx::query_as!(
+            ReservationRow,
+            r#"SELECT id, guest_name, guest_phone, party_size, table_number, starts_at, ends_at, status,
+          created_at, updated_at
+          FROM reservations
+          WHERE id = ?"#,
+            reservation_id
+        )
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => ReservationRow::from_row(row).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::ReservationNotFoundError),
+        }
+    }
+
+    async fn find_by_table(&self, table_number: u32) -> Result<Vec<Reservation>, Error> {
+        let result = sqlx::query_as!(
+            ReservationRow,
+            r#"SELECT id, guest_name, guest_phone, party_size, table_number, starts_at, ends_at, status,
+          created_at, updated_at
+          FROM reservations
+          WHERE table_number = ?
+          ORDER BY starts_at"#,
+            table_number
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => ReservationRow::from_rows(rows).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::ReservationNotFoundError),
+        }
+    }
+
+    async fn find_booked_between(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<Vec<Reservation>, Error> {
+        let result = sqlx::query_as!(
+            ReservationRow,
+            r#"SELECT id, guest_name, guest_phone, party_size, table_number, starts_at, ends_at, status,
+          created_at, updated_at
+          FROM reservations
+          WHERE status = 'booked' AND starts_at < ? AND ends_at > ?
+          ORDER BY starts_at"#,
+            until,
+            from
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => ReservationRow::from_rows(rows).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::ReservationNotFoundError),
+        }
+    }
+
+    async fn create(
+        &self,
+        mut reservation: Reservation,
+        mut audit: AuditRecord,
+    ) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ReservationInsertionError)?;
+        ensure_bookable(&mut tx, &reservation).await?;
+
+        let done = sqlx::query!(
+            r#"
+    INSERT INTO reservations (guest_name, guest_phone, party_size, table_number, starts_at, ends_at, status)
+    VALUES ( ?, ?, ?, ?, ?, ?, ? )
+            "#,
+            reservation.guest_name.as_str(),
+            reservation.guest_phone.as_str(),
+            reservation.party_size,
+            reservation.table_number,
+            reservation.starts_at,
+            reservation.ends_at,
+            reservation.status.as_str()
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::ReservationInsertionError)?;
+        let reservation_id = done.last_insert_id();
+
+        reservation.id = u32::try_from(reservation_id).ok();
+        audit.after = serde_json::to_string(&reservation).ok();
+        record(&mut tx, &audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::ReservationInsertionError)?;
+        Ok(reservation_id)
+    }
+
+    async fn update(&self, reservation: Reservation, audit: AuditRecord) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ReservationUpdateError)?;
+        if reservation.status == ReservationStatus::Booked {
+            ensure_bookable(&mut tx, &reservation).await?;
+        }
+
+        let done = sqlx::query!(
+            r#"UPDATE reservations
+          SET guest_name = ?, guest_phone = ?, party_size = ?, table_number = ?, starts_at = ?, ends_at = ?,
+              status = ?, updated_at = CURRENT_TIMESTAMP
+          WHERE id = ? AND status = 'booked'"#,
+            reservation.guest_name,
+            reservation.guest_phone,
+            reservation.party_size,
+            reservation.table_number,
+            reservation.starts_at,
+            reservation.ends_at,
+            reservation.status.as_str(),
+            reservation.id
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::ReservationUpdateError)?;
+        // only a booked reservation is changed, so a seat that got in first
+        // is not undone
+        if done.rows_affected() == 0 {
+            return Err(Error::InvalidReservationStatusTransition);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::ReservationUpdateError)?;
+        Ok(())
+    }
+
+    async fn seat(
+        &self,
+        reservation: Reservation,
+        table: Table,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ReservationUpdateError)?;
+
+        let done = sqlx::query!(
+            r#"UPDATE reservations
+          SET status = ?, updated_at = CURRENT_TIMESTAMP
+          WHERE id = ? AND status = 'booked'"#,
+            reservation.status.as_str(),
+            reservation.id
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::ReservationUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::InvalidReservationStatusTransition);
+        }
+        seat_free_table(&mut tx, &table, now).await?;
+        record(&mut tx, &audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::ReservationUpdateError)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use crate::model::audit::AuditRecordRow;
+use crate::persistence::sqlite::SqliteDb;
+use api_core::{
+    domain::audit::{AuditFilter, AuditRecord},
+    error::Error,
+    repository::audit_repository::AuditRepository,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use sqlx::{Sqlite, Transaction};
+
+#[derive(new, Clone)]
+pub struct SqliteAuditRepositoryImpl {
+    db: SqliteDb,
+}
+
+/// Writes the audit record of a change as part of `tx`, the transaction
+/// making it, so that neither is kept without the other.
+pub(crate) async fn record(
+    tx: &mut Transaction<'_, Sqlite>,
+    record: &AuditRecord,
+) -> Result<u64, Error> {
+    let id = sqlx::query_scalar::<_, i64>(
+        r#"
+    INSERT INTO audit_records (actor_kind, actor_id, action, table_number, item_id, before_snapshot, after_snapshot)
+    VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+    RETURNING id
+            "#,
+    )
+    .bind(record.actor_kind.as_str())
+    .bind(record.actor_id)
+    .bind(record.action.as_str())
+    .bind(record.table_number)
+    .bind(record.item_id)
+    .bind(&record.before)
+    .bind(&record.after)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| Error::AuditInsertionError)?;
+    u64::try_from(id).map_err(|_| Error::AuditInsertionError)
+}
+
+#[async_trait]
+impl AuditRepository for SqliteAuditRepositoryImpl {
+    async fn append(&self, audit: AuditRecord) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::AuditInsertionError)?;
+        let id = record(&mut tx, &audit).await?;
+        tx.commit().await.map_err(|_| Error::AuditInsertionError)?;
+        Ok(id)
+    }
+
+    async fn find(&self, filter: AuditFilter) -> Result<Vec<AuditRecord>, Error> {
+        let actor_kind = filter.actor_kind.map(|kind| kind.as_str());
+        let result = sqlx::query_as::<_, AuditRecordRow>(
+            r#"SELECT id, actor_kind, actor_id, action, table_number, item_id, before_snapshot, after_snapshot, recorded_at
+          FROM audit_records
+          WHERE ($1 IS NULL OR table_number = $1)
+            AND ($2 IS NULL OR item_id = $2)
+            AND ($3 IS NULL OR actor_kind = $3)
+            AND ($4 IS NULL OR actor_id = $4)
+          ORDER BY id DESC
+          LIMIT $5"#,
+        )
+        .bind(filter.table_number)
+        .bind(filter.item_id)
+        .bind(actor_kind)
+        .bind(filter.actor_id)
+        .bind(filter.limit)
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => AuditRecordRow::from_rows(rows).map_err(|_| Error::AuditQueryError),
+            Err(_) => Err(Error::AuditQueryError),
+        }
+    }
+}
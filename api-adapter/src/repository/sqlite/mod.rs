@@ -0,0 +1,13 @@
+//! The repositories on SQLite. Like the PostgreSQL ones, their queries are
+//! checked at run time only.
+
+pub mod audit_repository_impl;
+pub mod device_repository_impl;
+pub mod idempotency_repository_impl;
+pub mod item_repository_impl;
+pub mod outbox_repository_impl;
+pub mod reservation_repository_impl;
+pub mod staff_repository_impl;
+pub mod table_repository_impl;
+pub mod waitlist_repository_impl;
+pub mod webhook_repository_impl;
@@ -0,0 +1,112 @@
+use crate::model::{outbox::OutboxEventRow, sqlite::SqliteOutboxEventRow};
+use crate::persistence::sqlite::SqliteDb;
+use api_core::{
+    domain::{event::DomainEvent, outbox::OutboxEntry},
+    error::Error,
+    repository::outbox_repository::OutboxRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+use sqlx::{Sqlite, Transaction};
+
+#[derive(new, Clone)]
+pub struct SqliteOutboxRepositoryImpl {
+    db: SqliteDb,
+}
+
+/// Stores `event` for the relay as part of `tx`, so that it is kept exactly
+/// when the change it describes is.
+pub(crate) async fn enqueue(
+    tx: &mut Transaction<'_, Sqlite>,
+    event: &DomainEvent,
+) -> Result<(), Error> {
+    let payload = serde_json::to_string(event).map_err(|_| Error::OutboxStoreError)?;
+    let occurred_at = event.occurred_at();
+    sqlx::query(
+        r#"
+    INSERT INTO outbox_events (event_type, payload, occurred_at, next_attempt_at)
+    VALUES ( $1, $2, $3, $4 )
+            "#,
+    )
+    .bind(event.name())
+    .bind(payload)
+    .bind(occurred_at)
+    .bind(occurred_at)
+    .execute(&mut *tx)
+    .await
+    .map_err(|_| Error::OutboxStoreError)?;
+    Ok(())
+}
+
+#[async_trait]
+impl OutboxRepository for SqliteOutboxRepositoryImpl {
+    async fn find_pending(
+        &self,
+        now: NaiveDateTime,
+        limit: u32,
+    ) -> Result<Vec<OutboxEntry>, Error> {
+        let result = sqlx::query_as::<_, SqliteOutboxEventRow>(
+            r#"SELECT id, payload, attempts, handled_by, next_attempt_at
+          FROM outbox_events
+          WHERE delivered_at IS NULL AND next_attempt_at <= $1
+          ORDER BY id
+          LIMIT $2"#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(OutboxEventRow::from_rows(
+                rows.into_iter().map(OutboxEventRow::from).collect(),
+            )),
+            Err(_) => Err(Error::OutboxStoreError),
+        }
+    }
+
+    async fn mark_delivered(&self, id: u64, now: NaiveDateTime) -> Result<(), Error> {
+        let id = i64::try_from(id).map_err(|_| Error::OutboxStoreError)?;
+        let result = sqlx::query(
+            r#"UPDATE outbox_events
+          SET delivered_at = $1, last_error = NULL
+          WHERE id = $2"#,
+        )
+        .bind(now)
+        .bind(id)
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Error::OutboxStoreError),
+        }
+    }
+
+    async fn mark_failed(
+        &self,
+        id: u64,
+        error: String,
+        handled_by: &[String],
+        next_attempt_at: NaiveDateTime,
+    ) -> Result<(), Error> {
+        let id = i64::try_from(id).map_err(|_| Error::OutboxStoreError)?;
+        let result = sqlx::query(
+            r#"UPDATE outbox_events
+          SET attempts = attempts + 1, last_error = $1, handled_by = $2, next_attempt_at = $3
+          WHERE id = $4"#,
+        )
+        .bind(error)
+        .bind(handled_by.join(","))
+        .bind(next_attempt_at)
+        .bind(id)
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Error::OutboxStoreError),
+        }
+    }
+}
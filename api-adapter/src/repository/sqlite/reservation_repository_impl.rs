@@ -0,0 +1,246 @@
+use crate::model::{reservation::ReservationRow, table::TableRow};
+use crate::persistence::sqlite::SqliteDb;
+use crate::repository::sqlite::audit_repository_impl::record;
+use crate::repository::sqlite::table_repository_impl::seat_free_table;
+use api_core::{
+    domain::{
+        audit::AuditRecord,
+        reservation::{Reservation, ReservationStatus},
+        table::Table,
+    },
+    error::Error,
+    repository::reservation_repository::ReservationRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+use sqlx::{Sqlite, Transaction};
+
+#[derive(new, Clone)]
+pub struct SqliteReservationRepositoryImpl {
+    db: SqliteDb,
+}
+
+/// Locks the reservation's table for the rest of `tx`, then checks that the
+/// party fits it and that no other booking for it overlaps.
+async fn ensure_bookable(
+    tx: &mut Transaction<'_, Sqlite>,
+    reservation: &Reservation,
+) -> Result<(), Error> {
+    // SQLite has no row locks; writing first takes the database's write lock
+    // for the rest of the transaction instead.
+    sqlx::query("UPDATE tables SET updated_at = updated_at WHERE number = $1")
+        .bind(reservation.table_number)
+        .execute(&mut *tx)
+        .await
+        .map_err(|_| Error::TableNotFoundError)?;
+    let row = sqlx::query_as::<_, TableRow>(
+        r#"SELECT number, capacity, zone, status, seated_at, created_at, updated_at
+          FROM tables
+          WHERE number = $1"#,
+    )
+    .bind(reservation.table_number)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|_| Error::TableNotFoundError)?;
+    let table = TableRow::from_row(row).map_err(|_| Error::InvalidStoredRecord)?;
+    if !reservation.fits(&table) {
+        return Err(Error::TableCapacityExceeded);
+    }
+
+    let rows = sqlx::query_as::<_, ReservationRow>(
+        r#"SELECT id, guest_name, guest_phone, party_size, table_number, starts_at, ends_at, status,
+          created_at, updated_at
+          FROM reservations
+          WHERE table_number = $1 AND status = 'booked'"#,
+    )
+    .bind(reservation.table_number)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(|_| Error::ReservationNotFoundError)?;
+    let booked = ReservationRow::from_rows(rows).map_err(|_| Error::InvalidStoredRecord)?;
+    if booked.iter().any(|other| reservation.conflicts_with(other)) {
+        return Err(Error::ReservationConflict);
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl ReservationRepository for SqliteReservationRepositoryImpl {
+    async fn find_one(&self, reservation_id: u32) -> Result<Reservation, Error> {
+        let result = sqlx::query_as::<_, ReservationRow>(
+            r#"SELECT id, guest_name, guest_phone, party_size, table_number, starts_at, ends_at, status,
+          created_at, updated_at
+          FROM reservations
+          WHERE id = $1"#,
+        )
+        .bind(reservation_id)
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => ReservationRow::from_row(row).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::ReservationNotFoundError),
+        }
+    }
+
+    async fn find_by_table(&self, table_number: u32) -> Result<Vec<Reservation>, Error> {
+        let result = sqlx::query_as::<_, ReservationRow>(
+            r#"SELECT id, guest_name, guest_phone, party_size, table_number, starts_at, ends_at, status,
+          created_at, updated_at
+          FROM reservations
+          WHERE table_number = $1
+          ORDER BY starts_at"#,
+        )
+        .bind(table_number)
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => ReservationRow::from_rows(rows).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::ReservationNotFoundError),
+        }
+    }
+
+    async fn find_booked_between(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<Vec<Reservation>, Error> {
+        let result = sqlx::query_as::<_, ReservationRow>(
+            r#"SELECT id, guest_name, guest_phone, party_size, table_number, starts_at, ends_at, status,
+          created_at, updated_at
+          FROM reservations
+          WHERE status = 'booked' AND starts_at < $1 AND ends_at > $2
+          ORDER BY starts_at"#,
+        )
+        .bind(until)
+        .bind(from)
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => ReservationRow::from_rows(rows).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::ReservationNotFoundError),
+        }
+    }
+
+    async fn create(
+        &self,
+        mut reservation: Reservation,
+        mut audit: AuditRecord,
+    ) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ReservationInsertionError)?;
+        ensure_bookable(&mut tx, &reservation).await?;
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+    INSERT INTO reservations (guest_name, guest_phone, party_size, table_number, starts_at, ends_at, status)
+    VALUES ( $1, $2, $3, $4, $5, $6, $7 )
+    RETURNING id
+            "#,
+        )
+        .bind(&reservation.guest_name)
+        .bind(&reservation.guest_phone)
+        .bind(reservation.party_size)
+        .bind(reservation.table_number)
+        .bind(reservation.starts_at)
+        .bind(reservation.ends_at)
+        .bind(reservation.status.as_str())
+        .fetch_one(&mut tx)
+        .await
+        .map_err(|_| Error::ReservationInsertionError)?;
+        let reservation_id = u64::try_from(id).map_err(|_| Error::ReservationInsertionError)?;
+
+        reservation.id = u32::try_from(reservation_id).ok();
+        audit.after = serde_json::to_string(&reservation).ok();
+        record(&mut tx, &audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::ReservationInsertionError)?;
+        Ok(reservation_id)
+    }
+
+    async fn update(&self, reservation: Reservation, audit: AuditRecord) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ReservationUpdateError)?;
+        if reservation.status == ReservationStatus::Booked {
+            ensure_bookable(&mut tx, &reservation).await?;
+        }
+
+        let done = sqlx::query(
+            r#"UPDATE reservations
+          SET guest_name = $1, guest_phone = $2, party_size = $3, table_number = $4, starts_at = $5, ends_at = $6,
+              status = $7, updated_at = CURRENT_TIMESTAMP
+          WHERE id = $8 AND status = 'booked'"#,
+        )
+        .bind(reservation.guest_name)
+        .bind(reservation.guest_phone)
+        .bind(reservation.party_size)
+        .bind(reservation.table_number)
+        .bind(reservation.starts_at)
+        .bind(reservation.ends_at)
+        .bind(reservation.status.as_str())
+        .bind(reservation.id)
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::ReservationUpdateError)?;
+        // only a booked reservation is changed, so a seat that got in first
+        // is not undone
+        if done.rows_affected() == 0 {
+            return Err(Error::InvalidReservationStatusTransition);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::ReservationUpdateError)?;
+        Ok(())
+    }
+
+    async fn seat(
+        &self,
+        reservation: Reservation,
+        table: Table,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::ReservationUpdateError)?;
+
+        let done = sqlx::query(
+            r#"UPDATE reservations
+          SET status = $1, updated_at = CURRENT_TIMESTAMP
+          WHERE id = $2 AND status = 'booked'"#,
+        )
+        .bind(reservation.status.as_str())
+        .bind(reservation.id)
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::ReservationUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::InvalidReservationStatusTransition);
+        }
+        seat_free_table(&mut tx, &table, now).await?;
+        record(&mut tx, &audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::ReservationUpdateError)?;
+        Ok(())
+    }
+}
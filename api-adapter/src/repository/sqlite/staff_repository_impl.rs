@@ -0,0 +1,137 @@
+use crate::model::staff::StaffRow;
+use crate::persistence::sqlite::SqliteDb;
+use crate::repository::sqlite::audit_repository_impl::record;
+use api_core::{
+    domain::{audit::AuditRecord, staff::Staff},
+    error::Error,
+    repository::staff_repository::StaffRepository,
+};
+use async_trait::async_trait;
+use derive_new::new;
+
+#[derive(new, Clone)]
+pub struct SqliteStaffRepositoryImpl {
+    db: SqliteDb,
+}
+
+#[async_trait]
+impl StaffRepository for SqliteStaffRepositoryImpl {
+    async fn find_all(&self) -> Result<Vec<Staff>, Error> {
+        let result = sqlx::query_as::<_, StaffRow>(
+            r#"SELECT id, name, role, active, password_hash, created_at, updated_at
+          FROM staff
+          ORDER BY id"#,
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(StaffRow::from_rows(rows)),
+            Err(_) => Err(Error::StaffNotFoundError),
+        }
+    }
+
+    async fn find_one(&self, staff_id: u32) -> Result<Staff, Error> {
+        let result = sqlx::query_as::<_, StaffRow>(
+            r#"SELECT id, name, role, active, password_hash, created_at, updated_at
+          FROM staff
+          WHERE id = $1"#,
+        )
+        .bind(staff_id)
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(StaffRow::from_row(row)),
+            Err(_) => Err(Error::StaffNotFoundError),
+        }
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Staff, Error> {
+        let result = sqlx::query_as::<_, StaffRow>(
+            r#"SELECT id, name, role, active, password_hash, created_at, updated_at
+          FROM staff
+          WHERE name = $1"#,
+        )
+        .bind(name)
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(StaffRow::from_row(row)),
+            Err(_) => Err(Error::StaffNotFoundError),
+        }
+    }
+
+    async fn create(&self, mut staff: Staff, mut audit: AuditRecord) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::StaffInsertionError)?;
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+    INSERT INTO staff (name, role, active, password_hash)
+    VALUES ( $1, $2, $3, $4 )
+    RETURNING id
+            "#,
+        )
+        .bind(&staff.name)
+        .bind(staff.role.as_str())
+        .bind(staff.active)
+        .bind(&staff.password_hash)
+        .fetch_one(&mut tx)
+        .await
+        .map_err(|e| name_taken_or(e, Error::StaffInsertionError))?;
+        let staff_id = u64::try_from(id).map_err(|_| Error::StaffInsertionError)?;
+
+        staff.id = u32::try_from(staff_id).ok();
+        audit.after = serde_json::to_string(&staff).ok();
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::StaffInsertionError)?;
+        Ok(staff_id)
+    }
+
+    async fn update(&self, staff: Staff, audit: AuditRecord) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::StaffUpdateError)?;
+
+        let done = sqlx::query(
+            r#"UPDATE staff
+          SET name = $1, role = $2, active = $3, password_hash = $4, updated_at = CURRENT_TIMESTAMP
+          WHERE id = $5"#,
+        )
+        .bind(staff.name)
+        .bind(staff.role.as_str())
+        .bind(staff.active)
+        .bind(staff.password_hash)
+        .bind(staff.id)
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::StaffUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::StaffNotFoundError);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::StaffUpdateError)?;
+        Ok(())
+    }
+}
+
+/// Staff names are unique; the key on them rejects a taken one.
+fn name_taken_or(e: sqlx::Error, otherwise: Error) -> Error {
+    match e {
+        sqlx::Error::Database(e) if e.message().starts_with("UNIQUE constraint failed") => {
+            Error::StaffNameTaken
+        }
+        _ => otherwise,
+    }
+}
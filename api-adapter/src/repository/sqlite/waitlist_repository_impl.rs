@@ -0,0 +1,170 @@
+use crate::model::waitlist::WaitlistEntryRow;
+use crate::persistence::sqlite::SqliteDb;
+use crate::repository::sqlite::audit_repository_impl::record;
+use crate::repository::sqlite::outbox_repository_impl::enqueue;
+use crate::repository::sqlite::table_repository_impl::seat_free_table;
+use api_core::{
+    domain::{audit::AuditRecord, event::DomainEvent, table::Table, waitlist::WaitlistEntry},
+    error::Error,
+    repository::waitlist_repository::WaitlistRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+
+#[derive(new, Clone)]
+pub struct SqliteWaitlistRepositoryImpl {
+    db: SqliteDb,
+}
+
+#[async_trait]
+impl WaitlistRepository for SqliteWaitlistRepositoryImpl {
+    async fn find_one(&self, entry_id: u32) -> Result<WaitlistEntry, Error> {
+        let result = sqlx::query_as::<_, WaitlistEntryRow>(
+            r#"SELECT id, guest_name, guest_phone, party_size, quoted_wait_minutes, status, joined_at, updated_at
+          FROM waitlist_entries
+          WHERE id = $1"#,
+        )
+        .bind(entry_id)
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => WaitlistEntryRow::from_row(row).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::WaitlistEntryNotFoundError),
+        }
+    }
+
+    async fn find_waiting(&self) -> Result<Vec<WaitlistEntry>, Error> {
+        let result = sqlx::query_as::<_, WaitlistEntryRow>(
+            r#"SELECT id, guest_name, guest_phone, party_size, quoted_wait_minutes, status, joined_at, updated_at
+          FROM waitlist_entries
+          WHERE status = 'waiting'
+          ORDER BY joined_at, id"#,
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => WaitlistEntryRow::from_rows(rows).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::WaitlistEntryNotFoundError),
+        }
+    }
+
+    async fn create(&self, mut entry: WaitlistEntry, mut audit: AuditRecord) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WaitlistEntryInsertionError)?;
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+    INSERT INTO waitlist_entries (guest_name, guest_phone, party_size, quoted_wait_minutes, status, joined_at)
+    VALUES ( $1, $2, $3, $4, $5, $6 )
+    RETURNING id
+            "#,
+        )
+        .bind(&entry.guest_name)
+        .bind(&entry.guest_phone)
+        .bind(entry.party_size)
+        .bind(entry.quoted_wait_minutes)
+        .bind(entry.status.as_str())
+        .bind(entry.joined_at)
+        .fetch_one(&mut tx)
+        .await
+        .map_err(|_| Error::WaitlistEntryInsertionError)?;
+        let entry_id = u64::try_from(id).map_err(|_| Error::WaitlistEntryInsertionError)?;
+
+        entry.id = u32::try_from(entry_id).ok();
+        audit.after = serde_json::to_string(&entry).ok();
+        record(&mut tx, &audit).await?;
+
+        let event = DomainEvent::WaitlistJoined {
+            entry_id: u32::try_from(entry_id).map_err(|_| Error::WaitlistEntryInsertionError)?,
+            occurred_at: entry.joined_at,
+        };
+        enqueue(&mut tx, &event).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::WaitlistEntryInsertionError)?;
+        Ok(entry_id)
+    }
+
+    async fn update(&self, entry: WaitlistEntry, audit: AuditRecord) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WaitlistEntryUpdateError)?;
+
+        let done = sqlx::query(
+            r#"UPDATE waitlist_entries
+          SET party_size = $1, quoted_wait_minutes = $2, status = $3, updated_at = CURRENT_TIMESTAMP
+          WHERE id = $4"#,
+        )
+        .bind(entry.party_size)
+        .bind(entry.quoted_wait_minutes)
+        .bind(entry.status.as_str())
+        .bind(entry.id)
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::WaitlistEntryUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::WaitlistEntryNotFoundError);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::WaitlistEntryUpdateError)?;
+        Ok(())
+    }
+
+    async fn seat(
+        &self,
+        entry: WaitlistEntry,
+        table: Table,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let entry_id = entry.id.ok_or(Error::WaitlistEntryNotFoundError)?;
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WaitlistEntryUpdateError)?;
+
+        let done = sqlx::query(
+            r#"UPDATE waitlist_entries
+          SET status = $1, updated_at = CURRENT_TIMESTAMP
+          WHERE id = $2 AND status = 'waiting'"#,
+        )
+        .bind(entry.status.as_str())
+        .bind(entry_id)
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::WaitlistEntryUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::InvalidWaitlistStatusTransition);
+        }
+        seat_free_table(&mut tx, &table, now).await?;
+
+        let event = DomainEvent::WaitlistCalled {
+            entry_id,
+            table_number: table.number,
+            occurred_at: now,
+        };
+        enqueue(&mut tx, &event).await?;
+        record(&mut tx, &audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::WaitlistEntryUpdateError)?;
+        Ok(())
+    }
+}
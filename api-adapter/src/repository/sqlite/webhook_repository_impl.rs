@@ -0,0 +1,280 @@
+use crate::model::{
+    sqlite::SqliteWebhookDeliveryRow,
+    webhook::{WebhookDeliveryRow, WebhookSubscriptionRow},
+};
+use crate::persistence::sqlite::SqliteDb;
+use crate::repository::sqlite::audit_repository_impl::record;
+use api_core::{
+    domain::{
+        audit::AuditRecord,
+        webhook::{WebhookDelivery, WebhookSubscription},
+    },
+    error::Error,
+    repository::webhook_repository::WebhookRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+
+#[derive(new, Clone)]
+pub struct SqliteWebhookRepositoryImpl {
+    db: SqliteDb,
+}
+
+#[async_trait]
+impl WebhookRepository for SqliteWebhookRepositoryImpl {
+    async fn find_all(&self) -> Result<Vec<WebhookSubscription>, Error> {
+        let result = sqlx::query_as::<_, WebhookSubscriptionRow>(
+            r#"SELECT id, url, secret, event_types, active, consecutive_failures, created_at, updated_at
+          FROM webhook_subscriptions
+          ORDER BY id"#,
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(WebhookSubscriptionRow::from_rows(rows)),
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+
+    async fn find_one(&self, subscription_id: u32) -> Result<WebhookSubscription, Error> {
+        let result = sqlx::query_as::<_, WebhookSubscriptionRow>(
+            r#"SELECT id, url, secret, event_types, active, consecutive_failures, created_at, updated_at
+          FROM webhook_subscriptions
+          WHERE id = $1"#,
+        )
+        .bind(subscription_id)
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(WebhookSubscriptionRow::from_row(row)),
+            Err(_) => Err(Error::WebhookNotFoundError),
+        }
+    }
+
+    async fn create(
+        &self,
+        mut subscription: WebhookSubscription,
+        mut audit: AuditRecord,
+    ) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WebhookStoreError)?;
+
+        let id = sqlx::query_scalar::<_, i64>(
+            r#"
+    INSERT INTO webhook_subscriptions (url, secret, event_types, active)
+    VALUES ( $1, $2, $3, $4 )
+    RETURNING id
+            "#,
+        )
+        .bind(&subscription.url)
+        .bind(&subscription.secret)
+        .bind(subscription.event_types.join(","))
+        .bind(subscription.active)
+        .fetch_one(&mut tx)
+        .await
+        .map_err(|_| Error::WebhookStoreError)?;
+        let subscription_id = u64::try_from(id).map_err(|_| Error::WebhookStoreError)?;
+
+        subscription.id = u32::try_from(subscription_id).ok();
+        audit.after = serde_json::to_string(&subscription).ok();
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::WebhookStoreError)?;
+        Ok(subscription_id)
+    }
+
+    async fn update(
+        &self,
+        subscription: WebhookSubscription,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WebhookStoreError)?;
+
+        let done = sqlx::query(
+            r#"UPDATE webhook_subscriptions
+          SET url = $1, event_types = $2, active = $3, consecutive_failures = $4, updated_at = CURRENT_TIMESTAMP
+          WHERE id = $5"#,
+        )
+        .bind(subscription.url)
+        .bind(subscription.event_types.join(","))
+        .bind(subscription.active)
+        .bind(subscription.consecutive_failures)
+        .bind(subscription.id)
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::WebhookStoreError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::WebhookNotFoundError);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::WebhookStoreError)?;
+        Ok(())
+    }
+
+    async fn update_failures(&self, subscription: WebhookSubscription) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"UPDATE webhook_subscriptions
+          SET consecutive_failures = $1, updated_at = CURRENT_TIMESTAMP
+          WHERE id = $2"#,
+        )
+        .bind(subscription.consecutive_failures)
+        .bind(subscription.id)
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(done) if done.rows_affected() > 0 => Ok(()),
+            Ok(_) => Err(Error::WebhookNotFoundError),
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+
+    async fn delete(&self, subscription_id: u32, audit: AuditRecord) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WebhookStoreError)?;
+
+        sqlx::query("DELETE FROM webhook_deliveries WHERE subscription_id = $1")
+            .bind(subscription_id)
+            .execute(&mut tx)
+            .await
+            .map_err(|_| Error::WebhookStoreError)?;
+        let done = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1")
+            .bind(subscription_id)
+            .execute(&mut tx)
+            .await
+            .map_err(|_| Error::WebhookStoreError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::WebhookNotFoundError);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::WebhookStoreError)?;
+        Ok(())
+    }
+
+    async fn create_delivery(
+        &self,
+        event_id: u64,
+        delivery: WebhookDelivery,
+    ) -> Result<Option<u64>, Error> {
+        let result = sqlx::query_scalar::<_, i64>(
+            r#"
+    INSERT INTO webhook_deliveries (event_id, subscription_id, event_type, payload, status, next_attempt_at)
+    VALUES ( $1, $2, $3, $4, $5, $6 )
+    ON CONFLICT (event_id, subscription_id) DO NOTHING
+    RETURNING id
+            "#,
+        )
+        .bind(i64::try_from(event_id).map_err(|_| Error::WebhookStoreError)?)
+        .bind(delivery.subscription_id)
+        .bind(delivery.event_type)
+        .bind(delivery.payload)
+        .bind(delivery.status.as_str())
+        .bind(delivery.next_attempt_at)
+        .fetch_optional(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(id) => id
+                .map(|id| u64::try_from(id).map_err(|_| Error::WebhookStoreError))
+                .transpose(),
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+
+    async fn update_delivery(&self, delivery: WebhookDelivery) -> Result<(), Error> {
+        let id = delivery
+            .id
+            .map(i64::try_from)
+            .transpose()
+            .map_err(|_| Error::WebhookStoreError)?;
+        let result = sqlx::query(
+            r#"UPDATE webhook_deliveries
+          SET status = $1, attempts = $2, next_attempt_at = $3, response_status = $4, last_error = $5, delivered_at = $6
+          WHERE id = $7"#,
+        )
+        .bind(delivery.status.as_str())
+        .bind(delivery.attempts)
+        .bind(delivery.next_attempt_at)
+        .bind(delivery.response_status)
+        .bind(delivery.last_error)
+        .bind(delivery.delivered_at)
+        .bind(id)
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+
+    async fn find_due_deliveries(
+        &self,
+        now: NaiveDateTime,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        let result = sqlx::query_as::<_, SqliteWebhookDeliveryRow>(
+            r#"SELECT id, subscription_id, event_type, payload, status, attempts, next_attempt_at,
+          response_status, last_error, delivered_at
+          FROM webhook_deliveries
+          WHERE status = 'pending' AND next_attempt_at <= $1
+          ORDER BY id
+          LIMIT $2"#,
+        )
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(WebhookDeliveryRow::from_rows(
+                rows.into_iter().map(WebhookDeliveryRow::from).collect(),
+            )),
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+
+    async fn find_deliveries(
+        &self,
+        subscription_id: u32,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        let result = sqlx::query_as::<_, SqliteWebhookDeliveryRow>(
+            r#"SELECT id, subscription_id, event_type, payload, status, attempts, next_attempt_at,
+          response_status, last_error, delivered_at
+          FROM webhook_deliveries
+          WHERE subscription_id = $1
+          ORDER BY id DESC
+          LIMIT $2"#,
+        )
+        .bind(subscription_id)
+        .bind(limit)
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(WebhookDeliveryRow::from_rows(
+                rows.into_iter().map(WebhookDeliveryRow::from).collect(),
+            )),
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+}
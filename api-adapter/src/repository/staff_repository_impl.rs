@@ -0,0 +1,145 @@
+use crate::model::staff::StaffRow;
+use crate::persistence::mysql::Db;
+use crate::repository::audit_repository_impl::record;
+use api_core::{
+    domain::{audit::AuditRecord, staff::Staff},
+    error::Error,
+    repository::staff_repository::StaffRepository,
+};
+use async_trait::async_trait;
+use derive_new::new;
+use sqlx::mysql::MySqlDatabaseError;
+
+#[derive(new, Clone)]
+pub struct StaffRepositoryImpl {
+    db: Db,
+}
+
+#[async_trait]
+impl StaffRepository for StaffRepositoryImpl {
+    async fn find_all(&self) -> Result<Vec<Staff>, Error> {
+        let result = sqlx::query_as!(
+            StaffRow,
+            r#"SELECT id, name, role, active as `active: bool`, password_hash, created_at, updated_at
+          FROM staff
+          ORDER BY id"#
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(StaffRow::from_rows(rows)),
+            Err(_) => Err(Error::StaffNotFoundError),
+        }
+    }
+
+    async fn find_one(&self, staff_id: u32) -> Result<Staff, Error> {
+        let result = sqlx::query_as!(
+            StaffRow,
+            r#"SELECT id, name, role, active as `active: bool`, password_hash, created_at, updated_at
+          FROM staff
+          WHERE id = ?"#,
+            staff_id
+        )
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(StaffRow::from_row(row)),
+            Err(_) => Err(Error::StaffNotFoundError),
+        }
+    }
+
+    async fn find_by_name(&self, name: &str) -> Result<Staff, Error> {
+        let result = sqlx::query_as!(
+            StaffRow,
+            r#"SELECT id, name, role, active as `active: bool`, password_hash, created_at, updated_at
+          FROM staff
+          WHERE name = ?"#,
+            name
+        )
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(StaffRow::from_row(row)),
+            Err(_) => Err(Error::StaffNotFoundError),
+        }
+    }
+
+    async fn create(&self, mut staff: Staff, mut audit: AuditRecord) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::StaffInsertionError)?;
+
+        let done = sqlx::query!(
+            r#"
+    INSERT INTO staff (name, role, active, password_hash)
+    VALUES ( ?, ?, ?, ? )
+            "#,
+            staff.name.as_str(),
+            staff.role.as_str(),
+            staff.active,
+            staff.password_hash.as_deref()
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|e| name_taken_or(e, Error::StaffInsertionError))?;
+        let staff_id = done.last_insert_id();
+
+        staff.id = u32::try_from(staff_id).ok();
+        audit.after = serde_json::to_string(&staff).ok();
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::StaffInsertionError)?;
+        Ok(staff_id)
+    }
+
+    async fn update(&self, staff: Staff, audit: AuditRecord) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::StaffUpdateError)?;
+
+        let done = sqlx::query!(
+            r#"UPDATE staff
+          SET name = ?, role = ?, active = ?, password_hash = ?, updated_at = CURRENT_TIMESTAMP
+          WHERE id = ?"#,
+            staff.name,
+            staff.role.as_str(),
+            staff.active,
+            staff.password_hash,
+            staff.id
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::StaffUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::StaffNotFoundError);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::StaffUpdateError)?;
+        Ok(())
+    }
+}
+
+/// Staff names are unique; the key on them rejects a taken one with MySQL's
+/// `ER_DUP_ENTRY`.
+fn name_taken_or(e: sqlx::Error, otherwise: Error) -> Error {
+    match e {
+        sqlx::Error::Database(e)
+            if e.try_downcast_ref::<MySqlDatabaseError>()
+                .map(|e| e.number())
+                == Some(1062) =>
+        {
+            Error::StaffNameTaken
+        }
+        _ => otherwise,
+    }
+}
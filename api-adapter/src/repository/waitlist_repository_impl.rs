@@ -0,0 +1,171 @@
+use crate::model::waitlist::WaitlistEntryRow;
+use crate::persistence::mysql::Db;
+use crate::repository::audit_repository_impl::record;
+use crate::repository::outbox_repository_impl::enqueue;
+use crate::repository::table_repository_impl::seat_free_table;
+use api_core::{
+    domain::{audit::AuditRecord, event::DomainEvent, table::Table, waitlist::WaitlistEntry},
+    error::Error,
+    repository::waitlist_repository::WaitlistRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+
+#[derive(new, Clone)]
+pub struct WaitlistRepositoryImpl {
+    db: Db,
+}
+
+#[async_trait]
+impl WaitlistRepository for WaitlistRepositoryImpl {
+    async fn find_one(&self, entry_id: u32) -> Result<WaitlistEntry, Error> {
+        let result = sqlx::query_as!(
+            WaitlistEntryRow,
+            r#"SELECT id, guest_name, guest_phone, party_size, quoted_wait_minutes, status, joined_at, updated_at
+          FROM waitlist_entries
+          WHERE id = ?"#,
+            entry_id
+        )
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => WaitlistEntryRow::from_row(row).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::WaitlistEntryNotFoundError),
+        }
+    }
+
+    async fn find_waiting(&self) -> Result<Vec<WaitlistEntry>, Error> {
+        let result = sqlx::query_as!(
+            WaitlistEntryRow,
+            r#"SELECT id, guest_name, guest_phone, party_size, quoted_wait_minutes, status, joined_at, updated_at
+          FROM waitlist_entries
+          WHERE status = 'waiting'
+          ORDER BY joined_at, id"#
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => WaitlistEntryRow::from_rows(rows).map_err(|_| Error::InvalidStoredRecord),
+            Err(_) => Err(Error::WaitlistEntryNotFoundError),
+        }
+    }
+
+    async fn create(&self, mut entry: WaitlistEntry, mut audit: AuditRecord) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WaitlistEntryInsertionError)?;
+
+        let done = sqlx::query!(
+            r#"
+    INSERT INTO waitlist_entries (guest_name, guest_phone, party_size, quoted_wait_minutes, status, joined_at)
+    VALUES ( ?, ?, ?, ?, ?, ? )
+            "#,
+            entry.guest_name.as_str(),
+            entry.guest_phone.as_str(),
+            entry.party_size,
+            entry.quoted_wait_minutes,
+            entry.status.as_str(),
+            entry.joined_at
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::WaitlistEntryInsertionError)?;
+        let entry_id = done.last_insert_id();
+
+        entry.id = u32::try_from(entry_id).ok();
+        audit.after = serde_json::to_string(&entry).ok();
+        record(&mut tx, &audit).await?;
+
+        let event = DomainEvent::WaitlistJoined {
+            entry_id: u32::try_from(entry_id).map_err(|_| Error::WaitlistEntryInsertionError)?,
+            occurred_at: entry.joined_at,
+        };
+        enqueue(&mut tx, &event).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::WaitlistEntryInsertionError)?;
+        Ok(entry_id)
+    }
+
+    async fn update(&self, entry: WaitlistEntry, audit: AuditRecord) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WaitlistEntryUpdateError)?;
+
+        let done = sqlx::query!(
+            r#"UPDATE waitlist_entries
+          SET party_size = ?, quoted_wait_minutes = ?, status = ?, updated_at = CURRENT_TIMESTAMP
+          WHERE id = ?"#,
+            entry.party_size,
+            entry.quoted_wait_minutes,
+            entry.status.as_str(),
+            entry.id
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::WaitlistEntryUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::WaitlistEntryNotFoundError);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::WaitlistEntryUpdateError)?;
+        Ok(())
+    }
+
+    async fn seat(
+        &self,
+        entry: WaitlistEntry,
+        table: Table,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let entry_id = entry.id.ok_or(Error::WaitlistEntryNotFoundError)?;
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WaitlistEntryUpdateError)?;
+
+        let done = sqlx::query!(
+            r#"UPDATE waitlist_entries
+          SET status = ?, updated_at = CURRENT_TIMESTAMP
+          WHERE id = ? AND status = 'waiting'"#,
+            entry.status.as_str(),
+            entry_id
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::WaitlistEntryUpdateError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::InvalidWaitlistStatusTransition);
+        }
+        seat_free_table(&mut tx, &table, now).await?;
+
+        let event = DomainEvent::WaitlistCalled {
+            entry_id,
+            table_number: table.number,
+            occurred_at: now,
+        };
+        enqueue(&mut tx, &event).await?;
+        record(&mut tx, &audit).await?;
+
+        tx.commit()
+            .await
+            .map_err(|_| Error::WaitlistEntryUpdateError)?;
+        Ok(())
+    }
+}
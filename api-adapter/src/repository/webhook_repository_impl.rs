@@ -0,0 +1,280 @@
+use crate::model::webhook::{WebhookDeliveryRow, WebhookSubscriptionRow};
+use crate::persistence::mysql::Db;
+use crate::repository::audit_repository_impl::record;
+use api_core::{
+    domain::{
+        audit::AuditRecord,
+        webhook::{WebhookDelivery, WebhookSubscription},
+    },
+    error::Error,
+    repository::webhook_repository::WebhookRepository,
+};
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use derive_new::new;
+use sqlx::mysql::MySqlDatabaseError;
+
+#[derive(new, Clone)]
+pub struct WebhookRepositoryImpl {
+    db: Db,
+}
+
+#[async_trait]
+impl WebhookRepository for WebhookRepositoryImpl {
+    async fn find_all(&self) -> Result<Vec<WebhookSubscription>, Error> {
+        let result = sqlx::query_as!(
+            WebhookSubscriptionRow,
+            r#"SELECT id, url, secret, event_types, active as `active: bool`, consecutive_failures, created_at, updated_at
+          FROM webhook_subscriptions
+          ORDER BY id"#
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(WebhookSubscriptionRow::from_rows(rows)),
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+
+    async fn find_one(&self, subscription_id: u32) -> Result<WebhookSubscription, Error> {
+        let result = sqlx::query_as!(
+            WebhookSubscriptionRow,
+            r#"SELECT id, url, secret, event_types, active as `active: bool`, consecutive_failures, created_at, updated_at
+          FROM webhook_subscriptions
+          WHERE id = ?"#,
+            subscription_id
+        )
+        .fetch_one(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(row) => Ok(WebhookSubscriptionRow::from_row(row)),
+            Err(_) => Err(Error::WebhookNotFoundError),
+        }
+    }
+
+    async fn create(
+        &self,
+        mut subscription: WebhookSubscription,
+        mut audit: AuditRecord,
+    ) -> Result<u64, Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WebhookStoreError)?;
+
+        let done = sqlx::query!(
+            r#"
+    INSERT INTO webhook_subscriptions (url, secret, event_types, active)
+    VALUES ( ?, ?, ?, ? )
+            "#,
+            subscription.url.as_str(),
+            subscription.secret.as_str(),
+            subscription.event_types.join(","),
+            subscription.active
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::WebhookStoreError)?;
+        let subscription_id = done.last_insert_id();
+
+        subscription.id = u32::try_from(subscription_id).ok();
+        audit.after = serde_json::to_string(&subscription).ok();
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::WebhookStoreError)?;
+        Ok(subscription_id)
+    }
+
+    async fn update(
+        &self,
+        subscription: WebhookSubscription,
+        audit: AuditRecord,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WebhookStoreError)?;
+
+        let done = sqlx::query!(
+            r#"UPDATE webhook_subscriptions
+          SET url = ?, event_types = ?, active = ?, consecutive_failures = ?, updated_at = CURRENT_TIMESTAMP
+          WHERE id = ?"#,
+            subscription.url,
+            subscription.event_types.join(","),
+            subscription.active,
+            subscription.consecutive_failures,
+            subscription.id
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::WebhookStoreError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::WebhookNotFoundError);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::WebhookStoreError)?;
+        Ok(())
+    }
+
+    async fn update_failures(&self, subscription: WebhookSubscription) -> Result<(), Error> {
+        let result = sqlx::query!(
+            r#"UPDATE webhook_subscriptions
+          SET consecutive_failures = ?, updated_at = CURRENT_TIMESTAMP
+          WHERE id = ?"#,
+            subscription.consecutive_failures,
+            subscription.id
+        )
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(done) if done.rows_affected() > 0 => Ok(()),
+            Ok(_) => Err(Error::WebhookNotFoundError),
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+
+    async fn delete(&self, subscription_id: u32, audit: AuditRecord) -> Result<(), Error> {
+        let mut tx = self
+            .db
+            .pool
+            .begin()
+            .await
+            .map_err(|_| Error::WebhookStoreError)?;
+
+        sqlx::query!(
+            "DELETE FROM webhook_deliveries WHERE subscription_id = ?",
+            subscription_id
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::WebhookStoreError)?;
+        let done = sqlx::query!(
+            "DELETE FROM webhook_subscriptions WHERE id = ?",
+            subscription_id
+        )
+        .execute(&mut tx)
+        .await
+        .map_err(|_| Error::WebhookStoreError)?;
+        if done.rows_affected() == 0 {
+            return Err(Error::WebhookNotFoundError);
+        }
+        record(&mut tx, &audit).await?;
+
+        tx.commit().await.map_err(|_| Error::WebhookStoreError)?;
+        Ok(())
+    }
+
+    async fn create_delivery(
+        &self,
+        event_id: u64,
+        delivery: WebhookDelivery,
+    ) -> Result<Option<u64>, Error> {
+        let result = sqlx::query!(
+            r#"
+    INSERT INTO webhook_deliveries (event_id, subscription_id, event_type, payload, status, next_attempt_at)
+    VALUES ( ?, ?, ?, ?, ?, ? )
+            "#,
+            event_id,
+            delivery.subscription_id,
+            delivery.event_type,
+            delivery.payload,
+            delivery.status.as_str(),
+            delivery.next_attempt_at
+        )
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(done) => Ok(Some(done.last_insert_id())),
+            // the key on the event and subscription: it was queued already
+            Err(sqlx::Error::Database(e))
+                if e.try_downcast_ref::<MySqlDatabaseError>()
+                    .map(|e| e.number())
+                    == Some(1062) =>
+            {
+                Ok(None)
+            }
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+
+    async fn update_delivery(&self, delivery: WebhookDelivery) -> Result<(), Error> {
+        let result = sqlx::query!(
+            r#"UPDATE webhook_deliveries
+          SET status = ?, attempts = ?, next_attempt_at = ?, response_status = ?, last_error = ?, delivered_at = ?
+          WHERE id = ?"#,
+            delivery.status.as_str(),
+            delivery.attempts,
+            delivery.next_attempt_at,
+            delivery.response_status,
+            delivery.last_error,
+            delivery.delivered_at,
+            delivery.id
+        )
+        .execute(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(_) => Ok(()),
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+
+    async fn find_due_deliveries(
+        &self,
+        now: NaiveDateTime,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        let result = sqlx::query_as!(
+            WebhookDeliveryRow,
+            r#"SELECT id, subscription_id, event_type, payload, status, attempts, next_attempt_at,
+          response_status as `response_status: u16`, last_error, delivered_at
+          FROM webhook_deliveries
+          WHERE status = 'pending' AND next_attempt_at <= ?
+          ORDER BY id
+          LIMIT ?"#,
+            now,
+            limit
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(WebhookDeliveryRow::from_rows(rows)),
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+
+    async fn find_deliveries(
+        &self,
+        subscription_id: u32,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        let result = sqlx::query_as!(
+            WebhookDeliveryRow,
+            r#"SELECT id, subscription_id, event_type, payload, status, attempts, next_attempt_at,
+          response_status as `response_status: u16`, last_error, delivered_at
+          FROM webhook_deliveries
+          WHERE subscription_id = ?
+          ORDER BY id DESC
+          LIMIT ?"#,
+            subscription_id,
+            limit
+        )
+        .fetch_all(&self.db.pool)
+        .await;
+
+        match result {
+            Ok(rows) => Ok(WebhookDeliveryRow::from_rows(rows)),
+            Err(_) => Err(Error::WebhookStoreError),
+        }
+    }
+}
@@ -1,32 +1,347 @@
+use crate::notifier::{
+    dispatcher::{EventDispatcher, OutboxSinks},
+    event_bus::ItemEventBus,
+    file::FileSink,
+    webhook::{WebhookScheduler, WebhookSink},
+};
+use api_core::{
+    domain::{
+        audit::{AuditAction, AuditRecord},
+        staff::{Role, Staff},
+    },
+    repository::{staff_repository::StaffRepository, webhook_repository::WebhookRepository},
+};
+
+#[cfg(feature = "mysql")]
 use crate::{
-    persistence::mysql::{Db, Env},
-    repository::item_repository_impl::ItemRepositoryImpl,
+    notifier::{waitlist::WaitlistMessenger, webhook::HttpWebhookSender},
+    persistence::{
+        config::{DbConfig, DbError, ItemReadRouting},
+        mysql::Db,
+        Env,
+    },
+    repository::{
+        audit_repository_impl::AuditRepositoryImpl,
+        cached_item_repository::{CachedItemRepository, ItemCacheConfig},
+        device_repository_impl::DeviceRepositoryImpl,
+        idempotency_repository_impl::IdempotencyRepositoryImpl,
+        item_repository_impl::ItemRepositoryImpl,
+        outbox_repository_impl::OutboxRepositoryImpl,
+        reservation_repository_impl::ReservationRepositoryImpl,
+        staff_repository_impl::StaffRepositoryImpl,
+        table_repository_impl::TableRepositoryImpl,
+        waitlist_repository_impl::WaitlistRepositoryImpl,
+        webhook_repository_impl::WebhookRepositoryImpl,
+    },
 };
-use api_core::repository::Repositories;
+#[cfg(feature = "mysql")]
+use api_core::{notifier::WaitlistNotifier, repository::Repositories};
+#[cfg(feature = "mysql")]
+use std::mem;
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
+/// The repositories on MySQL.
+#[cfg(feature = "mysql")]
 #[derive(Clone)]
 pub struct RepositoriesImpl {
-    item_repository: ItemRepositoryImpl,
+    item_repository: CachedItemRepository<ItemRepositoryImpl>,
+    table_repository: TableRepositoryImpl,
+    reservation_repository: ReservationRepositoryImpl,
+    waitlist_repository: WaitlistRepositoryImpl,
+    staff_repository: StaffRepositoryImpl,
+    device_repository: DeviceRepositoryImpl,
+    audit_repository: AuditRepositoryImpl,
+    idempotency_repository: IdempotencyRepositoryImpl,
+    outbox_repository: OutboxRepositoryImpl,
+    webhook_repository: WebhookRepositoryImpl,
+    item_events: ItemEventBus,
+    event_publisher: EventDispatcher,
+    webhook_sender: HttpWebhookSender,
 }
 
+#[cfg(feature = "mysql")]
 impl Repositories for RepositoriesImpl {
-    type ItemRepo = ItemRepositoryImpl;
+    type ItemRepo = CachedItemRepository<ItemRepositoryImpl>;
+    type TableRepo = TableRepositoryImpl;
+    type ReservationRepo = ReservationRepositoryImpl;
+    type WaitlistRepo = WaitlistRepositoryImpl;
+    type StaffRepo = StaffRepositoryImpl;
+    type DeviceRepo = DeviceRepositoryImpl;
+    type AuditRepo = AuditRepositoryImpl;
+    type IdempotencyRepo = IdempotencyRepositoryImpl;
+    type OutboxRepo = OutboxRepositoryImpl;
+    type WebhookRepo = WebhookRepositoryImpl;
+    type Events = EventDispatcher;
+    type Webhooks = HttpWebhookSender;
 
     fn item_repository(&self) -> &Self::ItemRepo {
         &self.item_repository
     }
+
+    fn table_repository(&self) -> &Self::TableRepo {
+        &self.table_repository
+    }
+
+    fn reservation_repository(&self) -> &Self::ReservationRepo {
+        &self.reservation_repository
+    }
+
+    fn waitlist_repository(&self) -> &Self::WaitlistRepo {
+        &self.waitlist_repository
+    }
+
+    fn staff_repository(&self) -> &Self::StaffRepo {
+        &self.staff_repository
+    }
+
+    fn device_repository(&self) -> &Self::DeviceRepo {
+        &self.device_repository
+    }
+
+    fn audit_repository(&self) -> &Self::AuditRepo {
+        &self.audit_repository
+    }
+
+    fn idempotency_repository(&self) -> &Self::IdempotencyRepo {
+        &self.idempotency_repository
+    }
+
+    fn outbox_repository(&self) -> &Self::OutboxRepo {
+        &self.outbox_repository
+    }
+
+    fn webhook_repository(&self) -> &Self::WebhookRepo {
+        &self.webhook_repository
+    }
+
+    fn event_publisher(&self) -> &Self::Events {
+        &self.event_publisher
+    }
+
+    fn webhook_sender(&self) -> &Self::Webhooks {
+        &self.webhook_sender
+    }
 }
 
+#[cfg(feature = "mysql")]
 impl RepositoriesImpl {
-    pub async fn new() -> Self {
-        let db = Db::new(Env::Production).await;
-        let item_repository = ItemRepositoryImpl::new(db);
-        Self { item_repository }
+    /// The bus kitchen displays and tablets subscribe to for item events.
+    pub fn item_event_bus(&self) -> &ItemEventBus {
+        &self.item_events
     }
 
-    pub async fn test() -> Self {
-        let db = Db::new(Env::Test).await;
-        let item_repository = ItemRepositoryImpl::new(db);
-        Self { item_repository }
+    /// Has the guests on the waitlist told about their wait and called in
+    /// through `notifier`, as the outbox relays the events.
+    pub fn with_waitlist_notifier<N>(mut self, notifier: N) -> Self
+    where
+        N: WaitlistNotifier + Send + Sync + 'static,
+    {
+        let messenger = WaitlistMessenger::new(self.waitlist_repository.clone(), notifier);
+        self.event_publisher =
+            mem::take(&mut self.event_publisher).with_subscriber("waitlist", messenger);
+        self
     }
+
+    /// Connects to the production database as configured.
+    pub async fn new(
+        config: &DbConfig,
+        item_cache: ItemCacheConfig,
+        outbox_sinks: &OutboxSinks,
+    ) -> Result<Self, DbError> {
+        Ok(Self::with_db(
+            Db::connect(config).await?,
+            config.item_reads,
+            item_cache,
+            outbox_sinks,
+        ))
+    }
+
+    /// Without the item cache, since the tests share their database, and with
+    /// the staff the tests log in as.
+    pub async fn test() -> Result<Self, DbError> {
+        let repositories = Self::with_db(
+            Db::new(Env::Test).await?,
+            ItemReadRouting::default(),
+            ItemCacheConfig::disabled(),
+            &OutboxSinks::default(),
+        );
+        seed_test_staff(&repositories.staff_repository).await;
+        Ok(repositories)
+    }
+
+    fn with_db(
+        db: Db,
+        item_reads: ItemReadRouting,
+        item_cache: ItemCacheConfig,
+        outbox_sinks: &OutboxSinks,
+    ) -> Self {
+        let item_events = ItemEventBus::default();
+        let webhook_repository = WebhookRepositoryImpl::new(db.clone());
+        let event_publisher =
+            event_publisher(&item_events, webhook_repository.clone(), outbox_sinks);
+        Self {
+            item_repository: CachedItemRepository::new(
+                ItemRepositoryImpl::with_reads(db.clone(), item_reads),
+                item_cache,
+            ),
+            table_repository: TableRepositoryImpl::new(db.clone()),
+            reservation_repository: ReservationRepositoryImpl::new(db.clone()),
+            waitlist_repository: WaitlistRepositoryImpl::new(db.clone()),
+            staff_repository: StaffRepositoryImpl::new(db.clone()),
+            device_repository: DeviceRepositoryImpl::new(db.clone()),
+            audit_repository: AuditRepositoryImpl::new(db.clone()),
+            idempotency_repository: IdempotencyRepositoryImpl::new(db.clone()),
+            outbox_repository: OutboxRepositoryImpl::new(db),
+            webhook_repository,
+            item_events,
+            event_publisher,
+            webhook_sender: HttpWebhookSender::default(),
+        }
+    }
+}
+
+/// The subscribers the outbox relay hands every event to, whichever backend
+/// the repositories are on.
+fn event_publisher<W>(
+    item_events: &ItemEventBus,
+    webhook_repository: W,
+    outbox_sinks: &OutboxSinks,
+) -> EventDispatcher
+where
+    W: WebhookRepository + Send + Sync + 'static,
+{
+    let mut event_publisher = EventDispatcher::default()
+        .with_subscriber("item_events", item_events.clone())
+        .with_subscriber("webhooks", WebhookScheduler::new(webhook_repository));
+    if let Some(url) = &outbox_sinks.webhook_url {
+        event_publisher =
+            event_publisher.with_subscriber("outbox_webhook", WebhookSink::new(url.clone()));
+    }
+    if let Some(path) = &outbox_sinks.file {
+        event_publisher =
+            event_publisher.with_subscriber("outbox_file", FileSink::new(path.clone()));
+    }
+    event_publisher
+}
+
+/// The staff the tests log in as, every one with the password `password`.
+/// Only test databases get them: the migrations create no accounts.
+const TEST_STAFF: [(&str, Role); 4] = [
+    ("admin", Role::Admin),
+    ("manager", Role::Manager),
+    ("waiter", Role::Waiter),
+    ("cook", Role::Cook),
+];
+
+/// bcrypt of `password`.
+const TEST_PASSWORD_HASH: &str = "$2b$10$aS5jl3bAfpECygsRe1eBWOJ4wMwcSACb9B5QdUTbcKdPpcGyrONIm";
+
+async fn seed_test_staff<S: StaffRepository>(staff_repository: &S) {
+    for (name, role) in TEST_STAFF {
+        if staff_repository.find_by_name(name).await.is_ok() {
+            continue;
+        }
+        let mut staff = Staff::new(name.to_string(), role).expect("the test staff are valid");
+        staff.password_hash = Some(TEST_PASSWORD_HASH.to_string());
+        // a test running alongside may have added them first, which is as good
+        let audit = AuditRecord::by_system(AuditAction::StaffAdded, None, None, None, None);
+        let _ = staff_repository.create(staff, audit).await;
+    }
+}
+
+#[cfg(all(test, feature = "mysql"))]
+mod test {
+    use super::*;
+    use crate::repository::repository_suite::{repository_suite, RepositoriesFixture};
+    use async_trait::async_trait;
+
+    pub(crate) struct Fixture {
+        repositories: RepositoriesImpl,
+        db: Db,
+    }
+
+    #[async_trait]
+    impl RepositoriesFixture for Fixture {
+        type Repositories = RepositoriesImpl;
+
+        async fn set_up() -> Self {
+            let db = Db::new(Env::Test).await.unwrap();
+            sqlx::query!("INSERT IGNORE INTO tables (number) VALUES (1), (2), (3), (4), (5)")
+                .execute(&db.pool)
+                .await
+                .unwrap();
+            let fixture = Fixture {
+                repositories: RepositoriesImpl::with_db(
+                    db.clone(),
+                    ItemReadRouting::default(),
+                    ItemCacheConfig::disabled(),
+                    &OutboxSinks::default(),
+                ),
+                db,
+            };
+            fixture.tear_down().await;
+            fixture
+        }
+
+        async fn tear_down(&self) {
+            sqlx::query!("DELETE FROM webhook_deliveries")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("DELETE FROM webhook_subscriptions")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("DELETE FROM reservations")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("DELETE FROM waitlist_entries")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("DELETE FROM devices")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("DELETE FROM audit_records")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("DELETE FROM idempotency_keys")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("DELETE FROM outbox_events")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("DELETE FROM items")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("DELETE FROM item_transfers")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("DELETE FROM staff WHERE name LIKE 'test-%'")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query!("UPDATE tables SET status = 'free', seated_at = NULL WHERE number <= 5")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+        }
+
+        fn repositories(&self) -> &Self::Repositories {
+            &self.repositories
+        }
+    }
+
+    repository_suite!(Fixture);
 }
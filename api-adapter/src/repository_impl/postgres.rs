@@ -0,0 +1,253 @@
+use super::{event_publisher, seed_test_staff};
+use crate::{
+    notifier::{
+        dispatcher::{EventDispatcher, OutboxSinks},
+        event_bus::ItemEventBus,
+        waitlist::WaitlistMessenger,
+        webhook::HttpWebhookSender,
+    },
+    persistence::{
+        config::{DbConfig, DbError},
+        postgres::PgDb,
+        Env,
+    },
+    repository::{
+        cached_item_repository::{CachedItemRepository, ItemCacheConfig},
+        postgres::{
+            audit_repository_impl::PgAuditRepositoryImpl,
+            device_repository_impl::PgDeviceRepositoryImpl,
+            idempotency_repository_impl::PgIdempotencyRepositoryImpl,
+            item_repository_impl::PgItemRepositoryImpl,
+            outbox_repository_impl::PgOutboxRepositoryImpl,
+            reservation_repository_impl::PgReservationRepositoryImpl,
+            staff_repository_impl::PgStaffRepositoryImpl,
+            table_repository_impl::PgTableRepositoryImpl,
+            waitlist_repository_impl::PgWaitlistRepositoryImpl,
+            webhook_repository_impl::PgWebhookRepositoryImpl,
+        },
+    },
+};
+use api_core::{notifier::WaitlistNotifier, repository::Repositories};
+use std::mem;
+
+/// The repositories on PostgreSQL, in place of `RepositoriesImpl` when the
+/// `postgres` feature is enabled.
+#[derive(Clone)]
+pub struct PgRepositoriesImpl {
+    item_repository: CachedItemRepository<PgItemRepositoryImpl>,
+    table_repository: PgTableRepositoryImpl,
+    reservation_repository: PgReservationRepositoryImpl,
+    waitlist_repository: PgWaitlistRepositoryImpl,
+    staff_repository: PgStaffRepositoryImpl,
+    device_repository: PgDeviceRepositoryImpl,
+    audit_repository: PgAuditRepositoryImpl,
+    idempotency_repository: PgIdempotencyRepositoryImpl,
+    outbox_repository: PgOutboxRepositoryImpl,
+    webhook_repository: PgWebhookRepositoryImpl,
+    item_events: ItemEventBus,
+    event_publisher: EventDispatcher,
+    webhook_sender: HttpWebhookSender,
+}
+
+impl Repositories for PgRepositoriesImpl {
+    type ItemRepo = CachedItemRepository<PgItemRepositoryImpl>;
+    type TableRepo = PgTableRepositoryImpl;
+    type ReservationRepo = PgReservationRepositoryImpl;
+    type WaitlistRepo = PgWaitlistRepositoryImpl;
+    type StaffRepo = PgStaffRepositoryImpl;
+    type DeviceRepo = PgDeviceRepositoryImpl;
+    type AuditRepo = PgAuditRepositoryImpl;
+    type IdempotencyRepo = PgIdempotencyRepositoryImpl;
+    type OutboxRepo = PgOutboxRepositoryImpl;
+    type WebhookRepo = PgWebhookRepositoryImpl;
+    type Events = EventDispatcher;
+    type Webhooks = HttpWebhookSender;
+
+    fn item_repository(&self) -> &Self::ItemRepo {
+        &self.item_repository
+    }
+
+    fn table_repository(&self) -> &Self::TableRepo {
+        &self.table_repository
+    }
+
+    fn reservation_repository(&self) -> &Self::ReservationRepo {
+        &self.reservation_repository
+    }
+
+    fn waitlist_repository(&self) -> &Self::WaitlistRepo {
+        &self.waitlist_repository
+    }
+
+    fn staff_repository(&self) -> &Self::StaffRepo {
+        &self.staff_repository
+    }
+
+    fn device_repository(&self) -> &Self::DeviceRepo {
+        &self.device_repository
+    }
+
+    fn audit_repository(&self) -> &Self::AuditRepo {
+        &self.audit_repository
+    }
+
+    fn idempotency_repository(&self) -> &Self::IdempotencyRepo {
+        &self.idempotency_repository
+    }
+
+    fn outbox_repository(&self) -> &Self::OutboxRepo {
+        &self.outbox_repository
+    }
+
+    fn webhook_repository(&self) -> &Self::WebhookRepo {
+        &self.webhook_repository
+    }
+
+    fn event_publisher(&self) -> &Self::Events {
+        &self.event_publisher
+    }
+
+    fn webhook_sender(&self) -> &Self::Webhooks {
+        &self.webhook_sender
+    }
+}
+
+impl PgRepositoriesImpl {
+    /// The bus kitchen displays and tablets subscribe to for item events.
+    pub fn item_event_bus(&self) -> &ItemEventBus {
+        &self.item_events
+    }
+
+    /// Has the guests on the waitlist told about their wait and called in
+    /// through `notifier`, as the outbox relays the events.
+    pub fn with_waitlist_notifier<N>(mut self, notifier: N) -> Self
+    where
+        N: WaitlistNotifier + Send + Sync + 'static,
+    {
+        let messenger = WaitlistMessenger::new(self.waitlist_repository.clone(), notifier);
+        self.event_publisher =
+            mem::take(&mut self.event_publisher).with_subscriber("waitlist", messenger);
+        self
+    }
+
+    /// Connects to the production database as configured.
+    pub async fn new(
+        config: &DbConfig,
+        item_cache: ItemCacheConfig,
+        outbox_sinks: &OutboxSinks,
+    ) -> Result<Self, DbError> {
+        Ok(Self::with_db(
+            PgDb::connect(config).await?,
+            item_cache,
+            outbox_sinks,
+        ))
+    }
+
+    /// Without the item cache, since the tests share their database, and with
+    /// the staff the tests log in as.
+    pub async fn test() -> Result<Self, DbError> {
+        let repositories = Self::with_db(
+            PgDb::new(Env::Test).await?,
+            ItemCacheConfig::disabled(),
+            &OutboxSinks::default(),
+        );
+        seed_test_staff(&repositories.staff_repository).await;
+        Ok(repositories)
+    }
+
+    fn with_db(db: PgDb, item_cache: ItemCacheConfig, outbox_sinks: &OutboxSinks) -> Self {
+        let item_events = ItemEventBus::default();
+        let webhook_repository = PgWebhookRepositoryImpl::new(db.clone());
+        let event_publisher =
+            event_publisher(&item_events, webhook_repository.clone(), outbox_sinks);
+        Self {
+            item_repository: CachedItemRepository::new(
+                PgItemRepositoryImpl::new(db.clone()),
+                item_cache,
+            ),
+            table_repository: PgTableRepositoryImpl::new(db.clone()),
+            reservation_repository: PgReservationRepositoryImpl::new(db.clone()),
+            waitlist_repository: PgWaitlistRepositoryImpl::new(db.clone()),
+            staff_repository: PgStaffRepositoryImpl::new(db.clone()),
+            device_repository: PgDeviceRepositoryImpl::new(db.clone()),
+            audit_repository: PgAuditRepositoryImpl::new(db.clone()),
+            idempotency_repository: PgIdempotencyRepositoryImpl::new(db.clone()),
+            outbox_repository: PgOutboxRepositoryImpl::new(db),
+            webhook_repository,
+            item_events,
+            event_publisher,
+            webhook_sender: HttpWebhookSender::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::repository::repository_suite::{repository_suite, RepositoriesFixture};
+    use async_trait::async_trait;
+
+    pub(crate) struct Fixture {
+        repositories: PgRepositoriesImpl,
+        db: PgDb,
+    }
+
+    #[async_trait]
+    impl RepositoriesFixture for Fixture {
+        type Repositories = PgRepositoriesImpl;
+
+        async fn set_up() -> Self {
+            let db = PgDb::new(Env::Test).await.unwrap();
+            sqlx::query(
+                "INSERT INTO tables (number) VALUES (1), (2), (3), (4), (5) ON CONFLICT DO NOTHING",
+            )
+            .execute(&db.pool)
+            .await
+            .unwrap();
+            let fixture = Fixture {
+                repositories: PgRepositoriesImpl::with_db(
+                    db.clone(),
+                    ItemCacheConfig::disabled(),
+                    &OutboxSinks::default(),
+                ),
+                db,
+            };
+            fixture.tear_down().await;
+            fixture
+        }
+
+        async fn tear_down(&self) {
+            for table in [
+                "webhook_deliveries",
+                "webhook_subscriptions",
+                "reservations",
+                "waitlist_entries",
+                "devices",
+                "audit_records",
+                "idempotency_keys",
+                "outbox_events",
+                "items",
+                "item_transfers",
+            ] {
+                sqlx::query(&format!("DELETE FROM {}", table))
+                    .execute(&self.db.pool)
+                    .await
+                    .unwrap();
+            }
+            sqlx::query("DELETE FROM staff WHERE name LIKE 'test-%'")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+            sqlx::query("UPDATE tables SET status = 'free', seated_at = NULL WHERE number <= 5")
+                .execute(&self.db.pool)
+                .await
+                .unwrap();
+        }
+
+        fn repositories(&self) -> &Self::Repositories {
+            &self.repositories
+        }
+    }
+
+    repository_suite!(Fixture);
+}
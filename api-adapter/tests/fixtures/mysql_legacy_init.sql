@@ -0,0 +1,33 @@
+-- A database as docker/mysql/initdb/init.sql used to create it, before the
+-- application managed its own migrations, with an order already taken and
+-- one left behind by a table that is gone.
+CREATE TABLE `tables` (
+	`number` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`number`)
+) DEFAULT CHARACTER SET=utf8mb4;
+
+CREATE TABLE `items` (
+	`id` INT UNSIGNED NOT NULL AUTO_INCREMENT,
+	`name` VARCHAR(255) NOT NULL,
+	`quantity` INT UNSIGNED NOT NULL,
+	`table_number` INT UNSIGNED NOT NULL,
+	`start_cooking_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`finish_cooking_at` DATETIME NOT NULL,
+	`created_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	`updated_at` DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+	PRIMARY KEY (`id`)
+	-- FOREIGN KEY (`table_number`)
+	-- 	REFERENCES `tables`(`number`)
+	-- 	ON UPDATE CASCADE
+) DEFAULT CHARACTER SET=utf8mb4;
+
+INSERT INTO `tables` VALUES ();
+INSERT INTO `tables` VALUES ();
+INSERT INTO `tables` VALUES ();
+INSERT INTO `tables` VALUES ();
+INSERT INTO `tables` VALUES ();
+
+INSERT INTO `items` (`name`, `quantity`, `table_number`, `finish_cooking_at`)
+	VALUES ('Ramen', 1, 5, CURRENT_TIMESTAMP), ('Gyoza', 2, 9, CURRENT_TIMESTAMP);
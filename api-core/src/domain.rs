@@ -1 +1,14 @@
+pub mod actor;
+pub mod audit;
+pub mod device;
+pub mod event;
+pub mod idempotency;
 pub mod item;
+pub mod item_event;
+pub mod item_transfer;
+pub mod outbox;
+pub mod reservation;
+pub mod staff;
+pub mod table;
+pub mod waitlist;
+pub mod webhook;
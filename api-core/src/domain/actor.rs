@@ -0,0 +1,130 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::domain::device::Device;
+use crate::domain::staff::{Permission, Staff};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ActorKind {
+    Staff,
+    Device,
+    /// The server itself, for changes nobody asked for, such as a webhook
+    /// disabled after failing too often. It has no id of its own.
+    System,
+}
+
+impl ActorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActorKind::Staff => "staff",
+            ActorKind::Device => "device",
+            ActorKind::System => "system",
+        }
+    }
+}
+
+impl fmt::Display for ActorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ActorKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "staff" => Ok(ActorKind::Staff),
+            "device" => Ok(ActorKind::Device),
+            "system" => Ok(ActorKind::System),
+            _ => Err(format!("unknown actor kind: {}", s)),
+        }
+    }
+}
+
+/// Whoever is making a change: a logged-in staff member or a device using
+/// its API key.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Actor {
+    Staff(Staff),
+    Device(Device),
+}
+
+impl Actor {
+    pub fn kind(&self) -> ActorKind {
+        match self {
+            Actor::Staff(_) => ActorKind::Staff,
+            Actor::Device(_) => ActorKind::Device,
+        }
+    }
+
+    pub fn id(&self) -> Option<u32> {
+        match self {
+            Actor::Staff(staff) => staff.id,
+            Actor::Device(device) => device.id,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        match self {
+            Actor::Staff(staff) => staff.active,
+            Actor::Device(device) => !device.revoked,
+        }
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        match self {
+            Actor::Staff(staff) => staff.can(permission),
+            Actor::Device(device) => !device.revoked && device.scope.allows(permission),
+        }
+    }
+
+    pub fn can_access_table(&self, table_number: u32) -> bool {
+        match self {
+            Actor::Staff(_) => true,
+            Actor::Device(device) => device.scope.covers_table(table_number),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::device::DeviceScope;
+    use crate::domain::staff::Role;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn table_tablet_stays_on_its_table() {
+        let tablet = Actor::Device(Device::of(
+            1,
+            String::from("table-2"),
+            DeviceScope::Table(2),
+            String::from("hash"),
+            false,
+        ));
+        let waiter = Actor::Staff(Staff::of(
+            3,
+            String::from("waiter"),
+            Role::Waiter,
+            true,
+            None,
+        ));
+        assert_eq!(tablet.can_access_table(2), true);
+        assert_eq!(tablet.can_access_table(3), false);
+        assert_eq!(waiter.can_access_table(3), true);
+    }
+
+    #[test]
+    fn revoked_device_is_inactive() {
+        let kitchen = Actor::Device(Device::of(
+            1,
+            String::from("pass"),
+            DeviceScope::Kitchen,
+            String::from("hash"),
+            true,
+        ));
+        assert_eq!(kitchen.is_active(), false);
+        assert_eq!(kitchen.can(Permission::MarkItemsReady), false);
+    }
+}
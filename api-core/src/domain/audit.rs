@@ -0,0 +1,295 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+
+use crate::domain::actor::{Actor, ActorKind};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AuditAction {
+    ItemAdded,
+    ItemStatusChanged,
+    ItemsMoved,
+    TablesMerged,
+    ItemDeleted,
+    TableSeated,
+    TableStatusChanged,
+    ReservationMade,
+    ReservationModified,
+    ReservationCancelled,
+    ReservationSeated,
+    WaitlistJoined,
+    WaitlistLeft,
+    WaitlistSeated,
+    StaffAdded,
+    StaffDeactivated,
+    DeviceKeyIssued,
+    DeviceKeyRotated,
+    DeviceKeyRevoked,
+    WebhookCreated,
+    WebhookEnabled,
+    WebhookDisabled,
+    WebhookDeleted,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::ItemAdded => "item_added",
+            AuditAction::ItemStatusChanged => "item_status_changed",
+            AuditAction::ItemsMoved => "items_moved",
+            AuditAction::TablesMerged => "tables_merged",
+            AuditAction::ItemDeleted => "item_deleted",
+            AuditAction::TableSeated => "table_seated",
+            AuditAction::TableStatusChanged => "table_status_changed",
+            AuditAction::ReservationMade => "reservation_made",
+            AuditAction::ReservationModified => "reservation_modified",
+            AuditAction::ReservationCancelled => "reservation_cancelled",
+            AuditAction::ReservationSeated => "reservation_seated",
+            AuditAction::WaitlistJoined => "waitlist_joined",
+            AuditAction::WaitlistLeft => "waitlist_left",
+            AuditAction::WaitlistSeated => "waitlist_seated",
+            AuditAction::StaffAdded => "staff_added",
+            AuditAction::StaffDeactivated => "staff_deactivated",
+            AuditAction::DeviceKeyIssued => "device_key_issued",
+            AuditAction::DeviceKeyRotated => "device_key_rotated",
+            AuditAction::DeviceKeyRevoked => "device_key_revoked",
+            AuditAction::WebhookCreated => "webhook_created",
+            AuditAction::WebhookEnabled => "webhook_enabled",
+            AuditAction::WebhookDisabled => "webhook_disabled",
+            AuditAction::WebhookDeleted => "webhook_deleted",
+        }
+    }
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "item_added" => Ok(AuditAction::ItemAdded),
+            "item_status_changed" => Ok(AuditAction::ItemStatusChanged),
+            "items_moved" => Ok(AuditAction::ItemsMoved),
+            "tables_merged" => Ok(AuditAction::TablesMerged),
+            "item_deleted" => Ok(AuditAction::ItemDeleted),
+            "table_seated" => Ok(AuditAction::TableSeated),
+            "table_status_changed" => Ok(AuditAction::TableStatusChanged),
+            "reservation_made" => Ok(AuditAction::ReservationMade),
+            "reservation_modified" => Ok(AuditAction::ReservationModified),
+            "reservation_cancelled" => Ok(AuditAction::ReservationCancelled),
+            "reservation_seated" => Ok(AuditAction::ReservationSeated),
+            "waitlist_joined" => Ok(AuditAction::WaitlistJoined),
+            "waitlist_left" => Ok(AuditAction::WaitlistLeft),
+            "waitlist_seated" => Ok(AuditAction::WaitlistSeated),
+            "staff_added" => Ok(AuditAction::StaffAdded),
+            "staff_deactivated" => Ok(AuditAction::StaffDeactivated),
+            "device_key_issued" => Ok(AuditAction::DeviceKeyIssued),
+            "device_key_rotated" => Ok(AuditAction::DeviceKeyRotated),
+            "device_key_revoked" => Ok(AuditAction::DeviceKeyRevoked),
+            "webhook_created" => Ok(AuditAction::WebhookCreated),
+            "webhook_enabled" => Ok(AuditAction::WebhookEnabled),
+            "webhook_disabled" => Ok(AuditAction::WebhookDisabled),
+            "webhook_deleted" => Ok(AuditAction::WebhookDeleted),
+            _ => Err(format!("unknown audit action: {}", s)),
+        }
+    }
+}
+
+/// Who changed what and when. `before` and `after` are JSON snapshots of the
+/// changed record; one of them is missing when something was created or
+/// deleted.
+///
+/// It is written by the repository making the change, in the same
+/// transaction, so that a change is never left without its record or the
+/// other way round. For something created the repository also takes the
+/// `after` snapshot, once the record has its id.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AuditRecord {
+    pub id: Option<u32>,
+    pub actor_kind: ActorKind,
+    pub actor_id: u32,
+    pub action: AuditAction,
+    pub table_number: Option<u32>,
+    pub item_id: Option<u32>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    /// Set by the store when the record is appended.
+    pub recorded_at: Option<NaiveDateTime>,
+}
+
+impl AuditRecord {
+    pub fn new(
+        actor: &Actor,
+        action: AuditAction,
+        table_number: Option<u32>,
+        item_id: Option<u32>,
+        before: Option<String>,
+        after: Option<String>,
+    ) -> Result<Self, String> {
+        let actor_id = actor
+            .id()
+            .ok_or_else(|| String::from("only stored actors can be audited"))?;
+        Ok(AuditRecord {
+            id: None,
+            actor_kind: actor.kind(),
+            actor_id,
+            action,
+            table_number,
+            item_id,
+            before,
+            after,
+            recorded_at: None,
+        })
+    }
+
+    /// A change the server made by itself.
+    pub fn by_system(
+        action: AuditAction,
+        table_number: Option<u32>,
+        item_id: Option<u32>,
+        before: Option<String>,
+        after: Option<String>,
+    ) -> Self {
+        AuditRecord {
+            id: None,
+            actor_kind: ActorKind::System,
+            actor_id: 0,
+            action,
+            table_number,
+            item_id,
+            before,
+            after,
+            recorded_at: None,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn of(
+        id: u32,
+        actor_kind: ActorKind,
+        actor_id: u32,
+        action: AuditAction,
+        table_number: Option<u32>,
+        item_id: Option<u32>,
+        before: Option<String>,
+        after: Option<String>,
+        recorded_at: NaiveDateTime,
+    ) -> Self {
+        AuditRecord {
+            id: Some(id),
+            actor_kind,
+            actor_id,
+            action,
+            table_number,
+            item_id,
+            before,
+            after,
+            recorded_at: Some(recorded_at),
+        }
+    }
+}
+
+/// Narrows down an audit query. Unset fields match everything.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct AuditFilter {
+    pub table_number: Option<u32>,
+    pub item_id: Option<u32>,
+    pub actor_kind: Option<ActorKind>,
+    pub actor_id: Option<u32>,
+    pub limit: u32,
+}
+
+impl AuditFilter {
+    pub const DEFAULT_LIMIT: u32 = 100;
+    pub const MAX_LIMIT: u32 = 1000;
+
+    pub fn new(
+        table_number: Option<u32>,
+        item_id: Option<u32>,
+        actor_kind: Option<ActorKind>,
+        actor_id: Option<u32>,
+        limit: Option<u32>,
+    ) -> Result<Self, String> {
+        if actor_id.is_some() && actor_kind.is_none() {
+            return Err(String::from("an actor id needs an actor kind"));
+        }
+        let limit = limit.unwrap_or(Self::DEFAULT_LIMIT);
+        if !(1..=Self::MAX_LIMIT).contains(&limit) {
+            return Err(format!("limit should be between 1 and {}", Self::MAX_LIMIT));
+        }
+        Ok(AuditFilter {
+            table_number,
+            item_id,
+            actor_kind,
+            actor_id,
+            limit,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::staff::{Role, Staff};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn record_names_the_actor() {
+        let waiter = Actor::Staff(Staff::of(
+            3,
+            String::from("waiter"),
+            Role::Waiter,
+            true,
+            None,
+        ));
+        let record = AuditRecord::new(
+            &waiter,
+            AuditAction::ItemDeleted,
+            Some(1),
+            Some(7),
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(record.actor_kind, ActorKind::Staff);
+        assert_eq!(record.actor_id, 3);
+    }
+
+    #[test]
+    fn filter_with_actor_id_but_no_kind() {
+        assert_eq!(
+            AuditFilter::new(None, None, None, Some(3), None).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn filter_limit_is_bounded() {
+        assert_eq!(
+            AuditFilter::new(None, None, None, None, None)
+                .unwrap()
+                .limit,
+            AuditFilter::DEFAULT_LIMIT
+        );
+        assert_eq!(
+            AuditFilter::new(None, None, None, None, Some(0)).is_err(),
+            true
+        );
+        assert_eq!(
+            AuditFilter::new(None, None, None, None, Some(5000)).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn action_round_trips() {
+        let action: AuditAction = AuditAction::TablesMerged.as_str().parse().unwrap();
+        assert_eq!(action, AuditAction::TablesMerged);
+    }
+}
@@ -0,0 +1,162 @@
+use serde::Serialize;
+
+use crate::domain::staff::Permission;
+
+/// Which part of the API a device key opens up.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceScope {
+    /// A kitchen display: sees every table's items and marks them ready.
+    Kitchen,
+    /// A tablet on one table: only ever touches that table's items.
+    Table(u32),
+}
+
+impl DeviceScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeviceScope::Kitchen => "kitchen",
+            DeviceScope::Table(_) => "table",
+        }
+    }
+
+    pub fn table_number(&self) -> Option<u32> {
+        match self {
+            DeviceScope::Kitchen => None,
+            DeviceScope::Table(table_number) => Some(*table_number),
+        }
+    }
+
+    /// Rebuilds a scope from its stored name and, for table tablets, the
+    /// table it belongs to.
+    pub fn parse(scope: &str, table_number: Option<u32>) -> Result<Self, String> {
+        match (scope, table_number) {
+            ("kitchen", None) => Ok(DeviceScope::Kitchen),
+            ("table", Some(table_number)) => Ok(DeviceScope::Table(table_number)),
+            ("table", None) => Err(String::from("a table device needs a table number")),
+            ("kitchen", Some(_)) => Err(String::from("a kitchen device has no table number")),
+            _ => Err(format!("unknown device scope: {}", scope)),
+        }
+    }
+
+    pub fn allows(&self, permission: Permission) -> bool {
+        match permission {
+            Permission::MarkItemsReady => matches!(self, DeviceScope::Kitchen),
+            _ => false,
+        }
+    }
+
+    pub fn covers_table(&self, table_number: u32) -> bool {
+        match self {
+            DeviceScope::Kitchen => true,
+            DeviceScope::Table(own_table) => *own_table == table_number,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Device {
+    pub id: Option<u32>,
+    pub name: String,
+    pub scope: DeviceScope,
+    /// SHA-256 of the device's API key. The key itself is only shown once,
+    /// when it is issued or rotated.
+    #[serde(skip)]
+    pub key_hash: String,
+    pub revoked: bool,
+}
+
+impl Device {
+    pub fn new(name: String, scope: DeviceScope, key_hash: String) -> Result<Self, String> {
+        if name.is_empty() {
+            return Err(String::from("device name must not be empty"));
+        }
+        Ok(Device {
+            id: None,
+            name,
+            scope,
+            key_hash,
+            revoked: false,
+        })
+    }
+
+    pub fn of(id: u32, name: String, scope: DeviceScope, key_hash: String, revoked: bool) -> Self {
+        Device {
+            id: Some(id),
+            name,
+            scope,
+            key_hash,
+            revoked,
+        }
+    }
+
+    pub fn rotate(&mut self, key_hash: String) -> Result<(), String> {
+        if self.revoked {
+            return Err(String::from("a revoked device key cannot be rotated"));
+        }
+        self.key_hash = key_hash;
+        Ok(())
+    }
+
+    pub fn revoke(&mut self) {
+        self.revoked = true;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_scope() {
+        assert_eq!(
+            DeviceScope::parse("kitchen", None),
+            Ok(DeviceScope::Kitchen)
+        );
+        assert_eq!(
+            DeviceScope::parse("table", Some(3)),
+            Ok(DeviceScope::Table(3))
+        );
+        assert_eq!(DeviceScope::parse("table", None).is_err(), true);
+        assert_eq!(DeviceScope::parse("bar", None).is_err(), true);
+    }
+
+    #[test]
+    fn table_device_only_covers_its_own_table() {
+        let scope = DeviceScope::Table(3);
+        assert_eq!(scope.covers_table(3), true);
+        assert_eq!(scope.covers_table(4), false);
+        assert_eq!(DeviceScope::Kitchen.covers_table(4), true);
+    }
+
+    #[test]
+    fn only_kitchen_devices_mark_items_ready() {
+        assert_eq!(
+            DeviceScope::Kitchen.allows(Permission::MarkItemsReady),
+            true
+        );
+        assert_eq!(
+            DeviceScope::Table(1).allows(Permission::MarkItemsReady),
+            false
+        );
+        assert_eq!(
+            DeviceScope::Kitchen.allows(Permission::VoidServedItems),
+            false
+        );
+    }
+
+    #[test]
+    fn revoked_key_cannot_be_rotated() {
+        let mut device = Device::of(
+            1,
+            String::from("pass"),
+            DeviceScope::Kitchen,
+            String::from("old"),
+            false,
+        );
+        device.revoke();
+        assert_eq!(device.rotate(String::from("new")).is_err(), true);
+        assert_eq!(device.key_hash, "old");
+    }
+}
@@ -0,0 +1,190 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::item::{Item, ItemStatus};
+use crate::domain::item_transfer::ItemTransfer;
+use crate::domain::table::TableStatus;
+
+/// Something that happened in the restaurant, stored in the outbox together
+/// with the change and relayed to the publisher from there. Whatever needs
+/// to react to it subscribes to the publisher instead of being called from
+/// the use cases.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DomainEvent {
+    ItemAdded {
+        table_number: u32,
+        item: Item,
+        occurred_at: NaiveDateTime,
+    },
+    ItemStatusChanged {
+        table_number: u32,
+        item: Item,
+        occurred_at: NaiveDateTime,
+    },
+    ItemReady {
+        table_number: u32,
+        item: Item,
+        occurred_at: NaiveDateTime,
+    },
+    ItemServed {
+        table_number: u32,
+        item: Item,
+        occurred_at: NaiveDateTime,
+    },
+    ItemCancelled {
+        table_number: u32,
+        item: Item,
+        occurred_at: NaiveDateTime,
+    },
+    ItemsMoved {
+        transfer: ItemTransfer,
+        moved_items: u64,
+        occurred_at: NaiveDateTime,
+    },
+    TableStatusChanged {
+        table_number: u32,
+        status: TableStatus,
+        occurred_at: NaiveDateTime,
+    },
+    /// The party left and the table waits for cleaning.
+    TableClosed {
+        table_number: u32,
+        occurred_at: NaiveDateTime,
+    },
+    /// A walk-in party was put on the waitlist. Only the entry's id is
+    /// carried, since the guest's name and phone have no business in the
+    /// outbox sinks.
+    WaitlistJoined {
+        entry_id: u32,
+        occurred_at: NaiveDateTime,
+    },
+    /// A waiting party was given a table and is called in.
+    WaitlistCalled {
+        entry_id: u32,
+        table_number: u32,
+        occurred_at: NaiveDateTime,
+    },
+}
+
+impl DomainEvent {
+    /// Every event name, as returned by `name`.
+    pub const NAMES: [&'static str; 10] = [
+        "item_added",
+        "item_status_changed",
+        "item_ready",
+        "item_served",
+        "item_cancelled",
+        "items_moved",
+        "table_status_changed",
+        "table_closed",
+        "waitlist_joined",
+        "waitlist_called",
+    ];
+
+    /// Reaching `ready` is what the floor waits for and `served` is what
+    /// goes on the bill, so they are events of their own.
+    pub fn item_status_changed(table_number: u32, item: Item, now: NaiveDateTime) -> Self {
+        match item.status {
+            ItemStatus::Ready => DomainEvent::ItemReady {
+                table_number,
+                item,
+                occurred_at: now,
+            },
+            ItemStatus::Served => DomainEvent::ItemServed {
+                table_number,
+                item,
+                occurred_at: now,
+            },
+            _ => DomainEvent::ItemStatusChanged {
+                table_number,
+                item,
+                occurred_at: now,
+            },
+        }
+    }
+
+    /// A table being left is what billing waits for, so it is an event of
+    /// its own.
+    pub fn table_status_changed(
+        table_number: u32,
+        status: TableStatus,
+        now: NaiveDateTime,
+    ) -> Self {
+        match status {
+            TableStatus::NeedsCleaning => DomainEvent::TableClosed {
+                table_number,
+                occurred_at: now,
+            },
+            _ => DomainEvent::TableStatusChanged {
+                table_number,
+                status,
+                occurred_at: now,
+            },
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainEvent::ItemAdded { .. } => "item_added",
+            DomainEvent::ItemStatusChanged { .. } => "item_status_changed",
+            DomainEvent::ItemReady { .. } => "item_ready",
+            DomainEvent::ItemServed { .. } => "item_served",
+            DomainEvent::ItemCancelled { .. } => "item_cancelled",
+            DomainEvent::ItemsMoved { .. } => "items_moved",
+            DomainEvent::TableStatusChanged { .. } => "table_status_changed",
+            DomainEvent::TableClosed { .. } => "table_closed",
+            DomainEvent::WaitlistJoined { .. } => "waitlist_joined",
+            DomainEvent::WaitlistCalled { .. } => "waitlist_called",
+        }
+    }
+
+    pub fn occurred_at(&self) -> NaiveDateTime {
+        match self {
+            DomainEvent::ItemAdded { occurred_at, .. }
+            | DomainEvent::ItemStatusChanged { occurred_at, .. }
+            | DomainEvent::ItemReady { occurred_at, .. }
+            | DomainEvent::ItemServed { occurred_at, .. }
+            | DomainEvent::ItemCancelled { occurred_at, .. }
+            | DomainEvent::ItemsMoved { occurred_at, .. }
+            | DomainEvent::TableStatusChanged { occurred_at, .. }
+            | DomainEvent::TableClosed { occurred_at, .. }
+            | DomainEvent::WaitlistJoined { occurred_at, .. }
+            | DomainEvent::WaitlistCalled { occurred_at, .. } => *occurred_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn ready_items_get_their_own_event() {
+        let now = Local::now().naive_local();
+        let mut item = Item::new(String::from("Ramen"), 1, now).unwrap();
+        item.transition_to(ItemStatus::Cooking, now).unwrap();
+        let cooking = DomainEvent::item_status_changed(1, item.clone(), now);
+        assert_eq!(cooking.name(), "item_status_changed");
+
+        item.transition_to(ItemStatus::Ready, now).unwrap();
+        let ready = DomainEvent::item_status_changed(1, item.clone(), now);
+        assert_eq!(ready.name(), "item_ready");
+
+        item.transition_to(ItemStatus::Served, now).unwrap();
+        let served = DomainEvent::item_status_changed(1, item, now);
+        assert_eq!(served.name(), "item_served");
+    }
+
+    #[test]
+    fn leaving_a_table_closes_it() {
+        let now = Local::now().naive_local();
+        let seated = DomainEvent::table_status_changed(1, TableStatus::Seated, now);
+        assert_eq!(seated.name(), "table_status_changed");
+        let left = DomainEvent::table_status_changed(1, TableStatus::NeedsCleaning, now);
+        assert_eq!(left.name(), "table_closed");
+        assert_eq!(DomainEvent::NAMES.contains(&left.name()), true);
+    }
+}
@@ -0,0 +1,156 @@
+use chrono::{Duration, NaiveDateTime};
+
+/// How long a stored response is replayed for retries of the same request.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct IdempotencyPolicy {
+    pub window: Duration,
+    /// How long the request in flight holds its key. A retry after that takes
+    /// the key over, as the server handling the request may have died.
+    pub lease: Duration,
+}
+
+impl Default for IdempotencyPolicy {
+    fn default() -> Self {
+        IdempotencyPolicy {
+            window: Duration::hours(24),
+            lease: Duration::minutes(1),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+/// A client-chosen `Idempotency-Key` and what happened to the request first
+/// sent with it. `response` stays empty while that request is in flight.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct IdempotencyRecord {
+    pub key: String,
+    /// Hash of the request the key was first used with.
+    pub fingerprint: String,
+    pub response: Option<StoredResponse>,
+    pub expires_at: NaiveDateTime,
+    /// Until when the request in flight holds the key.
+    pub locked_until: NaiveDateTime,
+}
+
+impl IdempotencyRecord {
+    pub const MAX_KEY_LENGTH: usize = 255;
+
+    pub fn new(
+        key: String,
+        fingerprint: String,
+        policy: &IdempotencyPolicy,
+        now: NaiveDateTime,
+    ) -> Result<Self, String> {
+        if key.is_empty() || key.len() > Self::MAX_KEY_LENGTH {
+            return Err(format!(
+                "idempotency key should be 1 to {} characters long",
+                Self::MAX_KEY_LENGTH
+            ));
+        }
+        Ok(IdempotencyRecord {
+            key,
+            fingerprint,
+            response: None,
+            expires_at: now + policy.window,
+            locked_until: now + policy.lease,
+        })
+    }
+
+    pub fn of(
+        key: String,
+        fingerprint: String,
+        response: Option<StoredResponse>,
+        expires_at: NaiveDateTime,
+        locked_until: NaiveDateTime,
+    ) -> Self {
+        IdempotencyRecord {
+            key,
+            fingerprint,
+            response,
+            expires_at,
+            locked_until,
+        }
+    }
+
+    pub fn is_expired(&self, now: NaiveDateTime) -> bool {
+        self.expires_at <= now
+    }
+
+    /// Whether the request first sent with the key may still answer it.
+    pub fn is_locked(&self, now: NaiveDateTime) -> bool {
+        self.response.is_none() && now < self.locked_until
+    }
+
+    pub fn matches(&self, fingerprint: &str) -> bool {
+        self.fingerprint == fingerprint
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn empty_key() {
+        let record = IdempotencyRecord::new(
+            String::from(""),
+            String::from("hash"),
+            &IdempotencyPolicy::default(),
+            Local::now().naive_local(),
+        );
+        assert_eq!(record.is_err(), true);
+    }
+
+    #[test]
+    fn record_expires_after_the_window() {
+        let now = Local::now().naive_local();
+        let policy = IdempotencyPolicy {
+            window: Duration::minutes(10),
+            ..IdempotencyPolicy::default()
+        };
+        let record =
+            IdempotencyRecord::new(String::from("k"), String::from("hash"), &policy, now).unwrap();
+        assert_eq!(record.is_expired(now + Duration::minutes(9)), false);
+        assert_eq!(record.is_expired(now + Duration::minutes(10)), true);
+    }
+
+    #[test]
+    fn record_matches_only_the_same_request() {
+        let record = IdempotencyRecord::new(
+            String::from("k"),
+            String::from("hash"),
+            &IdempotencyPolicy::default(),
+            Local::now().naive_local(),
+        )
+        .unwrap();
+        assert_eq!(record.matches("hash"), true);
+        assert_eq!(record.matches("other"), false);
+    }
+
+    #[test]
+    fn lease_runs_out_unless_answered() {
+        let now = Local::now().naive_local();
+        let mut record = IdempotencyRecord::new(
+            String::from("k"),
+            String::from("hash"),
+            &IdempotencyPolicy::default(),
+            now,
+        )
+        .unwrap();
+        assert_eq!(record.is_locked(now + Duration::seconds(59)), true);
+        assert_eq!(record.is_locked(now + Duration::minutes(1)), false);
+
+        record.response = Some(StoredResponse {
+            status: 201,
+            body: String::new(),
+        });
+        assert_eq!(record.is_locked(now), false);
+    }
+}
@@ -1,8 +1,51 @@
 use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
 
 use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    Ordered,
+    Cooking,
+    Ready,
+    Served,
+}
+
+impl ItemStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemStatus::Ordered => "ordered",
+            ItemStatus::Cooking => "cooking",
+            ItemStatus::Ready => "ready",
+            ItemStatus::Served => "served",
+        }
+    }
+}
+
+impl fmt::Display for ItemStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ItemStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ordered" => Ok(ItemStatus::Ordered),
+            "cooking" => Ok(ItemStatus::Cooking),
+            "ready" => Ok(ItemStatus::Ready),
+            "served" => Ok(ItemStatus::Served),
+            _ => Err(format!("unknown item status: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Item {
     pub id: Option<u32>,
     pub name: String,
@@ -10,10 +53,52 @@ pub struct Item {
     pub table_number: Option<u32>,
     pub start_cooking_at: NaiveDateTime,
     pub finish_cooking_at: NaiveDateTime,
+    pub status: ItemStatus,
+    pub actual_start_cooking_at: Option<NaiveDateTime>,
+    pub actual_finish_cooking_at: Option<NaiveDateTime>,
+    /// Bumped on every change so that concurrent edits can be detected.
+    pub version: u32,
+}
+
+/// How long an order is planned to take: `minutes_per_step` for every
+/// `quantity_step` items it has begun, up to `max_minutes`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CookingPolicy {
+    pub quantity_step: u32,
+    pub minutes_per_step: u32,
+    pub max_minutes: u32,
+}
+
+impl Default for CookingPolicy {
+    fn default() -> Self {
+        CookingPolicy {
+            quantity_step: 10,
+            minutes_per_step: 5,
+            max_minutes: 15,
+        }
+    }
+}
+
+impl CookingPolicy {
+    pub fn cooking_duration(&self, quantity: u32) -> Duration {
+        let steps = i64::from(quantity / self.quantity_step.max(1)) + 1;
+        Duration::minutes(
+            (steps * i64::from(self.minutes_per_step)).min(i64::from(self.max_minutes)),
+        )
+    }
 }
 
 impl Item {
     pub fn new(name: String, quantity: u32, now: NaiveDateTime) -> Result<Self, String> {
+        Item::with_policy(name, quantity, now, &CookingPolicy::default())
+    }
+
+    pub fn with_policy(
+        name: String,
+        quantity: u32,
+        now: NaiveDateTime,
+        policy: &CookingPolicy,
+    ) -> Result<Self, String> {
         if name.is_empty() {
             return Err(String::from("name should not be empty"));
         }
@@ -21,11 +106,7 @@ impl Item {
             return Err(String::from("quantity should not be negative number"));
         }
         let start_cooking_at = now;
-        let finish_cooking_at = match quantity {
-            n if n < 10 => now + Duration::minutes(5),
-            n if n < 20 => now + Duration::minutes(10),
-            _ => now + Duration::minutes(15),
-        };
+        let finish_cooking_at = now + policy.cooking_duration(quantity);
         Ok(Item {
             id: None,
             name,
@@ -33,9 +114,14 @@ impl Item {
             table_number: None,
             start_cooking_at,
             finish_cooking_at,
+            status: ItemStatus::Ordered,
+            actual_start_cooking_at: None,
+            actual_finish_cooking_at: None,
+            version: 1,
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn of(
         id: u32,
         name: String,
@@ -43,6 +129,10 @@ impl Item {
         table_number: u32,
         start_cooking_at: NaiveDateTime,
         finish_cooking_at: NaiveDateTime,
+        status: ItemStatus,
+        actual_start_cooking_at: Option<NaiveDateTime>,
+        actual_finish_cooking_at: Option<NaiveDateTime>,
+        version: u32,
     ) -> Self {
         Item {
             id: Some(id),
@@ -51,16 +141,83 @@ impl Item {
             table_number: Some(table_number),
             start_cooking_at,
             finish_cooking_at,
+            status,
+            actual_start_cooking_at,
+            actual_finish_cooking_at,
+            version,
+        }
+    }
+
+    /// Whether the item is still at the version a client last saw. No
+    /// expectation matches any version.
+    pub fn matches_version(&self, expected: Option<u32>) -> bool {
+        expected.is_none() || expected == Some(self.version)
+    }
+
+    pub fn cooking_duration(&self) -> Duration {
+        self.finish_cooking_at - self.start_cooking_at
+    }
+
+    /// The finish time the kitchen is currently expected to hit: the actual
+    /// finish once the item is ready, otherwise the actual start plus the
+    /// planned cooking duration, falling back to the planned finish time.
+    pub fn estimated_finish_at(&self) -> NaiveDateTime {
+        match (self.actual_start_cooking_at, self.actual_finish_cooking_at) {
+            (_, Some(finished_at)) => finished_at,
+            (Some(started_at), None) => started_at + self.cooking_duration(),
+            (None, None) => self.finish_cooking_at,
         }
     }
 
     // TODO use Mock instead of now argument
     pub fn time_to_finish(self, now: NaiveDateTime) -> Duration {
-        match self.finish_cooking_at.cmp(&now) {
+        if self.actual_finish_cooking_at.is_some() {
+            return Duration::minutes(0);
+        }
+        let estimated_finish_at = self.estimated_finish_at();
+        match estimated_finish_at.cmp(&now) {
             Ordering::Equal | Ordering::Less => Duration::minutes(0),
-            Ordering::Greater => self.finish_cooking_at - now,
+            Ordering::Greater => estimated_finish_at - now,
+        }
+    }
+
+    pub fn overdue(&self, now: NaiveDateTime) -> Duration {
+        if self.actual_finish_cooking_at.is_some() {
+            return Duration::minutes(0);
+        }
+        let estimated_finish_at = self.estimated_finish_at();
+        match estimated_finish_at.cmp(&now) {
+            Ordering::Less => now - estimated_finish_at,
+            Ordering::Equal | Ordering::Greater => Duration::minutes(0),
         }
     }
+
+    pub fn transition_to(&mut self, status: ItemStatus, now: NaiveDateTime) -> Result<(), String> {
+        match (self.status, status) {
+            (ItemStatus::Ordered, ItemStatus::Cooking) => {
+                self.actual_start_cooking_at = Some(now);
+            }
+            (ItemStatus::Ordered, ItemStatus::Ready) | (ItemStatus::Cooking, ItemStatus::Ready) => {
+                self.actual_finish_cooking_at = Some(now);
+            }
+            (ItemStatus::Ready, ItemStatus::Served) => {}
+            (from, to) => {
+                return Err(format!("item cannot move from {} to {}", from, to));
+            }
+        }
+        self.status = status;
+        Ok(())
+    }
+}
+
+/// A cheap summary of a table's items that changes whenever one of them is
+/// added, changed, moved or voided.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ItemsRevision {
+    pub item_count: u64,
+    pub version_sum: u64,
+    /// In UTC, whatever time zone the database stamps `updated_at` in.
+    pub last_updated_at: Option<NaiveDateTime>,
 }
 
 #[cfg(test)]
@@ -69,6 +226,14 @@ mod test {
     use chrono::Local;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn matches_version() {
+        let item = Item::new(String::from("Gyoza"), 2, Local::now().naive_local()).unwrap();
+        assert_eq!(item.matches_version(None), true);
+        assert_eq!(item.matches_version(Some(1)), true);
+        assert_eq!(item.matches_version(Some(2)), false);
+    }
+
     #[test]
     fn new_item_with_0_quantity() {
         assert_eq!(
@@ -115,6 +280,25 @@ mod test {
         );
     }
 
+    #[test]
+    fn new_item_with_a_cooking_policy() {
+        let now = Local::now().naive_local();
+        let policy = CookingPolicy {
+            quantity_step: 4,
+            minutes_per_step: 3,
+            max_minutes: 10,
+        };
+        let planned = |quantity| {
+            Item::with_policy(String::from("Gyoza"), quantity, now, &policy)
+                .unwrap()
+                .cooking_duration()
+        };
+        assert_eq!(planned(3), Duration::minutes(3));
+        assert_eq!(planned(4), Duration::minutes(6));
+        assert_eq!(planned(8), Duration::minutes(9));
+        assert_eq!(planned(100), Duration::minutes(10));
+    }
+
     #[test]
     fn time_to_finish() {
         let now = Local::now().naive_local();
@@ -127,4 +311,57 @@ mod test {
         let quantity_20 = Item::new(String::from("sandwich"), 20, now).unwrap();
         assert_eq!(quantity_20.time_to_finish(now), Duration::minutes(15));
     }
+
+    #[test]
+    fn time_to_finish_after_late_start() {
+        let now = Local::now().naive_local();
+        let mut item = Item::new(String::from("sandwich"), 1, now).unwrap();
+        item.transition_to(ItemStatus::Cooking, now + Duration::minutes(3))
+            .unwrap();
+
+        assert_eq!(
+            item.actual_start_cooking_at,
+            Some(now + Duration::minutes(3))
+        );
+        assert_eq!(item.clone().time_to_finish(now), Duration::minutes(8));
+        assert_eq!(
+            item.overdue(now + Duration::minutes(10)),
+            Duration::minutes(2)
+        );
+    }
+
+    #[test]
+    fn time_to_finish_after_early_finish() {
+        let now = Local::now().naive_local();
+        let mut item = Item::new(String::from("sandwich"), 1, now).unwrap();
+        item.transition_to(ItemStatus::Cooking, now).unwrap();
+        item.transition_to(ItemStatus::Ready, now + Duration::minutes(2))
+            .unwrap();
+
+        assert_eq!(item.estimated_finish_at(), now + Duration::minutes(2));
+        assert_eq!(item.clone().time_to_finish(now), Duration::minutes(0));
+        assert_eq!(
+            item.overdue(now + Duration::minutes(30)),
+            Duration::minutes(0)
+        );
+    }
+
+    #[test]
+    fn overdue_without_progress() {
+        let now = Local::now().naive_local();
+        let item = Item::new(String::from("sandwich"), 1, now).unwrap();
+        assert_eq!(item.overdue(now), Duration::minutes(0));
+        assert_eq!(
+            item.overdue(now + Duration::minutes(12)),
+            Duration::minutes(7)
+        );
+    }
+
+    #[test]
+    fn invalid_status_transition() {
+        let now = Local::now().naive_local();
+        let mut item = Item::new(String::from("sandwich"), 1, now).unwrap();
+        assert_eq!(item.transition_to(ItemStatus::Served, now).is_err(), true);
+        assert_eq!(item.status, ItemStatus::Ordered);
+    }
 }
@@ -0,0 +1,137 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::domain::event::DomainEvent;
+use crate::domain::item::Item;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemEventKind {
+    Added,
+    StatusChanged,
+    Ready,
+    Cancelled,
+}
+
+impl ItemEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemEventKind::Added => "added",
+            ItemEventKind::StatusChanged => "status_changed",
+            ItemEventKind::Ready => "ready",
+            ItemEventKind::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for ItemEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ItemEventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "added" => Ok(ItemEventKind::Added),
+            "status_changed" => Ok(ItemEventKind::StatusChanged),
+            "ready" => Ok(ItemEventKind::Ready),
+            "cancelled" => Ok(ItemEventKind::Cancelled),
+            _ => Err(format!("unknown item event: {}", s)),
+        }
+    }
+}
+
+/// Something that happened to an item, as pushed to kitchen displays and
+/// table tablets. `item` is the item as it was right after the change.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct ItemEvent {
+    pub kind: ItemEventKind,
+    pub table_number: u32,
+    pub item: Item,
+    pub occurred_at: NaiveDateTime,
+}
+
+impl ItemEvent {
+    /// The part of a domain event that kitchen displays and tablets follow.
+    /// Events that are not about a single item have none.
+    pub fn from_domain(event: &DomainEvent) -> Option<ItemEvent> {
+        let (kind, table_number, item) = match event {
+            DomainEvent::ItemAdded {
+                table_number, item, ..
+            } => (ItemEventKind::Added, table_number, item),
+            DomainEvent::ItemStatusChanged {
+                table_number, item, ..
+            }
+            | DomainEvent::ItemServed {
+                table_number, item, ..
+            } => (ItemEventKind::StatusChanged, table_number, item),
+            DomainEvent::ItemReady {
+                table_number, item, ..
+            } => (ItemEventKind::Ready, table_number, item),
+            DomainEvent::ItemCancelled {
+                table_number, item, ..
+            } => (ItemEventKind::Cancelled, table_number, item),
+            DomainEvent::ItemsMoved { .. }
+            | DomainEvent::TableStatusChanged { .. }
+            | DomainEvent::TableClosed { .. }
+            | DomainEvent::WaitlistJoined { .. }
+            | DomainEvent::WaitlistCalled { .. } => return None,
+        };
+        Some(ItemEvent::of(
+            kind,
+            *table_number,
+            item.clone(),
+            event.occurred_at(),
+        ))
+    }
+
+    pub fn of(
+        kind: ItemEventKind,
+        table_number: u32,
+        item: Item,
+        occurred_at: NaiveDateTime,
+    ) -> Self {
+        ItemEvent {
+            kind,
+            table_number,
+            item,
+            occurred_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::item_transfer::ItemTransfer;
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn item_events_follow_domain_events() {
+        let now = Local::now().naive_local();
+        let item = Item::new(String::from("Ramen"), 1, now).unwrap();
+        let added = DomainEvent::ItemAdded {
+            table_number: 2,
+            item: item.clone(),
+            occurred_at: now,
+        };
+        assert_eq!(
+            ItemEvent::from_domain(&added),
+            Some(ItemEvent::of(ItemEventKind::Added, 2, item, now))
+        );
+
+        let moved = DomainEvent::ItemsMoved {
+            transfer: ItemTransfer::merge(1, 2).unwrap(),
+            moved_items: 3,
+            occurred_at: now,
+        };
+        assert_eq!(ItemEvent::from_domain(&moved), None);
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferKind {
+    Move,
+    Merge,
+}
+
+impl TransferKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferKind::Move => "move",
+            TransferKind::Merge => "merge",
+        }
+    }
+}
+
+impl fmt::Display for TransferKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Items changing tables, either because guests moved (`Move`, optionally
+/// only some of the items) or because two parties share one bill (`Merge`).
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ItemTransfer {
+    pub from_table_number: u32,
+    pub to_table_number: u32,
+    pub item_ids: Option<Vec<u32>>,
+    pub kind: TransferKind,
+}
+
+impl ItemTransfer {
+    pub fn move_items(
+        from_table_number: u32,
+        to_table_number: u32,
+        item_ids: Option<Vec<u32>>,
+    ) -> Result<Self, String> {
+        if from_table_number == to_table_number {
+            return Err(String::from("items should move to another table"));
+        }
+        if matches!(&item_ids, Some(ids) if ids.is_empty()) {
+            return Err(String::from("selected items should not be empty"));
+        }
+        Ok(ItemTransfer {
+            from_table_number,
+            to_table_number,
+            item_ids,
+            kind: TransferKind::Move,
+        })
+    }
+
+    pub fn merge(from_table_number: u32, into_table_number: u32) -> Result<Self, String> {
+        if from_table_number == into_table_number {
+            return Err(String::from("a table cannot be merged into itself"));
+        }
+        Ok(ItemTransfer {
+            from_table_number,
+            to_table_number: into_table_number,
+            item_ids: None,
+            kind: TransferKind::Merge,
+        })
+    }
+
+    pub fn moves_all_items(&self) -> bool {
+        self.item_ids.is_none()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn move_to_same_table() {
+        assert_eq!(ItemTransfer::move_items(1, 1, None).is_err(), true);
+        assert_eq!(ItemTransfer::merge(2, 2).is_err(), true);
+    }
+
+    #[test]
+    fn move_selected_items() {
+        assert_eq!(ItemTransfer::move_items(1, 2, Some(vec![])).is_err(), true);
+
+        let transfer = ItemTransfer::move_items(1, 2, Some(vec![3, 4])).unwrap();
+        assert_eq!(transfer.moves_all_items(), false);
+        assert_eq!(transfer.kind, TransferKind::Move);
+    }
+
+    #[test]
+    fn merge_moves_all_items() {
+        let transfer = ItemTransfer::merge(1, 2).unwrap();
+        assert_eq!(transfer.moves_all_items(), true);
+        assert_eq!(transfer.kind, TransferKind::Merge);
+    }
+}
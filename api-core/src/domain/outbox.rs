@@ -0,0 +1,60 @@
+use chrono::{Duration, NaiveDateTime};
+
+use crate::domain::event::DomainEvent;
+
+/// A domain event stored in the same transaction as the change it
+/// describes, waiting to be relayed. `event` is empty when the stored
+/// payload can no longer be read.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OutboxEntry {
+    pub id: u64,
+    pub event: Option<DomainEvent>,
+    /// Failed deliveries so far.
+    pub attempts: u32,
+    /// The subscribers that already handled the event.
+    pub handled_by: Vec<String>,
+    pub next_attempt_at: NaiveDateTime,
+}
+
+/// Exponential backoff between delivery attempts: `base_delay` after the
+/// first failure, doubling up to `max_delay`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RetryPolicy {
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            base_delay: Duration::seconds(5),
+            max_delay: Duration::minutes(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// When to try again after the `attempts`-th failed attempt.
+    pub fn next_attempt_at(&self, attempts: u32, now: NaiveDateTime) -> NaiveDateTime {
+        let doublings = attempts.saturating_sub(1).min(20);
+        let delay = self.base_delay * 2_i32.pow(doublings);
+        now + delay.min(self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_maximum() {
+        let policy = RetryPolicy::default();
+        let now = Local::now().naive_local();
+        assert_eq!(policy.next_attempt_at(1, now), now + Duration::seconds(5));
+        assert_eq!(policy.next_attempt_at(2, now), now + Duration::seconds(10));
+        assert_eq!(policy.next_attempt_at(4, now), now + Duration::seconds(40));
+        assert_eq!(policy.next_attempt_at(50, now), now + Duration::minutes(10));
+    }
+}
@@ -0,0 +1,243 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::domain::table::Table;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReservationStatus {
+    Booked,
+    Seated,
+    Cancelled,
+}
+
+impl ReservationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReservationStatus::Booked => "booked",
+            ReservationStatus::Seated => "seated",
+            ReservationStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl fmt::Display for ReservationStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ReservationStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "booked" => Ok(ReservationStatus::Booked),
+            "seated" => Ok(ReservationStatus::Seated),
+            "cancelled" => Ok(ReservationStatus::Cancelled),
+            _ => Err(format!("unknown reservation status: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Reservation {
+    pub id: Option<u32>,
+    pub guest_name: String,
+    pub guest_phone: String,
+    pub party_size: u32,
+    pub table_number: u32,
+    pub starts_at: NaiveDateTime,
+    pub ends_at: NaiveDateTime,
+    pub status: ReservationStatus,
+}
+
+impl Reservation {
+    pub fn new(
+        guest_name: String,
+        guest_phone: String,
+        party_size: u32,
+        table_number: u32,
+        starts_at: NaiveDateTime,
+        ends_at: NaiveDateTime,
+    ) -> Result<Self, String> {
+        if guest_name.is_empty() {
+            return Err(String::from("guest name should not be empty"));
+        }
+        if guest_phone.is_empty() {
+            return Err(String::from("guest phone should not be empty"));
+        }
+        if party_size < 1 {
+            return Err(String::from("party size should be at least 1"));
+        }
+        if ends_at <= starts_at {
+            return Err(String::from("time slot should end after it starts"));
+        }
+        Ok(Reservation {
+            id: None,
+            guest_name,
+            guest_phone,
+            party_size,
+            table_number,
+            starts_at,
+            ends_at,
+            status: ReservationStatus::Booked,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn of(
+        id: u32,
+        guest_name: String,
+        guest_phone: String,
+        party_size: u32,
+        table_number: u32,
+        starts_at: NaiveDateTime,
+        ends_at: NaiveDateTime,
+        status: ReservationStatus,
+    ) -> Self {
+        Reservation {
+            id: Some(id),
+            guest_name,
+            guest_phone,
+            party_size,
+            table_number,
+            starts_at,
+            ends_at,
+            status,
+        }
+    }
+
+    pub fn fits(&self, table: &Table) -> bool {
+        self.table_number == table.number && table.can_seat(self.party_size)
+    }
+
+    /// Two reservations conflict when they are both still booked for the same
+    /// table and their time slots overlap.
+    pub fn conflicts_with(&self, other: &Reservation) -> bool {
+        if self.id.is_some() && self.id == other.id {
+            return false;
+        }
+        self.status == ReservationStatus::Booked
+            && other.status == ReservationStatus::Booked
+            && self.table_number == other.table_number
+            && self.starts_at < other.ends_at
+            && other.starts_at < self.ends_at
+    }
+
+    pub fn seat(&mut self) -> Result<(), String> {
+        match self.status {
+            ReservationStatus::Booked => {
+                self.status = ReservationStatus::Seated;
+                Ok(())
+            }
+            status => Err(format!("reservation is already {}", status)),
+        }
+    }
+
+    pub fn cancel(&mut self) -> Result<(), String> {
+        match self.status {
+            ReservationStatus::Booked => {
+                self.status = ReservationStatus::Cancelled;
+                Ok(())
+            }
+            status => Err(format!("reservation is already {}", status)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::table::TableStatus;
+    use chrono::{Duration, Local};
+    use pretty_assertions::assert_eq;
+
+    fn reservation(table_number: u32, starts_at: NaiveDateTime, hours: i64) -> Reservation {
+        Reservation::new(
+            String::from("Tanaka"),
+            String::from("090-0000-0000"),
+            2,
+            table_number,
+            starts_at,
+            starts_at + Duration::hours(hours),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn new_reservation_with_invalid_time_slot() {
+        let now = Local::now().naive_local();
+        let res = Reservation::new(
+            String::from("Tanaka"),
+            String::from("090-0000-0000"),
+            2,
+            1,
+            now,
+            now,
+        );
+        assert_eq!(res.is_err(), true);
+    }
+
+    #[test]
+    fn new_reservation_with_empty_party() {
+        let now = Local::now().naive_local();
+        let res = Reservation::new(
+            String::from("Tanaka"),
+            String::from("090-0000-0000"),
+            0,
+            1,
+            now,
+            now + Duration::hours(2),
+        );
+        assert_eq!(res.is_err(), true);
+    }
+
+    #[test]
+    fn fits_table_capacity() {
+        let now = Local::now().naive_local();
+        let reservation = reservation(1, now, 2);
+        let table = |number, capacity| {
+            Table::of(
+                number,
+                capacity,
+                String::from("main"),
+                TableStatus::Free,
+                None,
+            )
+        };
+        assert_eq!(reservation.fits(&table(1, 2)), true);
+        assert_eq!(reservation.fits(&table(1, 1)), false);
+        assert_eq!(reservation.fits(&table(2, 4)), false);
+    }
+
+    #[test]
+    fn conflicts_with_overlapping_slot() {
+        let now = Local::now().naive_local();
+        let booked = reservation(1, now, 2);
+        assert_eq!(
+            reservation(1, now + Duration::hours(1), 2).conflicts_with(&booked),
+            true
+        );
+        assert_eq!(
+            reservation(1, now + Duration::hours(2), 2).conflicts_with(&booked),
+            false
+        );
+        assert_eq!(
+            reservation(2, now + Duration::hours(1), 2).conflicts_with(&booked),
+            false
+        );
+    }
+
+    #[test]
+    fn cancelled_reservation_does_not_conflict() {
+        let now = Local::now().naive_local();
+        let mut booked = reservation(1, now, 2);
+        booked.cancel().unwrap();
+        assert_eq!(reservation(1, now, 2).conflicts_with(&booked), false);
+        assert_eq!(booked.seat().is_err(), true);
+    }
+}
@@ -0,0 +1,147 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Serialize;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Waiter,
+    Cook,
+    Manager,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Waiter => "waiter",
+            Role::Cook => "cook",
+            Role::Manager => "manager",
+            Role::Admin => "admin",
+        }
+    }
+
+    pub fn allows(&self, permission: Permission) -> bool {
+        match permission {
+            Permission::MarkItemsReady => matches!(self, Role::Cook | Role::Admin),
+            Permission::VoidServedItems | Permission::ViewAuditLog => {
+                matches!(self, Role::Manager | Role::Admin)
+            }
+            Permission::ManageStaff | Permission::ManageDevices | Permission::ManageWebhooks => {
+                matches!(self, Role::Admin)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "waiter" => Ok(Role::Waiter),
+            "cook" => Ok(Role::Cook),
+            "manager" => Ok(Role::Manager),
+            "admin" => Ok(Role::Admin),
+            _ => Err(format!("unknown role: {}", s)),
+        }
+    }
+}
+
+/// Operations restricted to some roles. Anything not listed here is open to
+/// every active staff member.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Permission {
+    MarkItemsReady,
+    VoidServedItems,
+    ManageStaff,
+    ManageDevices,
+    ManageWebhooks,
+    ViewAuditLog,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Staff {
+    pub id: Option<u32>,
+    pub name: String,
+    pub role: Role,
+    pub active: bool,
+    /// bcrypt hash of the login password. Staff without one cannot log in.
+    #[serde(skip)]
+    pub password_hash: Option<String>,
+}
+
+impl Staff {
+    pub fn new(name: String, role: Role) -> Result<Self, String> {
+        if name.is_empty() {
+            return Err(String::from("name should not be empty"));
+        }
+        Ok(Staff {
+            id: None,
+            name,
+            role,
+            active: true,
+            password_hash: None,
+        })
+    }
+
+    pub fn of(
+        id: u32,
+        name: String,
+        role: Role,
+        active: bool,
+        password_hash: Option<String>,
+    ) -> Self {
+        Staff {
+            id: Some(id),
+            name,
+            role,
+            active,
+            password_hash,
+        }
+    }
+
+    pub fn can(&self, permission: Permission) -> bool {
+        self.active && self.role.allows(permission)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn new_staff_with_empty_name() {
+        assert_eq!(Staff::new(String::from(""), Role::Waiter).is_err(), true);
+    }
+
+    #[test]
+    fn only_cooks_mark_items_ready() {
+        let cook = Staff::of(1, String::from("Kenji"), Role::Cook, true, None);
+        let waiter = Staff::of(2, String::from("Yuki"), Role::Waiter, true, None);
+        assert_eq!(cook.can(Permission::MarkItemsReady), true);
+        assert_eq!(waiter.can(Permission::MarkItemsReady), false);
+        assert_eq!(cook.can(Permission::VoidServedItems), false);
+    }
+
+    #[test]
+    fn only_managers_void_served_items() {
+        let manager = Staff::of(1, String::from("Aiko"), Role::Manager, true, None);
+        assert_eq!(manager.can(Permission::VoidServedItems), true);
+        assert_eq!(manager.can(Permission::ManageStaff), false);
+    }
+
+    #[test]
+    fn inactive_staff_has_no_permission() {
+        let admin = Staff::of(1, String::from("Root"), Role::Admin, false, None);
+        assert_eq!(admin.can(Permission::ManageStaff), false);
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TableStatus {
+    Free,
+    Seated,
+    NeedsCleaning,
+}
+
+impl TableStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TableStatus::Free => "free",
+            TableStatus::Seated => "seated",
+            TableStatus::NeedsCleaning => "needs_cleaning",
+        }
+    }
+}
+
+impl fmt::Display for TableStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TableStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "free" => Ok(TableStatus::Free),
+            "seated" => Ok(TableStatus::Seated),
+            "needs_cleaning" => Ok(TableStatus::NeedsCleaning),
+            _ => Err(format!("unknown table status: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct Table {
+    pub number: u32,
+    pub capacity: u32,
+    pub zone: String,
+    pub status: TableStatus,
+    pub seated_at: Option<NaiveDateTime>,
+}
+
+impl Table {
+    pub fn of(
+        number: u32,
+        capacity: u32,
+        zone: String,
+        status: TableStatus,
+        seated_at: Option<NaiveDateTime>,
+    ) -> Self {
+        Table {
+            number,
+            capacity,
+            zone,
+            status,
+            seated_at,
+        }
+    }
+
+    pub fn can_seat(&self, party_size: u32) -> bool {
+        party_size <= self.capacity
+    }
+
+    pub fn is_seated(&self) -> bool {
+        self.status == TableStatus::Seated
+    }
+
+    pub fn seat(&mut self, now: NaiveDateTime) -> Result<(), String> {
+        self.transition_to(TableStatus::Seated, now)
+    }
+
+    /// A table goes round free -> seated -> needs cleaning -> free.
+    pub fn transition_to(&mut self, status: TableStatus, now: NaiveDateTime) -> Result<(), String> {
+        match (self.status, status) {
+            (TableStatus::Free, TableStatus::Seated) => {
+                self.status = status;
+                self.seated_at = Some(now);
+                Ok(())
+            }
+            (TableStatus::Seated, TableStatus::NeedsCleaning)
+            | (TableStatus::NeedsCleaning, TableStatus::Free) => {
+                self.status = status;
+                self.seated_at = None;
+                Ok(())
+            }
+            (from, to) => Err(format!(
+                "table {} cannot move from {} to {}",
+                self.number, from, to
+            )),
+        }
+    }
+}
+
+/// Picks the smallest free table that can seat the party, optionally
+/// restricted to one zone, so that large tables stay available for large
+/// parties.
+pub fn pick_table_for<'a>(
+    tables: &'a [Table],
+    party_size: u32,
+    zone: Option<&str>,
+) -> Option<&'a Table> {
+    tables
+        .iter()
+        .filter(|table| table.status == TableStatus::Free && table.can_seat(party_size))
+        .filter(|table| zone.is_none() || zone == Some(table.zone.as_str()))
+        .min_by_key(|table| (table.capacity, table.number))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+
+    fn table(number: u32, capacity: u32, zone: &str, status: TableStatus) -> Table {
+        Table::of(number, capacity, zone.to_string(), status, None)
+    }
+
+    #[test]
+    fn can_seat_up_to_capacity() {
+        let table = table(1, 4, "main", TableStatus::Free);
+        assert_eq!(table.can_seat(4), true);
+        assert_eq!(table.can_seat(5), false);
+    }
+
+    #[test]
+    fn seat_only_free_table() {
+        let now = Local::now().naive_local();
+        let mut table = table(1, 4, "main", TableStatus::Free);
+        assert_eq!(table.seat(now).is_ok(), true);
+        assert_eq!(table.status, TableStatus::Seated);
+        assert_eq!(table.seated_at, Some(now));
+        assert_eq!(table.seat(now).is_err(), true);
+    }
+
+    #[test]
+    fn table_cycle() {
+        let now = Local::now().naive_local();
+        let mut table = table(1, 4, "main", TableStatus::Seated);
+        assert_eq!(table.transition_to(TableStatus::Free, now).is_err(), true);
+        assert_eq!(
+            table.transition_to(TableStatus::NeedsCleaning, now).is_ok(),
+            true
+        );
+        assert_eq!(table.seated_at, None);
+        assert_eq!(table.seat(now).is_err(), true);
+        assert_eq!(table.transition_to(TableStatus::Free, now).is_ok(), true);
+        assert_eq!(table.seat(now).is_ok(), true);
+    }
+
+    #[test]
+    fn pick_smallest_free_table() {
+        let tables = vec![
+            table(1, 6, "main", TableStatus::Free),
+            table(2, 2, "main", TableStatus::Seated),
+            table(3, 4, "main", TableStatus::Free),
+            table(4, 4, "terrace", TableStatus::Free),
+            table(5, 2, "main", TableStatus::NeedsCleaning),
+        ];
+        assert_eq!(pick_table_for(&tables, 2, None).unwrap().number, 3);
+        assert_eq!(pick_table_for(&tables, 5, None).unwrap().number, 1);
+        assert_eq!(
+            pick_table_for(&tables, 2, Some("terrace")).unwrap().number,
+            4
+        );
+        assert_eq!(pick_table_for(&tables, 7, None), None);
+    }
+}
@@ -0,0 +1,360 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Duration, NaiveDateTime};
+use serde::Serialize;
+
+use crate::domain::reservation::{Reservation, ReservationStatus};
+use crate::domain::table::{pick_table_for, Table, TableStatus};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitlistStatus {
+    Waiting,
+    Seated,
+    Left,
+}
+
+impl WaitlistStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WaitlistStatus::Waiting => "waiting",
+            WaitlistStatus::Seated => "seated",
+            WaitlistStatus::Left => "left",
+        }
+    }
+}
+
+impl fmt::Display for WaitlistStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WaitlistStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "waiting" => Ok(WaitlistStatus::Waiting),
+            "seated" => Ok(WaitlistStatus::Seated),
+            "left" => Ok(WaitlistStatus::Left),
+            _ => Err(format!("unknown waitlist status: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct WaitlistEntry {
+    pub id: Option<u32>,
+    pub guest_name: String,
+    pub guest_phone: String,
+    pub party_size: u32,
+    pub quoted_wait_minutes: i64,
+    pub status: WaitlistStatus,
+    pub joined_at: NaiveDateTime,
+}
+
+impl WaitlistEntry {
+    pub fn new(
+        guest_name: String,
+        guest_phone: String,
+        party_size: u32,
+        now: NaiveDateTime,
+    ) -> Result<Self, String> {
+        if guest_name.is_empty() {
+            return Err(String::from("guest name should not be empty"));
+        }
+        if guest_phone.is_empty() {
+            return Err(String::from("guest phone should not be empty"));
+        }
+        if party_size < 1 {
+            return Err(String::from("party size should be at least 1"));
+        }
+        Ok(WaitlistEntry {
+            id: None,
+            guest_name,
+            guest_phone,
+            party_size,
+            quoted_wait_minutes: 0,
+            status: WaitlistStatus::Waiting,
+            joined_at: now,
+        })
+    }
+
+    pub fn of(
+        id: u32,
+        guest_name: String,
+        guest_phone: String,
+        party_size: u32,
+        quoted_wait_minutes: i64,
+        status: WaitlistStatus,
+        joined_at: NaiveDateTime,
+    ) -> Self {
+        WaitlistEntry {
+            id: Some(id),
+            guest_name,
+            guest_phone,
+            party_size,
+            quoted_wait_minutes,
+            status,
+            joined_at,
+        }
+    }
+
+    pub fn seat(&mut self) -> Result<(), String> {
+        self.finish(WaitlistStatus::Seated)
+    }
+
+    pub fn leave(&mut self) -> Result<(), String> {
+        self.finish(WaitlistStatus::Left)
+    }
+
+    fn finish(&mut self, status: WaitlistStatus) -> Result<(), String> {
+        match self.status {
+            WaitlistStatus::Waiting => {
+                self.status = status;
+                Ok(())
+            }
+            current => Err(format!("waitlist entry is already {}", current)),
+        }
+    }
+}
+
+/// How long a party usually keeps a table and how long it takes to get the
+/// table ready again afterwards.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TurnoverPolicy {
+    pub dining_duration: Duration,
+    pub cleaning_duration: Duration,
+}
+
+impl Default for TurnoverPolicy {
+    fn default() -> Self {
+        TurnoverPolicy {
+            dining_duration: Duration::minutes(60),
+            cleaning_duration: Duration::minutes(10),
+        }
+    }
+}
+
+impl TurnoverPolicy {
+    fn table_available_at(&self, table: &Table, now: NaiveDateTime) -> NaiveDateTime {
+        match table.status {
+            TableStatus::Free => now,
+            TableStatus::Seated => {
+                let leaves_at =
+                    table.seated_at.unwrap_or(now) + self.dining_duration + self.cleaning_duration;
+                leaves_at.max(now)
+            }
+            TableStatus::NeedsCleaning => now + self.cleaning_duration,
+        }
+    }
+
+    /// The first moment from `from` on at which the table is not held by a
+    /// booked reservation for a whole dining duration.
+    fn next_slot(
+        &self,
+        table: &Table,
+        from: NaiveDateTime,
+        reservations: &[Reservation],
+    ) -> NaiveDateTime {
+        let mut booked: Vec<&Reservation> = reservations
+            .iter()
+            .filter(|r| r.table_number == table.number && r.status == ReservationStatus::Booked)
+            .collect();
+        booked.sort_by_key(|r| r.starts_at);
+
+        let mut slot = from;
+        for reservation in booked {
+            if reservation.starts_at < slot + self.dining_duration && slot < reservation.ends_at {
+                slot = reservation.ends_at + self.cleaning_duration;
+            }
+        }
+        slot
+    }
+
+    /// Picks the smallest free table that fits the party and is not held for
+    /// a reservation starting within the next dining duration.
+    pub fn pick_table(
+        &self,
+        party_size: u32,
+        tables: &[Table],
+        reservations: &[Reservation],
+        now: NaiveDateTime,
+    ) -> Option<Table> {
+        let available: Vec<Table> = tables
+            .iter()
+            .filter(|table| self.next_slot(table, now, reservations) <= now)
+            .cloned()
+            .collect();
+        pick_table_for(&available, party_size, None).cloned()
+    }
+
+    /// Quotes how long a party of `party_size` has to wait, assuming the
+    /// parties already waiting are seated first. Returns `None` when no table
+    /// is large enough for the party.
+    pub fn quote_wait(
+        &self,
+        party_size: u32,
+        tables: &[Table],
+        reservations: &[Reservation],
+        waiting_ahead: &[WaitlistEntry],
+        now: NaiveDateTime,
+    ) -> Option<Duration> {
+        let mut available_at: Vec<(&Table, NaiveDateTime)> = tables
+            .iter()
+            .map(|table| (table, self.table_available_at(table, now)))
+            .collect();
+
+        for entry in waiting_ahead {
+            if let Some((index, slot)) =
+                self.earliest_slot(entry.party_size, &available_at, reservations)
+            {
+                available_at[index].1 = slot + self.dining_duration + self.cleaning_duration;
+            }
+        }
+
+        self.earliest_slot(party_size, &available_at, reservations)
+            .map(|(_, slot)| (slot - now).max(Duration::minutes(0)))
+    }
+
+    fn earliest_slot(
+        &self,
+        party_size: u32,
+        available_at: &[(&Table, NaiveDateTime)],
+        reservations: &[Reservation],
+    ) -> Option<(usize, NaiveDateTime)> {
+        available_at
+            .iter()
+            .enumerate()
+            .filter(|(_, (table, _))| table.can_seat(party_size))
+            .map(|(index, (table, from))| (index, self.next_slot(table, *from, reservations)))
+            .min_by_key(|(index, slot)| (*slot, available_at[*index].0.capacity))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+
+    fn table(
+        number: u32,
+        capacity: u32,
+        status: TableStatus,
+        seated_at: Option<NaiveDateTime>,
+    ) -> Table {
+        Table::of(number, capacity, String::from("main"), status, seated_at)
+    }
+
+    fn waiting(party_size: u32, now: NaiveDateTime) -> WaitlistEntry {
+        WaitlistEntry::new(
+            String::from("Sato"),
+            String::from("080-0000-0000"),
+            party_size,
+            now,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn new_entry_with_empty_party() {
+        let now = Local::now().naive_local();
+        let res = WaitlistEntry::new(String::from("Sato"), String::from("080"), 0, now);
+        assert_eq!(res.is_err(), true);
+    }
+
+    #[test]
+    fn seat_or_leave_only_once() {
+        let now = Local::now().naive_local();
+        let mut entry = waiting(2, now);
+        assert_eq!(entry.seat().is_ok(), true);
+        assert_eq!(entry.leave().is_err(), true);
+    }
+
+    #[test]
+    fn quote_free_table() {
+        let now = Local::now().naive_local();
+        let policy = TurnoverPolicy::default();
+        let tables = vec![table(1, 4, TableStatus::Free, None)];
+        assert_eq!(
+            policy.quote_wait(2, &tables, &[], &[], now),
+            Some(Duration::minutes(0))
+        );
+        assert_eq!(policy.quote_wait(5, &tables, &[], &[], now), None);
+    }
+
+    #[test]
+    fn quote_from_table_turnover() {
+        let now = Local::now().naive_local();
+        let policy = TurnoverPolicy::default();
+        let tables = vec![
+            table(1, 2, TableStatus::Seated, Some(now - Duration::minutes(40))),
+            table(2, 4, TableStatus::Seated, Some(now - Duration::minutes(10))),
+        ];
+        // table 1 frees up after 20 minutes of dining and 10 of cleaning
+        assert_eq!(
+            policy.quote_wait(2, &tables, &[], &[], now),
+            Some(Duration::minutes(30))
+        );
+        // a party of 4 has to wait for table 2
+        assert_eq!(
+            policy.quote_wait(4, &tables, &[], &[], now),
+            Some(Duration::minutes(60))
+        );
+        // the party ahead takes table 1, so the next one gets table 2
+        assert_eq!(
+            policy.quote_wait(2, &tables, &[], &[waiting(2, now)], now),
+            Some(Duration::minutes(60))
+        );
+    }
+
+    #[test]
+    fn pick_table_not_held_by_reservation() {
+        let now = Local::now().naive_local();
+        let policy = TurnoverPolicy::default();
+        let tables = vec![
+            table(1, 2, TableStatus::Free, None),
+            table(2, 4, TableStatus::Free, None),
+        ];
+        let reservation = Reservation::new(
+            String::from("Tanaka"),
+            String::from("090-0000-0000"),
+            2,
+            1,
+            now + Duration::minutes(30),
+            now + Duration::minutes(120),
+        )
+        .unwrap();
+        assert_eq!(policy.pick_table(2, &tables, &[], now).unwrap().number, 1);
+        assert_eq!(
+            policy
+                .pick_table(2, &tables, &[reservation], now)
+                .unwrap()
+                .number,
+            2
+        );
+    }
+
+    #[test]
+    fn quote_around_reservations() {
+        let now = Local::now().naive_local();
+        let policy = TurnoverPolicy::default();
+        let tables = vec![table(1, 4, TableStatus::Free, None)];
+        let reservation = Reservation::new(
+            String::from("Tanaka"),
+            String::from("090-0000-0000"),
+            4,
+            1,
+            now + Duration::minutes(30),
+            now + Duration::minutes(120),
+        )
+        .unwrap();
+        assert_eq!(
+            policy.quote_wait(2, &tables, &[reservation], &[], now),
+            Some(Duration::minutes(130))
+        );
+    }
+}
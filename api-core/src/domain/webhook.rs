@@ -0,0 +1,357 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::domain::event::DomainEvent;
+use crate::domain::outbox::RetryPolicy;
+
+/// How hard webhook deliveries are tried.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct WebhookPolicy {
+    pub retry: RetryPolicy,
+    /// Attempts after which a delivery is given up.
+    pub max_attempts: u32,
+    /// Failed attempts in a row after which a subscription is disabled.
+    pub disable_after: u32,
+}
+
+impl Default for WebhookPolicy {
+    fn default() -> Self {
+        WebhookPolicy {
+            retry: RetryPolicy::default(),
+            max_attempts: 8,
+            disable_after: 20,
+        }
+    }
+}
+
+/// An outside system that wants to be told about some domain events.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct WebhookSubscription {
+    pub id: Option<u32>,
+    pub url: String,
+    /// Key the payloads are signed with. It is only shown once, when the
+    /// subscription is created.
+    #[serde(skip)]
+    pub secret: String,
+    /// Names of the events to deliver; all of them when empty.
+    pub event_types: Vec<String>,
+    pub active: bool,
+    pub consecutive_failures: u32,
+}
+
+impl WebhookSubscription {
+    pub fn new(url: String, secret: String, event_types: Vec<String>) -> Result<Self, String> {
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("webhook url must be http(s): {}", url));
+        }
+        if secret.is_empty() {
+            return Err(String::from("webhook secret must not be empty"));
+        }
+        if let Some(unknown) = event_types
+            .iter()
+            .find(|name| !DomainEvent::NAMES.contains(&name.as_str()))
+        {
+            return Err(format!("unknown event type: {}", unknown));
+        }
+        Ok(WebhookSubscription {
+            id: None,
+            url,
+            secret,
+            event_types,
+            active: true,
+            consecutive_failures: 0,
+        })
+    }
+
+    pub fn of(
+        id: u32,
+        url: String,
+        secret: String,
+        event_types: Vec<String>,
+        active: bool,
+        consecutive_failures: u32,
+    ) -> Self {
+        WebhookSubscription {
+            id: Some(id),
+            url,
+            secret,
+            event_types,
+            active,
+            consecutive_failures,
+        }
+    }
+
+    pub fn wants(&self, event: &DomainEvent) -> bool {
+        self.active
+            && (self.event_types.is_empty() || self.event_types.iter().any(|n| n == event.name()))
+    }
+
+    /// Counts a failed attempt and disables the subscription once too many
+    /// failed in a row.
+    pub fn record_failure(&mut self, policy: &WebhookPolicy) {
+        self.consecutive_failures += 1;
+        if self.consecutive_failures >= policy.disable_after {
+            self.active = false;
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+    }
+
+    pub fn disable(&mut self) {
+        self.active = false;
+    }
+
+    /// Turns a subscription back on, e.g. once the receiving end is fixed.
+    pub fn enable(&mut self) {
+        self.active = true;
+        self.consecutive_failures = 0;
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+impl WebhookDeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookDeliveryStatus::Pending => "pending",
+            WebhookDeliveryStatus::Delivered => "delivered",
+            WebhookDeliveryStatus::Failed => "failed",
+        }
+    }
+}
+
+impl fmt::Display for WebhookDeliveryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookDeliveryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(WebhookDeliveryStatus::Pending),
+            "delivered" => Ok(WebhookDeliveryStatus::Delivered),
+            "failed" => Ok(WebhookDeliveryStatus::Failed),
+            _ => Err(format!("unknown webhook delivery status: {}", s)),
+        }
+    }
+}
+
+/// One event on its way to one subscription, kept as the delivery log.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub id: Option<u64>,
+    pub subscription_id: u32,
+    pub event_type: String,
+    /// The JSON body that is posted.
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    pub next_attempt_at: NaiveDateTime,
+    /// HTTP status of the last answer, if there was one.
+    pub response_status: Option<u16>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<NaiveDateTime>,
+}
+
+impl WebhookDelivery {
+    pub fn new(
+        subscription_id: u32,
+        event_type: String,
+        payload: String,
+        now: NaiveDateTime,
+    ) -> Self {
+        WebhookDelivery {
+            id: None,
+            subscription_id,
+            event_type,
+            payload,
+            status: WebhookDeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            response_status: None,
+            last_error: None,
+            delivered_at: None,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn of(
+        id: u64,
+        subscription_id: u32,
+        event_type: String,
+        payload: String,
+        status: WebhookDeliveryStatus,
+        attempts: u32,
+        next_attempt_at: NaiveDateTime,
+        response_status: Option<u16>,
+        last_error: Option<String>,
+        delivered_at: Option<NaiveDateTime>,
+    ) -> Self {
+        WebhookDelivery {
+            id: Some(id),
+            subscription_id,
+            event_type,
+            payload,
+            status,
+            attempts,
+            next_attempt_at,
+            response_status,
+            last_error,
+            delivered_at,
+        }
+    }
+
+    /// Records how an attempt went: the HTTP status of the answer, or why
+    /// there was none. Only a 2xx answer counts as delivered. Returns
+    /// whether it was.
+    pub fn record_attempt(
+        &mut self,
+        outcome: Result<u16, String>,
+        policy: &WebhookPolicy,
+        now: NaiveDateTime,
+    ) -> bool {
+        self.attempts += 1;
+        let (response_status, error) = match outcome {
+            Ok(status) if (200..300).contains(&status) => (Some(status), None),
+            Ok(status) => (Some(status), Some(format!("answered with {}", status))),
+            Err(e) => (None, Some(e)),
+        };
+        self.response_status = response_status;
+        let delivered = error.is_none();
+        self.last_error = error;
+        if delivered {
+            self.status = WebhookDeliveryStatus::Delivered;
+            self.delivered_at = Some(now);
+        } else if self.attempts >= policy.max_attempts {
+            self.status = WebhookDeliveryStatus::Failed;
+        } else {
+            self.next_attempt_at = policy.retry.next_attempt_at(self.attempts, now);
+        }
+        delivered
+    }
+
+    /// Gives up on a delivery that can no longer be made.
+    pub fn abandon(&mut self, reason: String) {
+        self.status = WebhookDeliveryStatus::Failed;
+        self.last_error = Some(reason);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::domain::item::Item;
+    use chrono::{Duration, Local};
+    use pretty_assertions::assert_eq;
+
+    fn subscription(event_types: Vec<&str>) -> Result<WebhookSubscription, String> {
+        WebhookSubscription::new(
+            String::from("https://example.com/hooks"),
+            String::from("secret"),
+            event_types.into_iter().map(String::from).collect(),
+        )
+    }
+
+    #[test]
+    fn subscriptions_only_name_known_events() {
+        assert_eq!(subscription(vec!["table_closed"]).is_ok(), true);
+        assert_eq!(subscription(vec!["table_opened"]).is_err(), true);
+        let ftp = WebhookSubscription::new(
+            String::from("ftp://example.com"),
+            String::from("secret"),
+            vec![],
+        );
+        assert_eq!(ftp.is_err(), true);
+    }
+
+    #[test]
+    fn subscriptions_get_the_events_they_want() {
+        let now = Local::now().naive_local();
+        let item = Item::new(String::from("Ramen"), 1, now).unwrap();
+        let added = DomainEvent::ItemAdded {
+            table_number: 1,
+            item,
+            occurred_at: now,
+        };
+        let closed = DomainEvent::TableClosed {
+            table_number: 1,
+            occurred_at: now,
+        };
+        let mut accounting = subscription(vec!["table_closed"]).unwrap();
+        assert_eq!(accounting.wants(&closed), true);
+        assert_eq!(accounting.wants(&added), false);
+        assert_eq!(subscription(vec![]).unwrap().wants(&added), true);
+
+        accounting.disable();
+        assert_eq!(accounting.wants(&closed), false);
+    }
+
+    #[test]
+    fn subscription_is_disabled_after_failures_in_a_row() {
+        let policy = WebhookPolicy {
+            disable_after: 3,
+            ..WebhookPolicy::default()
+        };
+        let mut subscription = subscription(vec![]).unwrap();
+        subscription.record_failure(&policy);
+        subscription.record_failure(&policy);
+        subscription.record_success();
+        subscription.record_failure(&policy);
+        subscription.record_failure(&policy);
+        assert_eq!(subscription.active, true);
+        subscription.record_failure(&policy);
+        assert_eq!(subscription.active, false);
+
+        subscription.enable();
+        assert_eq!(subscription.active, true);
+        assert_eq!(subscription.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn delivery_is_retried_until_it_gives_up() {
+        let policy = WebhookPolicy {
+            max_attempts: 2,
+            ..WebhookPolicy::default()
+        };
+        let now = Local::now().naive_local();
+        let mut delivery =
+            WebhookDelivery::new(1, String::from("table_closed"), String::from("{}"), now);
+
+        assert_eq!(delivery.record_attempt(Ok(500), &policy, now), false);
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Pending);
+        assert_eq!(delivery.response_status, Some(500));
+        assert_eq!(delivery.next_attempt_at, now + Duration::seconds(5));
+
+        let later = delivery.next_attempt_at;
+        let refused = Err(String::from("connection refused"));
+        assert_eq!(delivery.record_attempt(refused, &policy, later), false);
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Failed);
+        assert_eq!(delivery.response_status, None);
+    }
+
+    #[test]
+    fn delivery_succeeds_on_2xx() {
+        let policy = WebhookPolicy::default();
+        let now = Local::now().naive_local();
+        let mut delivery =
+            WebhookDelivery::new(1, String::from("item_served"), String::from("{}"), now);
+        assert_eq!(delivery.record_attempt(Ok(204), &policy, now), true);
+        assert_eq!(delivery.status, WebhookDeliveryStatus::Delivered);
+        assert_eq!(delivery.delivered_at, Some(now));
+        assert_eq!(delivery.last_error, None);
+    }
+}
@@ -3,4 +3,49 @@ pub enum Error {
     ItemInsertionError,
     ItemDeletionError,
     ItemNotFoundError,
+    ItemUpdateError,
+    InvalidItemStatusTransition,
+    ItemTransferError,
+    InvalidItemTransfer,
+    ItemVersionMismatch,
+    TableNotFoundError,
+    UnknownTable,
+    TableUpdateError,
+    TableNotAvailable,
+    TableNotSeated,
+    InvalidTableStatusTransition,
+    TableCapacityExceeded,
+    ReservationInsertionError,
+    ReservationNotFoundError,
+    ReservationUpdateError,
+    ReservationConflict,
+    InvalidReservationStatusTransition,
+    WaitlistEntryInsertionError,
+    WaitlistEntryNotFoundError,
+    WaitlistEntryUpdateError,
+    InvalidWaitlistStatusTransition,
+    NotificationError,
+    StaffInsertionError,
+    StaffNotFoundError,
+    StaffUpdateError,
+    StaffNameTaken,
+    PasswordTooShort,
+    Forbidden,
+    InvalidCredentials,
+    DeviceInsertionError,
+    DeviceNotFoundError,
+    DeviceUpdateError,
+    InvalidDeviceKeyOperation,
+    AuditInsertionError,
+    AuditQueryError,
+    InvalidIdempotencyKey,
+    IdempotencyKeyMismatch,
+    IdempotentRequestInProgress,
+    IdempotencyStoreError,
+    OutboxStoreError,
+    EventDeliveryError,
+    InvalidWebhookSubscription,
+    WebhookNotFoundError,
+    WebhookStoreError,
+    InvalidStoredRecord,
 }
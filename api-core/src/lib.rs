@@ -1,3 +1,4 @@
 pub mod domain;
 pub mod error;
+pub mod notifier;
 pub mod repository;
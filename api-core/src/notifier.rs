@@ -0,0 +1,63 @@
+use crate::domain::event::DomainEvent;
+use crate::domain::waitlist::WaitlistEntry;
+use crate::domain::webhook::{WebhookDelivery, WebhookSubscription};
+use crate::error::Error;
+use async_trait::async_trait;
+
+/// Sends a short text message to a guest on the waitlist, e.g. by SMS. The
+/// messages go out from the outbox, so a failure is retried rather than
+/// failing the request the guest was queued or seated with.
+#[async_trait]
+pub trait WaitlistNotifier {
+    async fn notify(&self, entry: &WaitlistEntry, message: String) -> Result<(), Error>;
+}
+
+/// Hands domain events to everything that subscribed to them.
+#[async_trait]
+pub trait EventPublisher {
+    /// Hands `event` to the subscribers not named in `handled_by`, the ones
+    /// that took it on an earlier attempt. `event_id` is the id of the
+    /// event's outbox entry.
+    async fn publish(
+        &self,
+        event_id: u64,
+        event: &DomainEvent,
+        handled_by: &[String],
+    ) -> Publication;
+}
+
+/// How handing an event to the subscribers went.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Publication {
+    /// Every subscriber that has handled the event by now.
+    pub handled_by: Vec<String>,
+    /// Why the others failed, one line per subscriber.
+    pub failures: Vec<String>,
+}
+
+impl Publication {
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Reacts to published domain events, e.g. by pushing them to connected
+/// screens or counting them for reports. An event can be handed over more
+/// than once; `event_id` stays the same every time, so it is what to tell
+/// a repeated event by.
+#[async_trait]
+pub trait EventSubscriber {
+    async fn handle(&self, event_id: u64, event: &DomainEvent) -> Result<(), Error>;
+}
+
+/// Posts a webhook delivery to its subscription, signed with the
+/// subscription's secret. Returns the HTTP status of the answer, or why no
+/// answer came back.
+#[async_trait]
+pub trait WebhookSender {
+    async fn send(
+        &self,
+        subscription: &WebhookSubscription,
+        delivery: &WebhookDelivery,
+    ) -> Result<u16, String>;
+}
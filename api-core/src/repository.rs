@@ -1,8 +1,49 @@
+pub mod audit_repository;
+pub mod device_repository;
+pub mod idempotency_repository;
 pub mod item_repository;
+pub mod outbox_repository;
+pub mod reservation_repository;
+pub mod staff_repository;
+pub mod table_repository;
+pub mod waitlist_repository;
+pub mod webhook_repository;
 
+use crate::notifier::{EventPublisher, WebhookSender};
+use crate::repository::audit_repository::AuditRepository;
+use crate::repository::device_repository::DeviceRepository;
+use crate::repository::idempotency_repository::IdempotencyRepository;
 use crate::repository::item_repository::ItemRepository;
+use crate::repository::outbox_repository::OutboxRepository;
+use crate::repository::reservation_repository::ReservationRepository;
+use crate::repository::staff_repository::StaffRepository;
+use crate::repository::table_repository::TableRepository;
+use crate::repository::waitlist_repository::WaitlistRepository;
+use crate::repository::webhook_repository::WebhookRepository;
 
 pub trait Repositories {
     type ItemRepo: ItemRepository;
+    type TableRepo: TableRepository;
+    type ReservationRepo: ReservationRepository;
+    type WaitlistRepo: WaitlistRepository;
+    type StaffRepo: StaffRepository;
+    type DeviceRepo: DeviceRepository;
+    type AuditRepo: AuditRepository;
+    type IdempotencyRepo: IdempotencyRepository;
+    type OutboxRepo: OutboxRepository;
+    type WebhookRepo: WebhookRepository;
+    type Events: EventPublisher;
+    type Webhooks: WebhookSender;
     fn item_repository(&self) -> &Self::ItemRepo;
+    fn table_repository(&self) -> &Self::TableRepo;
+    fn reservation_repository(&self) -> &Self::ReservationRepo;
+    fn waitlist_repository(&self) -> &Self::WaitlistRepo;
+    fn staff_repository(&self) -> &Self::StaffRepo;
+    fn device_repository(&self) -> &Self::DeviceRepo;
+    fn audit_repository(&self) -> &Self::AuditRepo;
+    fn idempotency_repository(&self) -> &Self::IdempotencyRepo;
+    fn outbox_repository(&self) -> &Self::OutboxRepo;
+    fn webhook_repository(&self) -> &Self::WebhookRepo;
+    fn event_publisher(&self) -> &Self::Events;
+    fn webhook_sender(&self) -> &Self::Webhooks;
 }
@@ -0,0 +1,10 @@
+use crate::domain::audit::{AuditFilter, AuditRecord};
+use crate::error::Error;
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait AuditRepository {
+    async fn append(&self, record: AuditRecord) -> Result<u64, Error>;
+    /// Newest records first.
+    async fn find(&self, filter: AuditFilter) -> Result<Vec<AuditRecord>, Error>;
+}
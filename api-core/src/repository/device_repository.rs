@@ -0,0 +1,15 @@
+use crate::domain::audit::AuditRecord;
+use crate::domain::device::Device;
+use crate::error::Error;
+use async_trait::async_trait;
+
+/// Changes are saved together with their `audit` record.
+#[async_trait]
+pub trait DeviceRepository {
+    async fn find_all(&self) -> Result<Vec<Device>, Error>;
+    async fn find_one(&self, device_id: u32) -> Result<Device, Error>;
+    async fn find_by_key_hash(&self, key_hash: &str) -> Result<Device, Error>;
+    /// Registers the device, filing `audit` with a snapshot of it.
+    async fn create(&self, device: Device, audit: AuditRecord) -> Result<u64, Error>;
+    async fn update(&self, device: Device, audit: AuditRecord) -> Result<(), Error>;
+}
@@ -0,0 +1,23 @@
+use crate::domain::idempotency::{IdempotencyRecord, StoredResponse};
+use crate::error::Error;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+#[async_trait]
+pub trait IdempotencyRepository {
+    async fn find_one(&self, key: &str) -> Result<Option<IdempotencyRecord>, Error>;
+    /// Fails with `IdempotentRequestInProgress` when the key is already taken.
+    async fn create(&self, record: IdempotencyRecord) -> Result<(), Error>;
+    /// Takes over a key left unanswered by a request whose lease ran out
+    /// before `now`, holding it until `locked_until`. Fails with
+    /// `IdempotentRequestInProgress` when another retry took it over first.
+    async fn reclaim(
+        &self,
+        key: &str,
+        now: NaiveDateTime,
+        locked_until: NaiveDateTime,
+    ) -> Result<(), Error>;
+    async fn complete(&self, key: &str, response: StoredResponse) -> Result<(), Error>;
+    async fn delete(&self, key: &str) -> Result<(), Error>;
+    async fn delete_expired(&self, now: NaiveDateTime) -> Result<u64, Error>;
+}
@@ -1,11 +1,53 @@
-use crate::domain::item::Item;
+use crate::domain::audit::AuditRecord;
+use crate::domain::item::{Item, ItemsRevision};
+use crate::domain::item_transfer::ItemTransfer;
+use crate::domain::table::Table;
 use crate::error::Error;
 use async_trait::async_trait;
+use chrono::NaiveDateTime;
 
+/// Every change is stored together with the domain event describing it, so
+/// that the event is relayed exactly when the change was made, and with its
+/// `audit` record.
 #[async_trait]
 pub trait ItemRepository {
     async fn find_all(&self, table_number: u32) -> Result<Vec<Item>, Error>;
     async fn find_one(&self, table_number: u32, item_id: u32) -> Result<Item, Error>;
-    async fn create(&self, table_number: u32, item: Item) -> Result<u64, Error>;
-    async fn delete(&self, table_number: u32, item_id: u32) -> Result<(), Error>;
+    /// Reads an item a change is about to build on. Repositories that read
+    /// from a replica read it from the primary instead, so that it reflects
+    /// the latest write.
+    async fn find_one_for_write(&self, table_number: u32, item_id: u32) -> Result<Item, Error>;
+    async fn find_revision(&self, table_number: u32) -> Result<ItemsRevision, Error>;
+    /// Adds the item, filing `audit` under its id with a snapshot of it.
+    async fn create(&self, table_number: u32, item: Item, audit: AuditRecord)
+        -> Result<u64, Error>;
+    /// Saves the item if it is still at `item.version`, bumping the version.
+    /// Fails with `ItemVersionMismatch` when someone else changed it first.
+    async fn update(
+        &self,
+        table_number: u32,
+        item: Item,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error>;
+    /// Deletes the item if it is still at `item.version`.
+    async fn delete(
+        &self,
+        table_number: u32,
+        item: Item,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error>;
+    /// Moves the items and, in the same transaction, saves the tables the
+    /// transfer changes: `seated_table`, the destination it seats, provided it
+    /// is still free, and `cleared_table`, the source it empties, provided it
+    /// is still seated.
+    async fn transfer(
+        &self,
+        transfer: ItemTransfer,
+        seated_table: Option<Table>,
+        cleared_table: Option<Table>,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<u64, Error>;
 }
@@ -0,0 +1,23 @@
+use crate::domain::outbox::OutboxEntry;
+use crate::error::Error;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+/// The events item changes left behind for the relay. Events are written by
+/// the item repository inside the transactions of the changes themselves.
+#[async_trait]
+pub trait OutboxRepository {
+    /// Undelivered entries that are due at `now`, oldest first.
+    async fn find_pending(&self, now: NaiveDateTime, limit: u32)
+        -> Result<Vec<OutboxEntry>, Error>;
+    async fn mark_delivered(&self, id: u64, now: NaiveDateTime) -> Result<(), Error>;
+    /// Keeps the entry for another attempt, noting the subscribers that
+    /// handled it so far.
+    async fn mark_failed(
+        &self,
+        id: u64,
+        error: String,
+        handled_by: &[String],
+        next_attempt_at: NaiveDateTime,
+    ) -> Result<(), Error>;
+}
@@ -0,0 +1,36 @@
+use crate::domain::audit::AuditRecord;
+use crate::domain::reservation::Reservation;
+use crate::domain::table::Table;
+use crate::error::Error;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+/// Changes are saved together with their `audit` record.
+#[async_trait]
+pub trait ReservationRepository {
+    async fn find_one(&self, reservation_id: u32) -> Result<Reservation, Error>;
+    async fn find_by_table(&self, table_number: u32) -> Result<Vec<Reservation>, Error>;
+    async fn find_booked_between(
+        &self,
+        from: NaiveDateTime,
+        until: NaiveDateTime,
+    ) -> Result<Vec<Reservation>, Error>;
+    /// Books a reservation. Its table stays locked from the capacity and
+    /// conflict checks until the insert commits, so two overlapping bookings
+    /// can't both get in. `audit` is filed with a snapshot of the booking.
+    async fn create(&self, reservation: Reservation, audit: AuditRecord) -> Result<u64, Error>;
+    /// Saves a reservation, checking one that is still booked the same way
+    /// `create` does. Only a reservation still booked in the database is
+    /// changed; otherwise this fails with `InvalidReservationStatusTransition`.
+    async fn update(&self, reservation: Reservation, audit: AuditRecord) -> Result<(), Error>;
+    /// Saves a seated reservation and its newly seated table together. Fails
+    /// with nothing written when the table is no longer free or the
+    /// reservation no longer booked.
+    async fn seat(
+        &self,
+        reservation: Reservation,
+        table: Table,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error>;
+}
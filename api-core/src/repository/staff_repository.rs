@@ -0,0 +1,15 @@
+use crate::domain::audit::AuditRecord;
+use crate::domain::staff::Staff;
+use crate::error::Error;
+use async_trait::async_trait;
+
+/// Changes are saved together with their `audit` record.
+#[async_trait]
+pub trait StaffRepository {
+    async fn find_all(&self) -> Result<Vec<Staff>, Error>;
+    async fn find_one(&self, staff_id: u32) -> Result<Staff, Error>;
+    async fn find_by_name(&self, name: &str) -> Result<Staff, Error>;
+    /// Adds the staff member, filing `audit` with a snapshot of them.
+    async fn create(&self, staff: Staff, audit: AuditRecord) -> Result<u64, Error>;
+    async fn update(&self, staff: Staff, audit: AuditRecord) -> Result<(), Error>;
+}
@@ -0,0 +1,26 @@
+use crate::domain::audit::AuditRecord;
+use crate::domain::table::{Table, TableStatus};
+use crate::error::Error;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+/// Changes are saved together with their `audit` record.
+#[async_trait]
+pub trait TableRepository {
+    async fn find_all(&self) -> Result<Vec<Table>, Error>;
+    async fn find_one(&self, table_number: u32) -> Result<Table, Error>;
+    /// Saves the table's new status along with its event, provided the table
+    /// still has the `previous` one. Fails with `TableNotAvailable` when
+    /// somebody else has changed it first.
+    async fn update(
+        &self,
+        table: Table,
+        previous: TableStatus,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error>;
+    /// Saves a table that was just seated, provided it is still free. Fails
+    /// with `TableNotAvailable` when somebody else has seated it first.
+    async fn seat(&self, table: Table, now: NaiveDateTime, audit: AuditRecord)
+        -> Result<(), Error>;
+}
@@ -0,0 +1,27 @@
+use crate::domain::audit::AuditRecord;
+use crate::domain::table::Table;
+use crate::domain::waitlist::WaitlistEntry;
+use crate::error::Error;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+/// Changes are saved together with their `audit` record.
+#[async_trait]
+pub trait WaitlistRepository {
+    async fn find_one(&self, entry_id: u32) -> Result<WaitlistEntry, Error>;
+    async fn find_waiting(&self) -> Result<Vec<WaitlistEntry>, Error>;
+    /// Saves the entry along with the event the guest is told about their
+    /// quoted wait from. `audit` is filed with a snapshot of the entry.
+    async fn create(&self, entry: WaitlistEntry, audit: AuditRecord) -> Result<u64, Error>;
+    async fn update(&self, entry: WaitlistEntry, audit: AuditRecord) -> Result<(), Error>;
+    /// Saves a seated entry and its newly seated table together, along with
+    /// the event the guest is called in from. Fails with nothing written when
+    /// the table is no longer free or the entry no longer waiting.
+    async fn seat(
+        &self,
+        entry: WaitlistEntry,
+        table: Table,
+        now: NaiveDateTime,
+        audit: AuditRecord,
+    ) -> Result<(), Error>;
+}
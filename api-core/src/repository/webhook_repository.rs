@@ -0,0 +1,49 @@
+use crate::domain::audit::AuditRecord;
+use crate::domain::webhook::{WebhookDelivery, WebhookSubscription};
+use crate::error::Error;
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+/// Changes to the subscriptions are saved together with their `audit` record.
+#[async_trait]
+pub trait WebhookRepository {
+    async fn find_all(&self) -> Result<Vec<WebhookSubscription>, Error>;
+    async fn find_one(&self, subscription_id: u32) -> Result<WebhookSubscription, Error>;
+    /// Adds the subscription, filing `audit` with a snapshot of it.
+    async fn create(
+        &self,
+        subscription: WebhookSubscription,
+        audit: AuditRecord,
+    ) -> Result<u64, Error>;
+    async fn update(
+        &self,
+        subscription: WebhookSubscription,
+        audit: AuditRecord,
+    ) -> Result<(), Error>;
+    /// Saves only how many deliveries to the subscription failed in a row,
+    /// which is bookkeeping rather than a change anyone audits.
+    async fn update_failures(&self, subscription: WebhookSubscription) -> Result<(), Error>;
+    /// Removes the subscription together with its delivery log.
+    async fn delete(&self, subscription_id: u32, audit: AuditRecord) -> Result<(), Error>;
+    /// Queues `delivery` for the outbox event `event_id`. An event handled
+    /// again queues nothing: with a delivery for the event and subscription
+    /// already there, this returns `None`.
+    async fn create_delivery(
+        &self,
+        event_id: u64,
+        delivery: WebhookDelivery,
+    ) -> Result<Option<u64>, Error>;
+    async fn update_delivery(&self, delivery: WebhookDelivery) -> Result<(), Error>;
+    /// Pending deliveries that are due at `now`, oldest first.
+    async fn find_due_deliveries(
+        &self,
+        now: NaiveDateTime,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, Error>;
+    /// The delivery log of a subscription, newest first.
+    async fn find_deliveries(
+        &self,
+        subscription_id: u32,
+        limit: u32,
+    ) -> Result<Vec<WebhookDelivery>, Error>;
+}
@@ -0,0 +1,238 @@
+use api_core::domain::{device::DeviceScope, staff::Staff};
+use axum::{
+    extract::OriginalUri,
+    http::{header::AUTHORIZATION, Method, Request, StatusCode},
+    middleware::Next,
+    response::IntoResponse,
+};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::AuthConfig,
+    modules::{Modules, ModulesImpl},
+};
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// What a signed token says about its bearer.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Claims {
+    pub sub: u32,
+    pub role: String,
+    pub exp: i64,
+}
+
+/// Signs and verifies the bearer tokens handed out by the login endpoint.
+#[derive(Clone)]
+pub struct TokenKeys {
+    encoding: EncodingKey,
+    decoding: DecodingKey,
+    ttl: Duration,
+}
+
+impl TokenKeys {
+    pub fn new(secret: &[u8], ttl: Duration) -> TokenKeys {
+        TokenKeys {
+            encoding: EncodingKey::from_secret(secret),
+            decoding: DecodingKey::from_secret(secret),
+            ttl,
+        }
+    }
+
+    pub fn from_config(config: &AuthConfig) -> TokenKeys {
+        let ttl = Duration::from_std(config.jwt_ttl).unwrap_or_else(|_| Duration::max_value());
+        TokenKeys::new(config.jwt_secret.as_bytes(), ttl)
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    pub fn issue(&self, staff: &Staff) -> Option<String> {
+        let claims = Claims {
+            sub: staff.id?,
+            role: staff.role.to_string(),
+            exp: (Utc::now() + self.ttl).timestamp(),
+        };
+        jsonwebtoken::encode(&Header::default(), &claims, &self.encoding).ok()
+    }
+
+    pub fn verify(&self, token: &str) -> Option<Claims> {
+        jsonwebtoken::decode::<Claims>(token, &self.decoding, &Validation::default())
+            .map(|data| data.claims)
+            .ok()
+    }
+}
+
+/// Rejects requests that carry neither a valid `Authorization: Bearer`
+/// token nor a valid device API key, and hands whichever it was on to the
+/// handlers. Devices are also held to the routes their scope opens up.
+pub async fn authenticate<B>(
+    mut req: Request<B>,
+    next: Next<B>,
+) -> Result<impl IntoResponse, StatusCode>
+where
+    B: Send,
+{
+    if let Some(key) = header_value(&req, API_KEY_HEADER) {
+        let modules = req
+            .extensions()
+            .get::<ModulesImpl>()
+            .cloned()
+            .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+        let device = modules
+            .device_usecase()
+            .authenticate(&key)
+            .await
+            .map_err(|_| StatusCode::UNAUTHORIZED)?;
+        let path = match req.extensions().get::<OriginalUri>() {
+            Some(OriginalUri(uri)) => uri.path().to_string(),
+            None => req.uri().path().to_string(),
+        };
+        if !device_may_access(&device.scope, req.method(), &path) {
+            return Err(StatusCode::FORBIDDEN);
+        }
+        req.extensions_mut().insert(device);
+        return Ok(next.run(req).await);
+    }
+
+    let keys = req
+        .extensions()
+        .get::<TokenKeys>()
+        .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+    let claims = header_value(&req, AUTHORIZATION.as_str())
+        .as_deref()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .and_then(|token| keys.verify(token))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    req.extensions_mut().insert(claims);
+    Ok(next.run(req).await)
+}
+
+fn header_value<B>(req: &Request<B>, name: &str) -> Option<String> {
+    req.headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+/// The route groups a device key opens up. Kitchen displays follow every
+/// table's items and mark them ready; table tablets order, follow and void
+/// their own table's items. Both may open the event stream, which checks the
+/// tables asked for itself.
+fn device_may_access(scope: &DeviceScope, method: &Method, path: &str) -> bool {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match scope {
+        DeviceScope::Kitchen => match segments.as_slice() {
+            ["tables"]
+            | ["tables", _, "items"]
+            | ["tables", _, "items", _]
+            | ["tables", _, "events"] => method == Method::GET,
+            ["tables", _, "items", _, "status"] => method == Method::PUT,
+            ["events"] => method == Method::GET,
+            _ => false,
+        },
+        DeviceScope::Table(table_number) => {
+            let own_table = table_number.to_string();
+            match segments.as_slice() {
+                ["tables", table, "item"] => *table == own_table && method == Method::POST,
+                ["tables", table, "items"] | ["tables", table, "events"] => {
+                    *table == own_table && method == Method::GET
+                }
+                ["tables", table, "items", _] => {
+                    *table == own_table && (method == Method::GET || method == Method::DELETE)
+                }
+                ["events"] => method == Method::GET,
+                _ => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use api_core::domain::staff::Role;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn issued_token_is_verified() {
+        let keys = TokenKeys::new(b"secret", Duration::minutes(5));
+        let cook = Staff::of(4, String::from("cook"), Role::Cook, true, None);
+        let token = keys.issue(&cook).unwrap();
+        let claims = keys.verify(&token).unwrap();
+        assert_eq!(claims.sub, 4);
+        assert_eq!(claims.role, "cook");
+    }
+
+    #[test]
+    fn token_signed_with_another_key_is_rejected() {
+        let keys = TokenKeys::new(b"secret", Duration::minutes(5));
+        let other_keys = TokenKeys::new(b"another-secret", Duration::minutes(5));
+        let cook = Staff::of(4, String::from("cook"), Role::Cook, true, None);
+        let token = other_keys.issue(&cook).unwrap();
+        assert_eq!(keys.verify(&token).is_none(), true);
+    }
+
+    #[test]
+    fn table_device_only_reaches_its_own_table() {
+        let scope = DeviceScope::Table(2);
+        assert_eq!(
+            device_may_access(&scope, &Method::GET, "/tables/2/items"),
+            true
+        );
+        assert_eq!(
+            device_may_access(&scope, &Method::POST, "/tables/2/item"),
+            true
+        );
+        assert_eq!(
+            device_may_access(&scope, &Method::GET, "/tables/3/items"),
+            false
+        );
+        assert_eq!(
+            device_may_access(&scope, &Method::GET, "/reservations/1"),
+            false
+        );
+        assert_eq!(
+            device_may_access(&scope, &Method::POST, "/tables/2/merge"),
+            false
+        );
+        assert_eq!(device_may_access(&scope, &Method::GET, "/events"), true);
+        assert_eq!(
+            device_may_access(&scope, &Method::GET, "/tables/2/events"),
+            true
+        );
+        assert_eq!(
+            device_may_access(&scope, &Method::GET, "/tables/3/events"),
+            false
+        );
+    }
+
+    #[test]
+    fn kitchen_device_reads_items_and_updates_status() {
+        let scope = DeviceScope::Kitchen;
+        assert_eq!(
+            device_may_access(&scope, &Method::GET, "/tables/4/items"),
+            true
+        );
+        assert_eq!(
+            device_may_access(&scope, &Method::PUT, "/tables/4/items/1/status"),
+            true
+        );
+        assert_eq!(
+            device_may_access(&scope, &Method::POST, "/tables/4/item"),
+            false
+        );
+        assert_eq!(device_may_access(&scope, &Method::GET, "/staff"), false);
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        let keys = TokenKeys::new(b"secret", Duration::minutes(-5));
+        let cook = Staff::of(4, String::from("cook"), Role::Cook, true, None);
+        let token = keys.issue(&cook).unwrap();
+        assert_eq!(keys.verify(&token).is_none(), true);
+    }
+}
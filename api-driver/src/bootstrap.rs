@@ -0,0 +1,70 @@
+use api_core::error::Error;
+use api_usecase::usecase::staff::MIN_PASSWORD_LEN;
+
+use crate::{
+    config::Config,
+    migrate,
+    modules::{Modules, ModulesImpl},
+};
+
+/// `api-driver create-admin <name>`: adds the first admin with the password
+/// read from stdin, since the migrations create no accounts to log in with.
+pub async fn create_admin(config: &Config, name: &str, password: &str) -> Result<(), String> {
+    migrate::prepare_database(config).await?;
+    let modules = ModulesImpl::new(config).await.map_err(|e| e.to_string())?;
+    bootstrap_admin(&modules, name, password).await?;
+    println!("admin {} added", name);
+    Ok(())
+}
+
+async fn bootstrap_admin(modules: &ModulesImpl, name: &str, password: &str) -> Result<(), String> {
+    match modules
+        .staff_usecase()
+        .bootstrap_admin(name.to_string(), password)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(Error::PasswordTooShort) => Err(format!(
+            "the password must be at least {} characters long",
+            MIN_PASSWORD_LEN
+        )),
+        Err(Error::Forbidden) => Err(String::from(
+            "there is an active admin already, who adds staff through the api",
+        )),
+        Err(e) => Err(format!("could not add the admin: {:?}", e)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn test_no_second_admin_is_bootstrapped() {
+        // the test database has its admin already
+        let modules = ModulesImpl::test().await.unwrap();
+        let result = bootstrap_admin(&modules, "intruder", "a long enough password").await;
+        assert_eq!(result.is_err(), true);
+        assert_eq!(
+            modules
+                .staff_usecase()
+                .authenticate("intruder", "a long enough password")
+                .await
+                .is_err(),
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn test_short_passwords_are_refused() {
+        let modules = ModulesImpl::test().await.unwrap();
+        let result = bootstrap_admin(&modules, "admin", "password").await;
+        assert_eq!(
+            result,
+            Err(String::from(
+                "the password must be at least 12 characters long"
+            ))
+        );
+    }
+}
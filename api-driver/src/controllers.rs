@@ -1 +1,21 @@
+pub mod audit;
+pub mod auth;
+pub mod device;
+pub mod event;
 pub mod item;
+pub mod reservation;
+pub mod staff;
+pub mod table;
+pub mod waitlist;
+pub mod webhook;
+
+use chrono::NaiveDateTime;
+
+/// Accepts both the ISO 8601 form and the `YYYY-MM-DD HH:MM:SS` form this API
+/// writes in its responses.
+pub fn parse_datetime(value: &str) -> Option<NaiveDateTime> {
+    value
+        .parse::<NaiveDateTime>()
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S"))
+        .ok()
+}
@@ -0,0 +1,216 @@
+use api_core::domain::{
+    actor::{Actor, ActorKind},
+    audit::{AuditFilter, AuditRecord},
+};
+use api_core::error::Error;
+use axum::{
+    extract::{Extension, Query},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::extractors::CurrentStaff;
+use crate::modules::{Modules, ModulesImpl};
+
+pub async fn query_audit(
+    Query(query): Query<JsonAuditQuery>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let actor_kind = match query.actor_kind {
+        Some(kind) => Some(
+            kind.parse::<ActorKind>()
+                .map_err(|_| StatusCode::BAD_REQUEST)?,
+        ),
+        None => None,
+    };
+    let filter = AuditFilter::new(
+        query.table_number,
+        query.item_id,
+        actor_kind,
+        query.actor_id,
+        query.limit,
+    )
+    .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let result = modules
+        .audit_usecase()
+        .query_audit(&Actor::Staff(staff), filter)
+        .await;
+    match result {
+        Ok(records) => {
+            let record_responses: Vec<JsonAuditRecordResponse> =
+                records.iter().map(JsonAuditRecordResponse::new).collect();
+            Ok(Json(record_responses))
+        }
+        Err(e) => match e {
+            Error::Forbidden => Err(StatusCode::FORBIDDEN),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct JsonAuditQuery {
+    pub table_number: Option<u32>,
+    pub item_id: Option<u32>,
+    pub actor_kind: Option<String>,
+    pub actor_id: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonAuditRecordResponse {
+    pub id: u32,
+    pub actor_kind: String,
+    pub actor_id: u32,
+    pub action: String,
+    pub table_number: Option<u32>,
+    pub item_id: Option<u32>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub recorded_at: String,
+}
+
+impl JsonAuditRecordResponse {
+    fn new(record: &AuditRecord) -> JsonAuditRecordResponse {
+        let parse = |snapshot: &Option<String>| {
+            snapshot
+                .as_deref()
+                .and_then(|json| serde_json::from_str(json).ok())
+        };
+        JsonAuditRecordResponse {
+            id: record.id.unwrap(),
+            actor_kind: record.actor_kind.to_string(),
+            actor_id: record.actor_id,
+            action: record.action.to_string(),
+            table_number: record.table_number,
+            item_id: record.item_id,
+            before: parse(&record.before),
+            after: parse(&record.after),
+            recorded_at: record
+                .recorded_at
+                .map(|recorded_at| recorded_at.to_string())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::Features;
+    use crate::controllers::auth::test::login;
+    use crate::controllers::item::{JsonItemAddingRequest, JsonItemAddlingResponse};
+    use crate::controllers::table::JsonTableStatusUpdateRequest;
+    use crate::modules::ModulesImpl;
+    use crate::routes;
+    use axum::http::header::AUTHORIZATION;
+    use axum_test_helper::TestClient;
+    use pretty_assertions::assert_eq;
+    use serial_test::serial;
+
+    async fn test_client() -> TestClient {
+        let test_app =
+            routes::router(ModulesImpl::test().await.unwrap(), &Features::default()).await;
+        TestClient::new(test_app)
+    }
+
+    #[tokio::test]
+    async fn test_waiter_cannot_read_audit() {
+        let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
+        let audit = client
+            .get("/audit")
+            .header(AUTHORIZATION, &waiter)
+            .send()
+            .await;
+        assert_eq!(audit.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_added_item_is_audited() {
+        let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
+        let manager = login(&client, "manager").await;
+        for status in ["needs_cleaning", "free", "seated"] {
+            client
+                .put("/tables/5/status")
+                .header(AUTHORIZATION, &waiter)
+                .json(&JsonTableStatusUpdateRequest {
+                    status: status.to_string(),
+                })
+                .send()
+                .await;
+        }
+        let post_item = client
+            .post("/tables/5/item")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemAddingRequest {
+                name: "Tempura".to_string(),
+                quantity: 1,
+            })
+            .send()
+            .await;
+        let response_text = post_item.text().await;
+        let response_json: JsonItemAddlingResponse = serde_json::from_str(&response_text).unwrap();
+
+        let audit = client
+            .get(&format!("/audit?item_id={}", response_json.item_id))
+            .header(AUTHORIZATION, &manager)
+            .send()
+            .await;
+        assert_eq!(audit.status(), StatusCode::OK);
+        let response_text = audit.text().await;
+        let records: Vec<JsonAuditRecordResponse> = serde_json::from_str(&response_text).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].action, "item_added");
+        assert_eq!(records[0].actor_kind, "staff");
+        assert_eq!(records[0].actor_id, 3);
+        assert_eq!(records[0].before.is_none(), true);
+        assert_eq!(records[0].after.as_ref().unwrap()["name"], "Tempura");
+
+        let unknown_actor_kind = client
+            .get("/audit?actor_kind=robot")
+            .header(AUTHORIZATION, &manager)
+            .send()
+            .await;
+        assert_eq!(unknown_actor_kind.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_table_status_change_is_audited() {
+        let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
+        let manager = login(&client, "manager").await;
+        for status in ["needs_cleaning", "free", "seated"] {
+            client
+                .put("/tables/5/status")
+                .header(AUTHORIZATION, &waiter)
+                .json(&JsonTableStatusUpdateRequest {
+                    status: status.to_string(),
+                })
+                .send()
+                .await;
+        }
+
+        let audit = client
+            .get("/audit?table_number=5&limit=1")
+            .header(AUTHORIZATION, &manager)
+            .send()
+            .await;
+        assert_eq!(audit.status(), StatusCode::OK);
+        let response_text = audit.text().await;
+        let records: Vec<JsonAuditRecordResponse> = serde_json::from_str(&response_text).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].action, "table_status_changed");
+        assert_eq!(records[0].actor_kind, "staff");
+        assert_eq!(records[0].actor_id, 3);
+        assert_eq!(records[0].before.as_ref().unwrap()["status"], "free");
+        assert_eq!(records[0].after.as_ref().unwrap()["status"], "seated");
+    }
+}
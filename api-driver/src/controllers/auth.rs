@@ -0,0 +1,120 @@
+use api_core::error::Error;
+use axum::{extract::Extension, http::StatusCode, response::IntoResponse, Json};
+
+use serde::{Deserialize, Serialize};
+
+use crate::auth::TokenKeys;
+use crate::modules::{Modules, ModulesImpl};
+
+pub async fn login(
+    Json(req): Json<JsonLoginRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    Extension(keys): Extension<TokenKeys>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .staff_usecase()
+        .authenticate(&req.name, &req.password)
+        .await;
+    match result {
+        Ok(staff) => {
+            let access_token = keys
+                .issue(&staff)
+                .ok_or(StatusCode::INTERNAL_SERVER_ERROR)?;
+            Ok(Json(JsonLoginResponse {
+                access_token,
+                token_type: "Bearer".to_string(),
+                expires_in: keys.ttl().num_seconds(),
+            }))
+        }
+        Err(e) => match e {
+            Error::InvalidCredentials => Err(StatusCode::UNAUTHORIZED),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonLoginRequest {
+    pub name: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonLoginResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::config::Features;
+    use crate::modules::ModulesImpl;
+    use crate::routes;
+    use axum::http::header::AUTHORIZATION;
+    use axum_test_helper::TestClient;
+    use pretty_assertions::assert_eq;
+
+    /// Logs in as one of the staff seeded by `init.sql` and returns the
+    /// `Authorization` header value to send with later requests.
+    pub(crate) async fn login(client: &TestClient, name: &str) -> String {
+        login_with(client, name, "password").await
+    }
+
+    pub(crate) async fn login_with(client: &TestClient, name: &str, password: &str) -> String {
+        let response = client
+            .post("/auth/login")
+            .json(&JsonLoginRequest {
+                name: name.to_string(),
+                password: password.to_string(),
+            })
+            .send()
+            .await;
+        let response_text = response.text().await;
+        let response_json: JsonLoginResponse = serde_json::from_str(&response_text).unwrap();
+        format!("Bearer {}", response_json.access_token)
+    }
+
+    async fn test_client() -> TestClient {
+        let test_app =
+            routes::router(ModulesImpl::test().await.unwrap(), &Features::default()).await;
+        TestClient::new(test_app)
+    }
+
+    #[tokio::test]
+    async fn test_login() {
+        let client = test_client().await;
+        let wrong_password = client
+            .post("/auth/login")
+            .json(&JsonLoginRequest {
+                name: "waiter".to_string(),
+                password: "wrong".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(wrong_password.status(), StatusCode::UNAUTHORIZED);
+
+        let token = login(&client, "waiter").await;
+        let get_tables = client
+            .get("/tables")
+            .header(AUTHORIZATION, token)
+            .send()
+            .await;
+        assert_eq!(get_tables.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_request_without_token() {
+        let client = test_client().await;
+        let no_token = client.get("/tables").send().await;
+        assert_eq!(no_token.status(), StatusCode::UNAUTHORIZED);
+
+        let forged_token = client
+            .get("/tables")
+            .header(AUTHORIZATION, "Bearer not.a.token")
+            .send()
+            .await;
+        assert_eq!(forged_token.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,240 @@
+use api_core::domain::{
+    actor::Actor,
+    device::{Device, DeviceScope},
+};
+use api_core::error::Error;
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::extractors::CurrentStaff;
+use crate::modules::{Modules, ModulesImpl};
+
+pub async fn query_devices(
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .device_usecase()
+        .query_devices(&Actor::Staff(staff))
+        .await;
+    match result {
+        Ok(devices) => {
+            let device_responses: Vec<JsonDeviceResponse> =
+                devices.iter().map(JsonDeviceResponse::new).collect();
+            Ok(Json(device_responses))
+        }
+        Err(e) => Err(device_error_status(e)),
+    }
+}
+
+pub async fn issue_device_key(
+    Json(req): Json<JsonDeviceKeyRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let scope =
+        DeviceScope::parse(&req.scope, req.table_number).map_err(|_| StatusCode::BAD_REQUEST)?;
+    if req.name.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let result = modules
+        .device_usecase()
+        .issue_key(&Actor::Staff(staff), req.name, scope)
+        .await;
+    match result {
+        Ok((device, api_key)) => Ok((
+            StatusCode::CREATED,
+            Json(JsonDeviceKeyResponse::new(&device, api_key)),
+        )),
+        Err(e) => Err(device_error_status(e)),
+    }
+}
+
+pub async fn rotate_device_key(
+    Path(device_id): Path<u32>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .device_usecase()
+        .rotate_key(&Actor::Staff(staff), device_id)
+        .await;
+    match result {
+        Ok((device, api_key)) => Ok(Json(JsonDeviceKeyResponse::new(&device, api_key))),
+        Err(e) => Err(device_error_status(e)),
+    }
+}
+
+pub async fn revoke_device_key(
+    Path(device_id): Path<u32>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .device_usecase()
+        .revoke_key(&Actor::Staff(staff), device_id)
+        .await;
+    match result {
+        Ok(device) => Ok(Json(JsonDeviceResponse::new(&device))),
+        Err(e) => Err(device_error_status(e)),
+    }
+}
+
+fn device_error_status(e: Error) -> StatusCode {
+    match e {
+        Error::Forbidden => StatusCode::FORBIDDEN,
+        Error::DeviceNotFoundError => StatusCode::NOT_FOUND,
+        Error::TableNotFoundError => StatusCode::UNPROCESSABLE_ENTITY,
+        Error::InvalidDeviceKeyOperation => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonDeviceKeyRequest {
+    pub name: String,
+    pub scope: String,
+    pub table_number: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonDeviceKeyResponse {
+    pub device: JsonDeviceResponse,
+    pub api_key: String,
+}
+
+impl JsonDeviceKeyResponse {
+    fn new(device: &Device, api_key: String) -> JsonDeviceKeyResponse {
+        JsonDeviceKeyResponse {
+            device: JsonDeviceResponse::new(device),
+            api_key,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonDeviceResponse {
+    pub id: u32,
+    pub name: String,
+    pub scope: String,
+    pub table_number: Option<u32>,
+    pub revoked: bool,
+}
+
+impl JsonDeviceResponse {
+    pub fn new(device: &Device) -> JsonDeviceResponse {
+        JsonDeviceResponse {
+            id: device.id.unwrap(),
+            name: device.name.clone(),
+            scope: device.scope.as_str().to_string(),
+            table_number: device.scope.table_number(),
+            revoked: device.revoked,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::auth::API_KEY_HEADER;
+    use crate::config::Features;
+    use crate::controllers::auth::test::login;
+    use crate::modules::ModulesImpl;
+    use crate::routes;
+    use axum::http::header::AUTHORIZATION;
+    use axum_test_helper::TestClient;
+    use pretty_assertions::assert_eq;
+
+    async fn test_client() -> TestClient {
+        let test_app =
+            routes::router(ModulesImpl::test().await.unwrap(), &Features::default()).await;
+        TestClient::new(test_app)
+    }
+
+    #[tokio::test]
+    async fn test_only_admin_issues_keys() {
+        let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
+        let issue = client
+            .post("/devices")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonDeviceKeyRequest {
+                name: "test-tablet".to_string(),
+                scope: "table".to_string(),
+                table_number: Some(2),
+            })
+            .send()
+            .await;
+        assert_eq!(issue.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_table_key_is_scoped_rotated_and_revoked() {
+        let client = test_client().await;
+        let admin = login(&client, "admin").await;
+        let issue = client
+            .post("/devices")
+            .header(AUTHORIZATION, &admin)
+            .json(&JsonDeviceKeyRequest {
+                name: "test-tablet".to_string(),
+                scope: "table".to_string(),
+                table_number: Some(2),
+            })
+            .send()
+            .await;
+        assert_eq!(issue.status(), StatusCode::CREATED);
+        let response_text = issue.text().await;
+        let issued: JsonDeviceKeyResponse = serde_json::from_str(&response_text).unwrap();
+        assert_eq!(issued.device.table_number, Some(2));
+
+        let own_table = client
+            .get("/tables/2/items")
+            .header(API_KEY_HEADER, &issued.api_key)
+            .send()
+            .await;
+        assert_eq!(own_table.status(), StatusCode::OK);
+
+        let other_table = client
+            .get("/tables/3/items")
+            .header(API_KEY_HEADER, &issued.api_key)
+            .send()
+            .await;
+        assert_eq!(other_table.status(), StatusCode::FORBIDDEN);
+
+        let rotate = client
+            .post(&format!("/devices/{}/rotate", issued.device.id))
+            .header(AUTHORIZATION, &admin)
+            .send()
+            .await;
+        assert_eq!(rotate.status(), StatusCode::OK);
+        let response_text = rotate.text().await;
+        let rotated: JsonDeviceKeyResponse = serde_json::from_str(&response_text).unwrap();
+
+        let old_key = client
+            .get("/tables/2/items")
+            .header(API_KEY_HEADER, &issued.api_key)
+            .send()
+            .await;
+        assert_eq!(old_key.status(), StatusCode::UNAUTHORIZED);
+
+        let revoke = client
+            .post(&format!("/devices/{}/revoke", issued.device.id))
+            .header(AUTHORIZATION, &admin)
+            .send()
+            .await;
+        assert_eq!(revoke.status(), StatusCode::OK);
+
+        let revoked_key = client
+            .get("/tables/2/items")
+            .header(API_KEY_HEADER, &rotated.api_key)
+            .send()
+            .await;
+        assert_eq!(revoked_key.status(), StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,272 @@
+use api_adapter::notifier::event_bus::PublishedItemEvent;
+use std::convert::Infallible;
+use std::time::Duration;
+
+use api_core::domain::{actor::Actor, item::Item, item_event::ItemEvent};
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Extension, Path, Query,
+    },
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+};
+use chrono::{Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+
+use crate::controllers::item::JsonItemResponse;
+use crate::extractors::{CurrentActor, LastEventId};
+use crate::modules::{Modules, ModulesImpl};
+
+/// Opens a WebSocket that pushes item events for one table, the tables of
+/// one station, or the whole kitchen when neither is given.
+pub async fn subscribe_events(
+    ws: WebSocketUpgrade,
+    Query(query): Query<JsonSubscriptionQuery>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    let subscription = subscription_for(query, &modules, &actor).await?;
+    // subscribe before the upgrade so nothing published meanwhile is missed
+    let events = modules.item_events().subscribe();
+    Ok(ws.on_upgrade(move |socket| forward_events(socket, events, subscription)))
+}
+
+/// How often the event stream refreshes the remaining and overdue minutes.
+const TICK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Streams a table's item events as Server-Sent Events, for screens that
+/// cannot use the WebSocket. Each event carries the bus id, so a client
+/// reconnecting with `Last-Event-ID` first gets what it missed. `tick` events
+/// with the remaining minutes are sent in between and have no id.
+pub async fn stream_table_events(
+    Path(table_number): Path<u32>,
+    LastEventId(last_event_id): LastEventId,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    if !actor.can_access_table(table_number) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let tables = modules
+        .table_usecase()
+        .query_tables()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !tables.iter().any(|table| table.number == table_number) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let (missed, mut events) = match last_event_id {
+        Some(last_event_id) => modules.item_events().subscribe_after(last_event_id),
+        None => (Vec::new(), modules.item_events().subscribe()),
+    };
+    let stream = async_stream::stream! {
+        for published in missed {
+            if published.event.table_number == table_number {
+                if let Some(event) = sse_event(&published) {
+                    yield Ok::<Event, Infallible>(event);
+                }
+            }
+        }
+        let mut ticks = tokio::time::interval(TICK_INTERVAL);
+        loop {
+            let next = tokio::select! {
+                received = events.recv() => Some(received),
+                _ = ticks.tick() => None,
+            };
+            match next {
+                Some(Ok(published)) if published.event.table_number == table_number => {
+                    if let Some(event) = sse_event(&published) {
+                        yield Ok(event);
+                    }
+                }
+                Some(Ok(_)) => {}
+                // the client reconnects with its Last-Event-ID and is caught
+                // up from the kept events
+                Some(Err(RecvError::Lagged(_))) | Some(Err(RecvError::Closed)) => break,
+                None => {
+                    if let Ok(items) = modules.item_usecase().query_items(table_number).await {
+                        if let Some(event) = tick_event(&items) {
+                            yield Ok(event);
+                        }
+                    }
+                }
+            }
+        }
+    };
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn sse_event(published: &PublishedItemEvent) -> Option<Event> {
+    Event::default()
+        .id(published.id.to_string())
+        .event(published.event.kind.as_str())
+        .json_data(JsonItemEvent::new(&published.event))
+        .ok()
+}
+
+fn tick_event(items: &[Item]) -> Option<Event> {
+    let now = Local::now().naive_local();
+    let remaining: Vec<JsonRemainingTime> = items
+        .iter()
+        .filter_map(|item| JsonRemainingTime::new(item, now))
+        .collect();
+    Event::default().event("tick").json_data(remaining).ok()
+}
+
+/// The tables a connection follows. The kitchen also follows tables added
+/// after it connected.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Subscription {
+    Kitchen,
+    Tables(Vec<u32>),
+}
+
+impl Subscription {
+    pub fn covers(&self, event: &ItemEvent) -> bool {
+        match self {
+            Subscription::Kitchen => true,
+            Subscription::Tables(table_numbers) => table_numbers.contains(&event.table_number),
+        }
+    }
+}
+
+async fn subscription_for(
+    query: JsonSubscriptionQuery,
+    modules: &ModulesImpl,
+    actor: &Actor,
+) -> Result<Subscription, StatusCode> {
+    let tables = modules
+        .table_usecase()
+        .query_tables()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let (subscription, table_numbers) = match (query.table_number, query.station) {
+        (Some(_), Some(_)) => return Err(StatusCode::BAD_REQUEST),
+        (Some(table_number), None) => {
+            if !tables.iter().any(|table| table.number == table_number) {
+                return Err(StatusCode::NOT_FOUND);
+            }
+            (Subscription::Tables(vec![table_number]), vec![table_number])
+        }
+        (None, Some(station)) => {
+            let table_numbers: Vec<u32> = tables
+                .iter()
+                .filter(|table| table.zone == station)
+                .map(|table| table.number)
+                .collect();
+            if table_numbers.is_empty() {
+                return Err(StatusCode::NOT_FOUND);
+            }
+            (Subscription::Tables(table_numbers.clone()), table_numbers)
+        }
+        (None, None) => (
+            Subscription::Kitchen,
+            tables.iter().map(|table| table.number).collect(),
+        ),
+    };
+    if !table_numbers
+        .iter()
+        .all(|table_number| actor.can_access_table(*table_number))
+    {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    Ok(subscription)
+}
+
+async fn forward_events(
+    mut socket: WebSocket,
+    mut events: Receiver<PublishedItemEvent>,
+    subscription: Subscription,
+) {
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(published) if subscription.covers(&published.event) => {
+                    let message = match serde_json::to_string(&JsonItemEvent::new(&published.event)) {
+                        Ok(message) => message,
+                        Err(_) => continue,
+                    };
+                    if socket.send(Message::Text(message)).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                // a slow client misses events rather than holding up the bus
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonSubscriptionQuery {
+    pub table_number: Option<u32>,
+    /// A station is a zone of the floor, e.g. `terrace`.
+    pub station: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonItemEvent {
+    pub event: String,
+    pub table_number: u32,
+    pub item: JsonItemResponse,
+    pub occurred_at: String,
+}
+
+impl JsonItemEvent {
+    pub fn new(event: &ItemEvent) -> JsonItemEvent {
+        JsonItemEvent {
+            event: event.kind.to_string(),
+            table_number: event.table_number,
+            item: JsonItemResponse::new(&event.item),
+            occurred_at: event.occurred_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonRemainingTime {
+    pub item_id: u32,
+    pub status: String,
+    pub remaining_cooking_minutes: i64,
+    pub overdue_minutes: i64,
+}
+
+impl JsonRemainingTime {
+    fn new(item: &Item, now: NaiveDateTime) -> Option<JsonRemainingTime> {
+        Some(JsonRemainingTime {
+            item_id: item.id?,
+            status: item.status.to_string(),
+            remaining_cooking_minutes: item.clone().time_to_finish(now).num_minutes(),
+            overdue_minutes: item.overdue(now).num_minutes(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use api_core::domain::item_event::ItemEventKind;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn subscriptions_cover_their_tables() {
+        let now = Local::now().naive_local();
+        let item = Item::new(String::from("Ramen"), 1, now).unwrap();
+        let event = ItemEvent::of(ItemEventKind::Added, 3, item, now);
+        assert_eq!(Subscription::Kitchen.covers(&event), true);
+        assert_eq!(Subscription::Tables(vec![3, 4]).covers(&event), true);
+        assert_eq!(Subscription::Tables(vec![1]).covers(&event), false);
+    }
+}
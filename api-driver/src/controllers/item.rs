@@ -1,19 +1,30 @@
 use std::collections::HashMap;
 
-use api_core::domain::item::Item;
+use api_core::domain::actor::Actor;
+use api_core::domain::item::{Item, ItemStatus, ItemsRevision};
 use api_core::error::Error;
+use api_usecase::usecase::idempotency::IdempotencyOutcome;
 use axum::{
     extract::{Extension, Path},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{
+        header::{HeaderName, CACHE_CONTROL, CONTENT_TYPE, ETAG, LAST_MODIFIED},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{Headers, IntoResponse, Response},
     Json,
 };
 
-use chrono::Local;
+use chrono::{Local, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 
+use crate::extractors::{
+    CurrentActor, IdempotencyKey, IfMatch, IfModifiedSince, IfNoneMatch, HTTP_DATE_FORMAT,
+};
 use crate::modules::{Modules, ModulesImpl};
 
+/// Set on responses replayed for a repeated `Idempotency-Key`.
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
 #[axum_macros::debug_handler]
 pub async fn query_item(
     Path(params): Path<HashMap<String, u32>>,
@@ -29,7 +40,7 @@ pub async fn query_item(
     match result {
         Ok(item) => {
             let json = JsonItemResponse::new(&item);
-            Ok(Json(json))
+            Ok((etag_header(&item), Json(json)))
         }
         Err(e) => match e {
             Error::ItemNotFoundError => Err(StatusCode::NOT_FOUND),
@@ -38,10 +49,31 @@ pub async fn query_item(
     }
 }
 
+/// Tablets poll this, so it is tagged with the table's items revision and a
+/// matching `If-None-Match` or `If-Modified-Since` is answered with 304
+/// without loading the items.
 pub async fn query_items(
     Path(table_number): Path<u32>,
+    if_none_match: IfNoneMatch,
+    if_modified_since: IfModifiedSince,
     Extension(modules): Extension<ModulesImpl>,
-) -> Result<impl IntoResponse, StatusCode> {
+) -> Result<Response, StatusCode> {
+    let revision = modules
+        .item_usecase()
+        .query_items_revision(table_number)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let etag = revision_etag(&revision);
+    let headers = revision_headers(&etag, revision.last_updated_at)?;
+    // If-Modified-Since only counts without If-None-Match (RFC 7232 section 6)
+    let unchanged = match if_none_match.0 {
+        Some(_) => if_none_match.matches(&etag),
+        None => if_modified_since.unchanged(revision.last_updated_at),
+    };
+    if unchanged {
+        return Ok((StatusCode::NOT_MODIFIED, headers, ()).into_response());
+    }
+
     let result = modules.item_usecase().query_items(table_number).await;
     match result {
         Ok(items) => {
@@ -49,7 +81,7 @@ pub async fn query_items(
             for item in items {
                 item_responses.push(JsonItemResponse::new(&item));
             }
-            Ok(Json(item_responses))
+            Ok((headers, Json(item_responses)).into_response())
         }
         Err(e) => match e {
             Error::ItemNotFoundError => Err(StatusCode::NOT_FOUND),
@@ -58,42 +90,272 @@ pub async fn query_items(
     }
 }
 
+/// The remaining and overdue minutes in a listing move with the clock, so the
+/// ETag is weak and only tracks what is stored.
+fn revision_etag(revision: &ItemsRevision) -> String {
+    format!(
+        "W/\"{}-{}-{}\"",
+        revision.item_count,
+        revision.version_sum,
+        revision
+            .last_updated_at
+            .map_or(0, |updated_at| updated_at.timestamp())
+    )
+}
+
+/// `last_updated_at` is in UTC on every backend, so it is sent as is.
+fn revision_headers(
+    etag: &str,
+    last_updated_at: Option<NaiveDateTime>,
+) -> Result<HeaderMap, StatusCode> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        ETAG,
+        HeaderValue::from_str(etag).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+    );
+    if let Some(updated_at) = last_updated_at {
+        let last_modified = updated_at.format(HTTP_DATE_FORMAT).to_string();
+        headers.insert(
+            LAST_MODIFIED,
+            HeaderValue::from_str(&last_modified).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
+        );
+    }
+    headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    Ok(headers)
+}
+
 pub async fn add_item(
     Path(table_number): Path<u32>,
+    IdempotencyKey(key): IdempotencyKey,
     Json(req): Json<JsonItemAddingRequest>,
     Extension(modules): Extension<ModulesImpl>,
-) -> Result<impl IntoResponse, StatusCode> {
-    let maybe_item = Item::new(req.name, req.quantity, Local::now().naive_local());
+    CurrentActor(actor): CurrentActor,
+) -> Result<Response, StatusCode> {
+    let key = match key {
+        Some(key) => key,
+        None => {
+            let (status, body) = match create_item(table_number, req, &modules, &actor).await {
+                Ok(answer) | Err(answer) => answer,
+            };
+            return Ok(json_response(status, body));
+        }
+    };
+
+    // the same key may only be reused by the same caller for the same order
+    let request = serde_json::to_string(&(actor.kind().as_str(), actor.id(), table_number, &req))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let outcome = modules
+        .idempotency_usecase()
+        .begin(&key, &request, Local::now().naive_local())
+        .await;
+    match outcome {
+        Ok(IdempotencyOutcome::Proceed) => {}
+        Ok(IdempotencyOutcome::Replay(stored)) => {
+            let status = StatusCode::from_u16(stored.status)
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            let mut response = json_response(status, stored.body);
+            response
+                .headers_mut()
+                .insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+            return Ok(response);
+        }
+        Err(e) => {
+            return match e {
+                Error::InvalidIdempotencyKey => Err(StatusCode::BAD_REQUEST),
+                Error::IdempotencyKeyMismatch => Err(StatusCode::UNPROCESSABLE_ENTITY),
+                Error::IdempotentRequestInProgress => Err(StatusCode::CONFLICT),
+                _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+            }
+        }
+    }
+
+    // a retry may only run the request again if this attempt wrote nothing
+    let (status, body, retriable) = match create_item(table_number, req, &modules, &actor).await {
+        Ok((status, body)) => (status, body, false),
+        Err((status, body)) => (status, body, status.is_server_error()),
+    };
+    let stored = if retriable {
+        modules.idempotency_usecase().abandon(&key).await
+    } else {
+        modules
+            .idempotency_usecase()
+            .complete(&key, status.as_u16(), body.clone())
+            .await
+    };
+    if stored.is_err() {
+        return Err(StatusCode::INTERNAL_SERVER_ERROR);
+    }
+    Ok(json_response(status, body))
+}
+
+/// Answers `Ok` once the item is saved, and `Err` if nothing was written.
+async fn create_item(
+    table_number: u32,
+    req: JsonItemAddingRequest,
+    modules: &ModulesImpl,
+    actor: &Actor,
+) -> Result<(StatusCode, String), (StatusCode, String)> {
+    let maybe_item = Item::with_policy(
+        req.name,
+        req.quantity,
+        Local::now().naive_local(),
+        modules.item_usecase().cooking_policy(),
+    );
     if maybe_item.is_err() {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err((StatusCode::BAD_REQUEST, String::new()));
+    }
+    let result = modules
+        .item_usecase()
+        .add_item(actor, table_number, maybe_item.unwrap())
+        .await;
+    match result {
+        Ok(id) => match serde_json::to_string(&JsonItemAddlingResponse { item_id: id }) {
+            Ok(body) => Ok((StatusCode::CREATED, body)),
+            Err(_) => Ok((StatusCode::INTERNAL_SERVER_ERROR, String::new())),
+        },
+        Err(e) => Err(match e {
+            Error::TableNotFoundError | Error::UnknownTable => {
+                (StatusCode::NOT_FOUND, String::new())
+            }
+            Error::TableNotSeated => (StatusCode::CONFLICT, String::new()),
+            Error::Forbidden => (StatusCode::FORBIDDEN, String::new()),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, String::new()),
+        }),
+    }
+}
+
+/// Items are tagged with their version; send it back in `If-Match` to change
+/// the item only if nobody else has.
+fn etag_header(item: &Item) -> Headers<[(HeaderName, HeaderValue); 1]> {
+    let etag = HeaderValue::from_str(&format!("\"{}\"", item.version))
+        .expect("a quoted number is a valid header value");
+    Headers([(ETAG, etag)])
+}
+
+fn json_response(status: StatusCode, body: String) -> Response {
+    if body.is_empty() {
+        return status.into_response();
+    }
+    let mut response = (status, body).into_response();
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+pub async fn update_item_status(
+    Path(params): Path<HashMap<String, u32>>,
+    IfMatch(expected_version): IfMatch,
+    Json(req): Json<JsonItemStatusUpdateRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    let status = req
+        .status
+        .parse::<ItemStatus>()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let result = modules
+        .item_usecase()
+        .update_item_status(
+            &actor,
+            *params.get("table_number").unwrap(),
+            *params.get("item_id").unwrap(),
+            status,
+            expected_version,
+            Local::now().naive_local(),
+        )
+        .await;
+    match result {
+        Ok(item) => Ok((etag_header(&item), Json(JsonItemResponse::new(&item)))),
+        Err(e) => match e {
+            Error::ItemNotFoundError => Err(StatusCode::NOT_FOUND),
+            Error::ItemVersionMismatch => Err(StatusCode::PRECONDITION_FAILED),
+            Error::InvalidItemStatusTransition => Err(StatusCode::CONFLICT),
+            Error::Forbidden => Err(StatusCode::FORBIDDEN),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+pub async fn transfer_items(
+    Path(table_number): Path<u32>,
+    Json(req): Json<JsonItemTransferRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .item_usecase()
+        .move_items(
+            &actor,
+            table_number,
+            req.to_table_number,
+            req.item_ids,
+            Local::now().naive_local(),
+        )
+        .await;
+    match result {
+        Ok(moved_items) => Ok(Json(JsonItemTransferResponse { moved_items })),
+        Err(e) => Err(transfer_error_status(e)),
     }
+}
+
+pub async fn merge_tables(
+    Path(table_number): Path<u32>,
+    Json(req): Json<JsonTableMergeRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
     let result = modules
         .item_usecase()
-        .add_item(table_number, maybe_item.unwrap())
+        .merge_tables(
+            &actor,
+            table_number,
+            req.into_table_number,
+            Local::now().naive_local(),
+        )
         .await;
     match result {
-        Ok(id) => Ok((
-            StatusCode::CREATED,
-            Json(JsonItemAddlingResponse { item_id: id }),
-        )),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Ok(moved_items) => Ok(Json(JsonItemTransferResponse { moved_items })),
+        Err(e) => Err(transfer_error_status(e)),
+    }
+}
+
+fn transfer_error_status(e: Error) -> StatusCode {
+    match e {
+        Error::InvalidItemTransfer => StatusCode::BAD_REQUEST,
+        Error::TableNotFoundError | Error::UnknownTable | Error::ItemNotFoundError => {
+            StatusCode::NOT_FOUND
+        }
+        Error::TableNotSeated | Error::TableNotAvailable => StatusCode::CONFLICT,
+        Error::Forbidden => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
 
 pub async fn delete_item(
     Path(params): Path<HashMap<String, u32>>,
+    IfMatch(expected_version): IfMatch,
     Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
 ) -> impl IntoResponse {
     let result = modules
         .item_usecase()
         .delete_item(
+            &actor,
             *params.get("table_number").unwrap(),
             *params.get("item_id").unwrap(),
+            expected_version,
+            Local::now().naive_local(),
         )
         .await;
     match result {
         Ok(_) => Ok(StatusCode::NO_CONTENT),
-        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        Err(e) => match e {
+            Error::ItemNotFoundError => Err(StatusCode::NOT_FOUND),
+            Error::ItemVersionMismatch => Err(StatusCode::PRECONDITION_FAILED),
+            Error::Forbidden => Err(StatusCode::FORBIDDEN),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
     }
 }
 
@@ -108,26 +370,54 @@ pub struct JsonItemAddlingResponse {
     pub item_id: u64,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonItemStatusUpdateRequest {
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonItemTransferRequest {
+    pub to_table_number: u32,
+    pub item_ids: Option<Vec<u32>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonTableMergeRequest {
+    pub into_table_number: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonItemTransferResponse {
+    pub moved_items: u64,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct JsonItemResponse {
     pub id: u32,
     pub name: String,
     pub quantity: u32,
+    pub status: String,
     pub remaining_cooking_minutes: i64,
+    pub overdue_minutes: i64,
     pub cooking_started_at: String,
+    pub version: u32,
 }
 
 impl JsonItemResponse {
-    fn new(item: &Item) -> JsonItemResponse {
+    pub(crate) fn new(item: &Item) -> JsonItemResponse {
+        let now = Local::now().naive_local();
         JsonItemResponse {
             id: item.id.unwrap(),
             name: item.name.clone(),
             quantity: item.quantity,
-            remaining_cooking_minutes: item
-                .clone()
-                .time_to_finish(Local::now().naive_local())
-                .num_minutes(),
-            cooking_started_at: item.start_cooking_at.to_string(),
+            status: item.status.to_string(),
+            remaining_cooking_minutes: item.clone().time_to_finish(now).num_minutes(),
+            overdue_minutes: item.overdue(now).num_minutes(),
+            cooking_started_at: item
+                .actual_start_cooking_at
+                .unwrap_or(item.start_cooking_at)
+                .to_string(),
+            version: item.version,
         }
     }
 }
@@ -135,21 +425,55 @@ impl JsonItemResponse {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::config::Features;
+    use crate::controllers::auth::test::login;
+    use crate::controllers::table::JsonTableStatusUpdateRequest;
+    use crate::extractors::IDEMPOTENCY_KEY_HEADER;
     use crate::modules::ModulesImpl;
     use crate::routes;
+    use axum::http::header::{
+        AUTHORIZATION, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+    };
     use axum_test_helper::TestClient;
+    use chrono::Utc;
     use pretty_assertions::assert_eq;
+    use serial_test::serial;
 
     async fn test_client() -> TestClient {
-        let test_app = routes::router(ModulesImpl::test().await).await;
-        TestClient::new(test_app)
+        let test_app =
+            routes::router(ModulesImpl::test().await.unwrap(), &Features::default()).await;
+        let client = TestClient::new(test_app);
+        let token = login(&client, "waiter").await;
+        seat_table(&client, &token, 1).await;
+        client
+    }
+
+    // walks the table round to seated whatever state a previous test left it
+    // in, so only the last step has to succeed
+    async fn seat_table(client: &TestClient, token: &str, table_number: u32) {
+        let mut statuses = Vec::new();
+        for status in ["needs_cleaning", "free", "seated"] {
+            let update_status = client
+                .put(&format!("/tables/{}/status", table_number))
+                .header(AUTHORIZATION, token)
+                .json(&JsonTableStatusUpdateRequest {
+                    status: status.to_string(),
+                })
+                .send()
+                .await;
+            statuses.push(update_status.status());
+        }
+        assert_eq!(statuses.last(), Some(&StatusCode::OK));
     }
 
     #[tokio::test]
+    #[serial]
     async fn test_validation_error() {
         let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
         let empty_name = client
             .post("/tables/1/item")
+            .header(AUTHORIZATION, &waiter)
             .json(&JsonItemAddingRequest {
                 name: "".to_string(),
                 quantity: 1,
@@ -158,6 +482,7 @@ mod test {
             .await;
         let empty_quantity = client
             .post("/tables/1/item")
+            .header(AUTHORIZATION, &waiter)
             .json(&JsonItemAddingRequest {
                 name: "a".to_string(),
                 quantity: 0,
@@ -169,10 +494,38 @@ mod test {
     }
 
     #[tokio::test]
+    #[serial]
+    async fn test_post_item_to_unseated_table() {
+        let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
+        client
+            .put("/tables/2/status")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonTableStatusUpdateRequest {
+                status: "needs_cleaning".to_string(),
+            })
+            .send()
+            .await;
+        let post_item = client
+            .post("/tables/2/item")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemAddingRequest {
+                name: "Sushi".to_string(),
+                quantity: 1,
+            })
+            .send()
+            .await;
+        assert_eq!(post_item.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    #[serial]
     async fn test_post_item() {
         let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
         let post_item = client
             .post("/tables/1/item")
+            .header(AUTHORIZATION, &waiter)
             .json(&JsonItemAddingRequest {
                 name: "Sushi".to_string(),
                 quantity: 10,
@@ -183,10 +536,64 @@ mod test {
     }
 
     #[tokio::test]
+    #[serial]
+    async fn test_post_item_with_idempotency_key() {
+        let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
+        let key = format!("test-{}", Local::now().timestamp_nanos());
+        let order = JsonItemAddingRequest {
+            name: "Gyoza".to_string(),
+            quantity: 2,
+        };
+
+        let first = client
+            .post("/tables/1/item")
+            .header(AUTHORIZATION, &waiter)
+            .header(IDEMPOTENCY_KEY_HEADER, &key)
+            .json(&order)
+            .send()
+            .await;
+        assert_eq!(first.status(), StatusCode::CREATED);
+        let first_json: JsonItemAddlingResponse =
+            serde_json::from_str(&first.text().await).unwrap();
+
+        let retry = client
+            .post("/tables/1/item")
+            .header(AUTHORIZATION, &waiter)
+            .header(IDEMPOTENCY_KEY_HEADER, &key)
+            .json(&order)
+            .send()
+            .await;
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(
+            retry.headers().get(IDEMPOTENT_REPLAYED_HEADER).unwrap(),
+            "true"
+        );
+        let retry_json: JsonItemAddlingResponse =
+            serde_json::from_str(&retry.text().await).unwrap();
+        assert_eq!(retry_json.item_id, first_json.item_id);
+
+        let different_order = client
+            .post("/tables/1/item")
+            .header(AUTHORIZATION, &waiter)
+            .header(IDEMPOTENCY_KEY_HEADER, &key)
+            .json(&JsonItemAddingRequest {
+                name: "Gyoza".to_string(),
+                quantity: 3,
+            })
+            .send()
+            .await;
+        assert_eq!(different_order.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    #[serial]
     async fn test_query_items() {
         let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
         let post_response = client
             .post("/tables/1/item")
+            .header(AUTHORIZATION, &waiter)
             .json(&JsonItemAddingRequest {
                 name: "Sushi".to_string(),
                 quantity: 10,
@@ -199,6 +606,7 @@ mod test {
 
         let get_item = client
             .get(&format!("/tables/1/items/{}", response_json.item_id))
+            .header(AUTHORIZATION, &waiter)
             .send()
             .await;
         assert_eq!(get_item.status(), StatusCode::OK);
@@ -208,7 +616,11 @@ mod test {
         assert_eq!(response_json.name, "Sushi");
         assert_eq!(response_json.quantity, 10);
 
-        let get_items = client.get("/tables/1/items").send().await;
+        let get_items = client
+            .get("/tables/1/items")
+            .header(AUTHORIZATION, &waiter)
+            .send()
+            .await;
         assert_eq!(get_items.status(), StatusCode::OK);
 
         let response_text = get_items.text().await;
@@ -217,6 +629,285 @@ mod test {
     }
 
     #[tokio::test]
+    #[serial]
+    async fn test_if_match_rejects_stale_changes() {
+        let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
+        let post_response = client
+            .post("/tables/1/item")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemAddingRequest {
+                name: "Udon".to_string(),
+                quantity: 1,
+            })
+            .send()
+            .await;
+        let response_text = post_response.text().await;
+        let response_json: JsonItemAddlingResponse = serde_json::from_str(&response_text).unwrap();
+        let item_endpoint = format!("/tables/1/items/{}", response_json.item_id);
+
+        let get_item = client
+            .get(&item_endpoint)
+            .header(AUTHORIZATION, &waiter)
+            .send()
+            .await;
+        let etag = get_item.headers().get(ETAG).unwrap().clone();
+        assert_eq!(etag, "\"1\"");
+
+        let start_cooking = client
+            .put(&format!("{}/status", item_endpoint))
+            .header(AUTHORIZATION, &waiter)
+            .header(IF_MATCH, etag.clone())
+            .json(&JsonItemStatusUpdateRequest {
+                status: "cooking".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(start_cooking.status(), StatusCode::OK);
+        assert_eq!(start_cooking.headers().get(ETAG).unwrap(), "\"2\"");
+
+        // the cook still holds the first version
+        let cook = login(&client, "cook").await;
+        let stale_ready = client
+            .put(&format!("{}/status", item_endpoint))
+            .header(AUTHORIZATION, &cook)
+            .header(IF_MATCH, etag)
+            .json(&JsonItemStatusUpdateRequest {
+                status: "ready".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(stale_ready.status(), StatusCode::PRECONDITION_FAILED);
+
+        let ready = client
+            .put(&format!("{}/status", item_endpoint))
+            .header(AUTHORIZATION, &cook)
+            .header(IF_MATCH, "\"2\"")
+            .json(&JsonItemStatusUpdateRequest {
+                status: "ready".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(ready.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_conditional_query_items() {
+        let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
+        // no other test touches table 4, so nothing else changes its items
+        seat_table(&client, &waiter, 4).await;
+        client
+            .post("/tables/4/item")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemAddingRequest {
+                name: "Edamame".to_string(),
+                quantity: 1,
+            })
+            .send()
+            .await;
+        let get_items = client
+            .get("/tables/4/items")
+            .header(AUTHORIZATION, &waiter)
+            .send()
+            .await;
+        assert_eq!(get_items.status(), StatusCode::OK);
+        let etag = get_items.headers().get(ETAG).unwrap().clone();
+        let last_modified = get_items.headers().get(LAST_MODIFIED).unwrap().clone();
+        // the item was just added, and the date is sent in UTC
+        let modified_at =
+            NaiveDateTime::parse_from_str(last_modified.to_str().unwrap(), HTTP_DATE_FORMAT)
+                .unwrap();
+        let age = Utc::now().naive_utc() - modified_at;
+        assert_eq!(age.num_seconds().abs() < 60, true);
+
+        let unchanged = client
+            .get("/tables/4/items")
+            .header(AUTHORIZATION, &waiter)
+            .header(IF_NONE_MATCH, etag.clone())
+            .send()
+            .await;
+        assert_eq!(unchanged.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(unchanged.headers().get(ETAG).unwrap(), &etag);
+        assert_eq!(
+            unchanged.headers().get(LAST_MODIFIED).unwrap(),
+            &last_modified
+        );
+
+        let not_modified_since = client
+            .get("/tables/4/items")
+            .header(AUTHORIZATION, &waiter)
+            .header(IF_MODIFIED_SINCE, last_modified.clone())
+            .send()
+            .await;
+        assert_eq!(not_modified_since.status(), StatusCode::NOT_MODIFIED);
+        let modified_since = client
+            .get("/tables/4/items")
+            .header(AUTHORIZATION, &waiter)
+            .header(IF_MODIFIED_SINCE, "Sat, 16 Apr 2022 05:20:00 GMT")
+            .send()
+            .await;
+        assert_eq!(modified_since.status(), StatusCode::OK);
+
+        client
+            .post("/tables/4/item")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemAddingRequest {
+                name: "Edamame".to_string(),
+                quantity: 1,
+            })
+            .send()
+            .await;
+        let changed = client
+            .get("/tables/4/items")
+            .header(AUTHORIZATION, &waiter)
+            .header(IF_NONE_MATCH, etag)
+            .send()
+            .await;
+        assert_eq!(changed.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_update_item_status() {
+        let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
+        let cook = login(&client, "cook").await;
+        let post_response = client
+            .post("/tables/1/item")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemAddingRequest {
+                name: "Ramen".to_string(),
+                quantity: 1,
+            })
+            .send()
+            .await;
+        let response_text = post_response.text().await;
+        let response_json: JsonItemAddlingResponse = serde_json::from_str(&response_text).unwrap();
+        let status_endpoint = format!("/tables/1/items/{}/status", response_json.item_id);
+
+        let start_cooking = client
+            .put(&status_endpoint)
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemStatusUpdateRequest {
+                status: "cooking".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(start_cooking.status(), StatusCode::OK);
+
+        let response_text = start_cooking.text().await;
+        let response_json: JsonItemResponse = serde_json::from_str(&response_text).unwrap();
+        assert_eq!(response_json.status, "cooking");
+        assert_eq!(response_json.overdue_minutes, 0);
+
+        let serve_before_ready = client
+            .put(&status_endpoint)
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemStatusUpdateRequest {
+                status: "served".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(serve_before_ready.status(), StatusCode::CONFLICT);
+
+        let unknown_status = client
+            .put(&status_endpoint)
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemStatusUpdateRequest {
+                status: "burnt".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(unknown_status.status(), StatusCode::BAD_REQUEST);
+
+        let ready_by_waiter = client
+            .put(&status_endpoint)
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemStatusUpdateRequest {
+                status: "ready".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(ready_by_waiter.status(), StatusCode::FORBIDDEN);
+
+        let ready_by_cook = client
+            .put(&status_endpoint)
+            .header(AUTHORIZATION, &cook)
+            .json(&JsonItemStatusUpdateRequest {
+                status: "ready".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(ready_by_cook.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_post_item_without_token() {
+        let client = test_client().await;
+        let post_item = client
+            .post("/tables/1/item")
+            .json(&JsonItemAddingRequest {
+                name: "Sushi".to_string(),
+                quantity: 1,
+            })
+            .send()
+            .await;
+        assert_eq!(post_item.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_transfer_items() {
+        let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
+        let post_response = client
+            .post("/tables/1/item")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemAddingRequest {
+                name: "Gyoza".to_string(),
+                quantity: 1,
+            })
+            .send()
+            .await;
+        let response_text = post_response.text().await;
+        let response_json: JsonItemAddlingResponse = serde_json::from_str(&response_text).unwrap();
+        let item_id = u32::try_from(response_json.item_id).unwrap();
+
+        seat_table(&client, &waiter, 3).await;
+        let transfer = client
+            .post("/tables/1/transfer")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonItemTransferRequest {
+                to_table_number: 3,
+                item_ids: Some(vec![item_id]),
+            })
+            .send()
+            .await;
+        assert_eq!(transfer.status(), StatusCode::OK);
+
+        let get_item = client
+            .get(&format!("/tables/3/items/{}", item_id))
+            .header(AUTHORIZATION, &waiter)
+            .send()
+            .await;
+        assert_eq!(get_item.status(), StatusCode::OK);
+
+        let merge = client
+            .post("/tables/3/merge")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonTableMergeRequest {
+                into_table_number: 3,
+            })
+            .send()
+            .await;
+        assert_eq!(merge.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    #[serial]
     async fn test_delete_item() {
         // TODO write test case if axum_test_helper::TestClient supports delete method
     }
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use api_core::domain::reservation::Reservation;
+use api_core::error::Error;
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::controllers::parse_datetime;
+use crate::extractors::CurrentActor;
+use crate::modules::{Modules, ModulesImpl};
+
+pub async fn query_reservation(
+    Path(params): Path<HashMap<String, u32>>,
+    Extension(modules): Extension<ModulesImpl>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .reservation_usecase()
+        .query_reservation(*params.get("reservation_id").unwrap())
+        .await;
+    match result {
+        Ok(reservation) => Ok(Json(JsonReservationResponse::new(&reservation))),
+        Err(e) => Err(error_status(e)),
+    }
+}
+
+pub async fn make_reservation(
+    Json(req): Json<JsonReservationRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    let reservation = req.into_reservation().ok_or(StatusCode::BAD_REQUEST)?;
+    let result = modules
+        .reservation_usecase()
+        .make_reservation(&actor, reservation)
+        .await;
+    match result {
+        Ok(id) => Ok((
+            StatusCode::CREATED,
+            Json(JsonReservationAddingResponse { reservation_id: id }),
+        )),
+        Err(e) => Err(error_status(e)),
+    }
+}
+
+pub async fn modify_reservation(
+    Path(params): Path<HashMap<String, u32>>,
+    Json(req): Json<JsonReservationRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    let changes = req.into_reservation().ok_or(StatusCode::BAD_REQUEST)?;
+    let result = modules
+        .reservation_usecase()
+        .modify_reservation(&actor, *params.get("reservation_id").unwrap(), changes)
+        .await;
+    match result {
+        Ok(reservation) => Ok(Json(JsonReservationResponse::new(&reservation))),
+        Err(e) => Err(error_status(e)),
+    }
+}
+
+pub async fn cancel_reservation(
+    Path(params): Path<HashMap<String, u32>>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .reservation_usecase()
+        .cancel_reservation(&actor, *params.get("reservation_id").unwrap())
+        .await;
+    match result {
+        Ok(reservation) => Ok(Json(JsonReservationResponse::new(&reservation))),
+        Err(e) => Err(error_status(e)),
+    }
+}
+
+pub async fn seat_reservation(
+    Path(params): Path<HashMap<String, u32>>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .reservation_usecase()
+        .seat_reservation(
+            &actor,
+            *params.get("reservation_id").unwrap(),
+            Local::now().naive_local(),
+        )
+        .await;
+    match result {
+        Ok(reservation) => Ok(Json(JsonReservationResponse::new(&reservation))),
+        Err(e) => Err(error_status(e)),
+    }
+}
+
+fn error_status(e: Error) -> StatusCode {
+    match e {
+        Error::ReservationNotFoundError => StatusCode::NOT_FOUND,
+        Error::TableNotFoundError | Error::TableCapacityExceeded => {
+            StatusCode::UNPROCESSABLE_ENTITY
+        }
+        Error::ReservationConflict
+        | Error::InvalidReservationStatusTransition
+        | Error::TableNotAvailable => StatusCode::CONFLICT,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonReservationRequest {
+    pub guest_name: String,
+    pub guest_phone: String,
+    pub party_size: u32,
+    pub table_number: u32,
+    pub starts_at: String,
+    pub ends_at: String,
+}
+
+impl JsonReservationRequest {
+    fn into_reservation(self) -> Option<Reservation> {
+        let starts_at = parse_datetime(&self.starts_at)?;
+        let ends_at = parse_datetime(&self.ends_at)?;
+        Reservation::new(
+            self.guest_name,
+            self.guest_phone,
+            self.party_size,
+            self.table_number,
+            starts_at,
+            ends_at,
+        )
+        .ok()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonReservationAddingResponse {
+    pub reservation_id: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonReservationResponse {
+    pub id: u32,
+    pub guest_name: String,
+    pub guest_phone: String,
+    pub party_size: u32,
+    pub table_number: u32,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub status: String,
+}
+
+impl JsonReservationResponse {
+    fn new(reservation: &Reservation) -> JsonReservationResponse {
+        JsonReservationResponse {
+            id: reservation.id.unwrap(),
+            guest_name: reservation.guest_name.clone(),
+            guest_phone: reservation.guest_phone.clone(),
+            party_size: reservation.party_size,
+            table_number: reservation.table_number,
+            starts_at: reservation.starts_at.to_string(),
+            ends_at: reservation.ends_at.to_string(),
+            status: reservation.status.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::Features;
+    use crate::controllers::auth::test::login;
+    use crate::modules::ModulesImpl;
+    use crate::routes;
+    use axum::http::header::AUTHORIZATION;
+    use axum_test_helper::TestClient;
+    use pretty_assertions::assert_eq;
+
+    async fn test_client() -> TestClient {
+        let test_app =
+            routes::router(ModulesImpl::test().await.unwrap(), &Features::default()).await;
+        TestClient::new(test_app)
+    }
+
+    #[tokio::test]
+    async fn test_validation_error() {
+        let client = test_client().await;
+        let token = login(&client, "waiter").await;
+        let invalid_slot = client
+            .post("/reservations")
+            .header(AUTHORIZATION, &token)
+            .json(&JsonReservationRequest {
+                guest_name: "Tanaka".to_string(),
+                guest_phone: "090-0000-0000".to_string(),
+                party_size: 2,
+                table_number: 1,
+                starts_at: "2030-01-01T20:00:00".to_string(),
+                ends_at: "2030-01-01T18:00:00".to_string(),
+            })
+            .send()
+            .await;
+        let invalid_datetime = client
+            .post("/reservations")
+            .header(AUTHORIZATION, &token)
+            .json(&JsonReservationRequest {
+                guest_name: "Tanaka".to_string(),
+                guest_phone: "090-0000-0000".to_string(),
+                party_size: 2,
+                table_number: 1,
+                starts_at: "tomorrow".to_string(),
+                ends_at: "2030-01-01T18:00:00".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(invalid_slot.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(invalid_datetime.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_query_unknown_reservation() {
+        let client = test_client().await;
+        let token = login(&client, "waiter").await;
+        let get_reservation = client
+            .get("/reservations/0")
+            .header(AUTHORIZATION, &token)
+            .send()
+            .await;
+        assert_eq!(get_reservation.status(), StatusCode::NOT_FOUND);
+    }
+}
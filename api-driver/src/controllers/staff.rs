@@ -0,0 +1,250 @@
+use api_core::domain::{
+    actor::Actor,
+    staff::{Role, Staff},
+};
+use api_core::error::Error;
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::extractors::CurrentStaff;
+use crate::modules::{Modules, ModulesImpl};
+
+pub async fn query_staff_members(
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .staff_usecase()
+        .query_staff_members(&Actor::Staff(staff))
+        .await;
+    match result {
+        Ok(members) => {
+            let staff_responses: Vec<JsonStaffResponse> =
+                members.iter().map(JsonStaffResponse::new).collect();
+            Ok(Json(staff_responses))
+        }
+        Err(e) => match e {
+            Error::Forbidden => Err(StatusCode::FORBIDDEN),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+pub async fn add_staff(
+    Json(req): Json<JsonStaffAddingRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let role = req
+        .role
+        .parse::<Role>()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let new_staff = Staff::new(req.name, role).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let result = modules
+        .staff_usecase()
+        .add_staff(&Actor::Staff(staff), new_staff, &req.password)
+        .await;
+    match result {
+        Ok(id) => Ok((
+            StatusCode::CREATED,
+            Json(JsonStaffAddingResponse { staff_id: id }),
+        )),
+        Err(e) => match e {
+            Error::PasswordTooShort => Err(StatusCode::BAD_REQUEST),
+            Error::Forbidden => Err(StatusCode::FORBIDDEN),
+            Error::StaffNameTaken => Err(StatusCode::CONFLICT),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+pub async fn deactivate_staff(
+    Path(staff_id): Path<u32>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .staff_usecase()
+        .deactivate_staff(&Actor::Staff(staff), staff_id)
+        .await;
+    match result {
+        Ok(staff) => Ok(Json(JsonStaffResponse::new(&staff))),
+        Err(e) => match e {
+            Error::StaffNotFoundError => Err(StatusCode::NOT_FOUND),
+            Error::Forbidden => Err(StatusCode::FORBIDDEN),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonStaffAddingRequest {
+    pub name: String,
+    pub role: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonStaffAddingResponse {
+    pub staff_id: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonStaffResponse {
+    pub id: u32,
+    pub name: String,
+    pub role: String,
+    pub active: bool,
+}
+
+impl JsonStaffResponse {
+    pub fn new(staff: &Staff) -> JsonStaffResponse {
+        JsonStaffResponse {
+            id: staff.id.unwrap(),
+            name: staff.name.clone(),
+            role: staff.role.to_string(),
+            active: staff.active,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::Features;
+    use crate::controllers::auth::test::{login, login_with};
+    use crate::controllers::item::JsonItemAddingRequest;
+    use crate::modules::ModulesImpl;
+    use crate::routes;
+    use axum::http::header::AUTHORIZATION;
+    use axum_test_helper::TestClient;
+    use chrono::Local;
+    use pretty_assertions::assert_eq;
+
+    const PASSWORD: &str = "a long enough password";
+
+    async fn test_client() -> TestClient {
+        let test_app =
+            routes::router(ModulesImpl::test().await.unwrap(), &Features::default()).await;
+        TestClient::new(test_app)
+    }
+
+    #[tokio::test]
+    async fn test_deactivated_staff_token_is_rejected() {
+        let client = test_client().await;
+        let admin = login(&client, "admin").await;
+        // staff names are unique, so every run signs up someone new
+        let name = format!("test-{}", Local::now().timestamp_nanos());
+        client
+            .post("/staff")
+            .header(AUTHORIZATION, &admin)
+            .json(&JsonStaffAddingRequest {
+                name: name.clone(),
+                role: "waiter".to_string(),
+                password: PASSWORD.to_string(),
+            })
+            .send()
+            .await;
+        let leaving = login_with(&client, &name, PASSWORD).await;
+
+        let members = client
+            .get("/staff")
+            .header(AUTHORIZATION, &admin)
+            .send()
+            .await;
+        let response_text = members.text().await;
+        let members: Vec<JsonStaffResponse> = serde_json::from_str(&response_text).unwrap();
+        let leaving_id = members.iter().find(|staff| staff.name == name).unwrap().id;
+        client
+            .post(&format!("/staff/{}/deactivate", leaving_id))
+            .header(AUTHORIZATION, &admin)
+            .send()
+            .await;
+
+        let after_deactivation = client
+            .post("/tables/1/item")
+            .header(AUTHORIZATION, &leaving)
+            .json(&JsonItemAddingRequest {
+                name: "Sushi".to_string(),
+                quantity: 1,
+            })
+            .send()
+            .await;
+        assert_eq!(after_deactivation.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_only_admin_adds_staff() {
+        let client = test_client().await;
+        let waiter = login(&client, "waiter").await;
+        let admin = login(&client, "admin").await;
+        let name = format!("test-{}", Local::now().timestamp_nanos());
+        let by_waiter = client
+            .post("/staff")
+            .header(AUTHORIZATION, &waiter)
+            .json(&JsonStaffAddingRequest {
+                name: name.clone(),
+                role: "cook".to_string(),
+                password: PASSWORD.to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(by_waiter.status(), StatusCode::FORBIDDEN);
+
+        let by_admin = client
+            .post("/staff")
+            .header(AUTHORIZATION, &admin)
+            .json(&JsonStaffAddingRequest {
+                name: name.clone(),
+                role: "cook".to_string(),
+                password: PASSWORD.to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(by_admin.status(), StatusCode::CREATED);
+
+        let same_name = client
+            .post("/staff")
+            .header(AUTHORIZATION, &admin)
+            .json(&JsonStaffAddingRequest {
+                name: name.clone(),
+                role: "waiter".to_string(),
+                password: PASSWORD.to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(same_name.status(), StatusCode::CONFLICT);
+
+        let response_text = by_admin.text().await;
+        let response_json: JsonStaffAddingResponse = serde_json::from_str(&response_text).unwrap();
+        let deactivate = client
+            .post(&format!("/staff/{}/deactivate", response_json.staff_id))
+            .header(AUTHORIZATION, &admin)
+            .send()
+            .await;
+        assert_eq!(deactivate.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_short_password_is_refused() {
+        let client = test_client().await;
+        let admin = login(&client, "admin").await;
+        let response = client
+            .post("/staff")
+            .header(AUTHORIZATION, &admin)
+            .json(&JsonStaffAddingRequest {
+                name: format!("test-{}", Local::now().timestamp_nanos()),
+                role: "waiter".to_string(),
+                password: "password".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
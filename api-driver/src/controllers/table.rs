@@ -0,0 +1,182 @@
+use api_core::domain::table::{Table, TableStatus};
+use api_core::error::Error;
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::extractors::CurrentActor;
+use crate::modules::{Modules, ModulesImpl};
+
+pub async fn query_tables(
+    Extension(modules): Extension<ModulesImpl>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules.table_usecase().query_tables().await;
+    match result {
+        Ok(tables) => {
+            let table_responses: Vec<JsonTableResponse> =
+                tables.iter().map(JsonTableResponse::new).collect();
+            Ok(Json(table_responses))
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub async fn seat_party(
+    Json(req): Json<JsonSeatingRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    if req.party_size < 1 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let result = modules
+        .table_usecase()
+        .seat_party(&actor, req.party_size, req.zone, Local::now().naive_local())
+        .await;
+    match result {
+        Ok(table) => Ok(Json(JsonTableResponse::new(&table))),
+        Err(e) => match e {
+            Error::TableNotAvailable => Err(StatusCode::CONFLICT),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+pub async fn update_table_status(
+    Path(table_number): Path<u32>,
+    Json(req): Json<JsonTableStatusUpdateRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    let status = req
+        .status
+        .parse::<TableStatus>()
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let result = modules
+        .table_usecase()
+        .update_table_status(&actor, table_number, status, Local::now().naive_local())
+        .await;
+    match result {
+        Ok(table) => Ok(Json(JsonTableResponse::new(&table))),
+        Err(e) => match e {
+            Error::TableNotFoundError => Err(StatusCode::NOT_FOUND),
+            Error::InvalidTableStatusTransition | Error::TableNotAvailable => {
+                Err(StatusCode::CONFLICT)
+            }
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonSeatingRequest {
+    pub party_size: u32,
+    pub zone: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonTableStatusUpdateRequest {
+    pub status: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonTableResponse {
+    pub number: u32,
+    pub capacity: u32,
+    pub zone: String,
+    pub status: String,
+}
+
+impl JsonTableResponse {
+    pub fn new(table: &Table) -> JsonTableResponse {
+        JsonTableResponse {
+            number: table.number,
+            capacity: table.capacity,
+            zone: table.zone.clone(),
+            status: table.status.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::Features;
+    use crate::controllers::auth::test::login;
+    use crate::modules::ModulesImpl;
+    use crate::routes;
+    use axum::http::header::AUTHORIZATION;
+    use axum_test_helper::TestClient;
+    use pretty_assertions::assert_eq;
+    use serial_test::serial;
+
+    async fn test_client() -> TestClient {
+        let test_app =
+            routes::router(ModulesImpl::test().await.unwrap(), &Features::default()).await;
+        TestClient::new(test_app)
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_seat_party() {
+        let client = test_client().await;
+        let token = login(&client, "waiter").await;
+        let seat_party = client
+            .post("/tables/seat")
+            .header(AUTHORIZATION, &token)
+            .json(&JsonSeatingRequest {
+                party_size: 2,
+                zone: None,
+            })
+            .send()
+            .await;
+        assert_eq!(seat_party.status(), StatusCode::OK);
+
+        let response_text = seat_party.text().await;
+        let table: JsonTableResponse = serde_json::from_str(&response_text).unwrap();
+        assert_eq!(table.status, "seated");
+        assert_eq!(table.capacity >= 2, true);
+
+        let clear_table = client
+            .put(&format!("/tables/{}/status", table.number))
+            .header(AUTHORIZATION, &token)
+            .json(&JsonTableStatusUpdateRequest {
+                status: "needs_cleaning".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(clear_table.status(), StatusCode::OK);
+
+        let clean_table = client
+            .put(&format!("/tables/{}/status", table.number))
+            .header(AUTHORIZATION, &token)
+            .json(&JsonTableStatusUpdateRequest {
+                status: "free".to_string(),
+            })
+            .send()
+            .await;
+        assert_eq!(clean_table.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_seat_too_large_party() {
+        let client = test_client().await;
+        let token = login(&client, "waiter").await;
+        let seat_party = client
+            .post("/tables/seat")
+            .header(AUTHORIZATION, &token)
+            .json(&JsonSeatingRequest {
+                party_size: 100,
+                zone: None,
+            })
+            .send()
+            .await;
+        assert_eq!(seat_party.status(), StatusCode::CONFLICT);
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use api_core::domain::waitlist::WaitlistEntry;
+use api_core::error::Error;
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+use crate::controllers::table::JsonTableResponse;
+use crate::extractors::CurrentActor;
+use crate::modules::{Modules, ModulesImpl};
+
+pub async fn query_waitlist(
+    Extension(modules): Extension<ModulesImpl>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules.waitlist_usecase().query_waitlist().await;
+    match result {
+        Ok(entries) => {
+            let entry_responses: Vec<JsonWaitlistEntryResponse> =
+                entries.iter().map(JsonWaitlistEntryResponse::new).collect();
+            Ok(Json(entry_responses))
+        }
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+pub async fn join_waitlist(
+    Json(req): Json<JsonWaitlistAddingRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    let entry = WaitlistEntry::new(
+        req.guest_name,
+        req.guest_phone,
+        req.party_size,
+        Local::now().naive_local(),
+    )
+    .map_err(|_| StatusCode::BAD_REQUEST)?;
+    let result = modules
+        .waitlist_usecase()
+        .join_waitlist(&actor, entry)
+        .await;
+    match result {
+        Ok(entry) => Ok((
+            StatusCode::CREATED,
+            Json(JsonWaitlistEntryResponse::new(&entry)),
+        )),
+        Err(e) => match e {
+            Error::TableCapacityExceeded => Err(StatusCode::UNPROCESSABLE_ENTITY),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+pub async fn leave_waitlist(
+    Path(params): Path<HashMap<String, u32>>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .waitlist_usecase()
+        .leave_waitlist(&actor, *params.get("entry_id").unwrap())
+        .await;
+    match result {
+        Ok(entry) => Ok(Json(JsonWaitlistEntryResponse::new(&entry))),
+        Err(e) => match e {
+            Error::WaitlistEntryNotFoundError => Err(StatusCode::NOT_FOUND),
+            Error::InvalidWaitlistStatusTransition => Err(StatusCode::CONFLICT),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+pub async fn seat_next(
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentActor(actor): CurrentActor,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .waitlist_usecase()
+        .seat_next(&actor, Local::now().naive_local())
+        .await;
+    match result {
+        Ok((entry, table)) => Ok(Json(JsonWaitlistSeatingResponse {
+            entry: JsonWaitlistEntryResponse::new(&entry),
+            table: JsonTableResponse::new(&table),
+        })),
+        Err(e) => match e {
+            Error::WaitlistEntryNotFoundError => Err(StatusCode::NOT_FOUND),
+            Error::TableNotAvailable => Err(StatusCode::CONFLICT),
+            _ => Err(StatusCode::INTERNAL_SERVER_ERROR),
+        },
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonWaitlistAddingRequest {
+    pub guest_name: String,
+    pub guest_phone: String,
+    pub party_size: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonWaitlistEntryResponse {
+    pub id: u32,
+    pub guest_name: String,
+    pub party_size: u32,
+    pub quoted_wait_minutes: i64,
+    pub status: String,
+    pub joined_at: String,
+}
+
+impl JsonWaitlistEntryResponse {
+    fn new(entry: &WaitlistEntry) -> JsonWaitlistEntryResponse {
+        JsonWaitlistEntryResponse {
+            id: entry.id.unwrap(),
+            guest_name: entry.guest_name.clone(),
+            party_size: entry.party_size,
+            quoted_wait_minutes: entry.quoted_wait_minutes,
+            status: entry.status.to_string(),
+            joined_at: entry.joined_at.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonWaitlistSeatingResponse {
+    pub entry: JsonWaitlistEntryResponse,
+    pub table: JsonTableResponse,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::Features;
+    use crate::controllers::auth::test::login;
+    use crate::modules::ModulesImpl;
+    use crate::routes;
+    use axum::http::header::AUTHORIZATION;
+    use axum_test_helper::TestClient;
+    use pretty_assertions::assert_eq;
+
+    async fn test_client() -> TestClient {
+        let test_app =
+            routes::router(ModulesImpl::test().await.unwrap(), &Features::default()).await;
+        TestClient::new(test_app)
+    }
+
+    #[tokio::test]
+    async fn test_join_and_leave_waitlist() {
+        let client = test_client().await;
+        let token = login(&client, "waiter").await;
+        let join = client
+            .post("/waitlist")
+            .header(AUTHORIZATION, &token)
+            .json(&JsonWaitlistAddingRequest {
+                guest_name: "Sato".to_string(),
+                guest_phone: "080-0000-0000".to_string(),
+                party_size: 2,
+            })
+            .send()
+            .await;
+        assert_eq!(join.status(), StatusCode::CREATED);
+
+        let response_text = join.text().await;
+        let entry: JsonWaitlistEntryResponse = serde_json::from_str(&response_text).unwrap();
+        assert_eq!(entry.status, "waiting");
+        assert_eq!(entry.quoted_wait_minutes >= 0, true);
+
+        let leave = client
+            .post(&format!("/waitlist/{}/leave", entry.id))
+            .header(AUTHORIZATION, &token)
+            .send()
+            .await;
+        assert_eq!(leave.status(), StatusCode::OK);
+
+        let leave_again = client
+            .post(&format!("/waitlist/{}/leave", entry.id))
+            .header(AUTHORIZATION, &token)
+            .send()
+            .await;
+        assert_eq!(leave_again.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_join_with_too_large_party() {
+        let client = test_client().await;
+        let token = login(&client, "waiter").await;
+        let join = client
+            .post("/waitlist")
+            .header(AUTHORIZATION, &token)
+            .json(&JsonWaitlistAddingRequest {
+                guest_name: "Sato".to_string(),
+                guest_phone: "080-0000-0000".to_string(),
+                party_size: 100,
+            })
+            .send()
+            .await;
+        assert_eq!(join.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
@@ -0,0 +1,299 @@
+use api_core::domain::{
+    actor::Actor,
+    staff::Staff,
+    webhook::{WebhookDelivery, WebhookSubscription},
+};
+use api_core::error::Error;
+use axum::{
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::extractors::CurrentStaff;
+use crate::modules::{Modules, ModulesImpl};
+
+pub async fn query_webhooks(
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .webhook_usecase()
+        .query_webhooks(&Actor::Staff(staff))
+        .await;
+    match result {
+        Ok(subscriptions) => {
+            let webhook_responses: Vec<JsonWebhookResponse> =
+                subscriptions.iter().map(JsonWebhookResponse::new).collect();
+            Ok(Json(webhook_responses))
+        }
+        Err(e) => Err(webhook_error_status(e)),
+    }
+}
+
+pub async fn create_webhook(
+    Json(req): Json<JsonWebhookRequest>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .webhook_usecase()
+        .create_webhook(&Actor::Staff(staff), req.url, req.event_types)
+        .await;
+    match result {
+        Ok((subscription, secret)) => Ok((
+            StatusCode::CREATED,
+            Json(JsonWebhookSecretResponse::new(&subscription, secret)),
+        )),
+        Err(e) => Err(webhook_error_status(e)),
+    }
+}
+
+pub async fn enable_webhook(
+    Path(webhook_id): Path<u32>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    set_webhook_active(modules, staff, webhook_id, true).await
+}
+
+pub async fn disable_webhook(
+    Path(webhook_id): Path<u32>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    set_webhook_active(modules, staff, webhook_id, false).await
+}
+
+async fn set_webhook_active(
+    modules: ModulesImpl,
+    staff: Staff,
+    webhook_id: u32,
+    active: bool,
+) -> Result<Json<JsonWebhookResponse>, StatusCode> {
+    let result = modules
+        .webhook_usecase()
+        .set_webhook_active(&Actor::Staff(staff), webhook_id, active)
+        .await;
+    match result {
+        Ok(subscription) => Ok(Json(JsonWebhookResponse::new(&subscription))),
+        Err(e) => Err(webhook_error_status(e)),
+    }
+}
+
+pub async fn delete_webhook(
+    Path(webhook_id): Path<u32>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .webhook_usecase()
+        .delete_webhook(&Actor::Staff(staff), webhook_id)
+        .await;
+    match result {
+        Ok(()) => Ok(StatusCode::NO_CONTENT),
+        Err(e) => Err(webhook_error_status(e)),
+    }
+}
+
+pub async fn query_deliveries(
+    Path(webhook_id): Path<u32>,
+    Extension(modules): Extension<ModulesImpl>,
+    CurrentStaff(staff): CurrentStaff,
+) -> Result<impl IntoResponse, StatusCode> {
+    let result = modules
+        .webhook_usecase()
+        .query_deliveries(&Actor::Staff(staff), webhook_id)
+        .await;
+    match result {
+        Ok(deliveries) => {
+            let delivery_responses: Vec<JsonWebhookDeliveryResponse> = deliveries
+                .iter()
+                .map(JsonWebhookDeliveryResponse::new)
+                .collect();
+            Ok(Json(delivery_responses))
+        }
+        Err(e) => Err(webhook_error_status(e)),
+    }
+}
+
+fn webhook_error_status(e: Error) -> StatusCode {
+    match e {
+        Error::Forbidden => StatusCode::FORBIDDEN,
+        Error::WebhookNotFoundError => StatusCode::NOT_FOUND,
+        Error::InvalidWebhookSubscription => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonWebhookRequest {
+    pub url: String,
+    /// Event names to deliver; all events when left out.
+    #[serde(default)]
+    pub event_types: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonWebhookResponse {
+    pub id: u32,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub active: bool,
+    pub consecutive_failures: u32,
+}
+
+impl JsonWebhookResponse {
+    pub fn new(subscription: &WebhookSubscription) -> JsonWebhookResponse {
+        JsonWebhookResponse {
+            id: subscription.id.unwrap(),
+            url: subscription.url.clone(),
+            event_types: subscription.event_types.clone(),
+            active: subscription.active,
+            consecutive_failures: subscription.consecutive_failures,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonWebhookSecretResponse {
+    pub webhook: JsonWebhookResponse,
+    pub secret: String,
+}
+
+impl JsonWebhookSecretResponse {
+    fn new(subscription: &WebhookSubscription, secret: String) -> JsonWebhookSecretResponse {
+        JsonWebhookSecretResponse {
+            webhook: JsonWebhookResponse::new(subscription),
+            secret,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct JsonWebhookDeliveryResponse {
+    pub id: u64,
+    pub event_type: String,
+    pub status: String,
+    pub attempts: u32,
+    pub next_attempt_at: String,
+    pub response_status: Option<u16>,
+    pub last_error: Option<String>,
+    pub delivered_at: Option<String>,
+}
+
+impl JsonWebhookDeliveryResponse {
+    fn new(delivery: &WebhookDelivery) -> JsonWebhookDeliveryResponse {
+        JsonWebhookDeliveryResponse {
+            id: delivery.id.unwrap(),
+            event_type: delivery.event_type.clone(),
+            status: delivery.status.to_string(),
+            attempts: delivery.attempts,
+            next_attempt_at: delivery.next_attempt_at.to_string(),
+            response_status: delivery.response_status,
+            last_error: delivery.last_error.clone(),
+            delivered_at: delivery
+                .delivered_at
+                .map(|delivered_at| delivered_at.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::Features;
+    use crate::controllers::auth::test::login;
+    use crate::modules::ModulesImpl;
+    use crate::routes;
+    use axum::http::header::AUTHORIZATION;
+    use axum_test_helper::TestClient;
+    use pretty_assertions::assert_eq;
+
+    async fn test_client() -> TestClient {
+        let test_app =
+            routes::router(ModulesImpl::test().await.unwrap(), &Features::default()).await;
+        TestClient::new(test_app)
+    }
+
+    fn request(event_types: Vec<&str>) -> JsonWebhookRequest {
+        JsonWebhookRequest {
+            // nothing listens on the discard port
+            url: "http://127.0.0.1:9/test-hooks".to_string(),
+            event_types: event_types.into_iter().map(String::from).collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_admin_manages_webhooks() {
+        let client = test_client().await;
+        let manager = login(&client, "manager").await;
+        let create = client
+            .post("/webhooks")
+            .header(AUTHORIZATION, &manager)
+            .json(&request(vec!["table_closed"]))
+            .send()
+            .await;
+        assert_eq!(create.status(), StatusCode::FORBIDDEN);
+
+        let list = client
+            .get("/webhooks")
+            .header(AUTHORIZATION, &manager)
+            .send()
+            .await;
+        assert_eq!(list.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_is_created_logged_and_disabled() {
+        let client = test_client().await;
+        let admin = login(&client, "admin").await;
+
+        let unknown_event = client
+            .post("/webhooks")
+            .header(AUTHORIZATION, &admin)
+            .json(&request(vec!["table_opened"]))
+            .send()
+            .await;
+        assert_eq!(unknown_event.status(), StatusCode::BAD_REQUEST);
+
+        let create = client
+            .post("/webhooks")
+            .header(AUTHORIZATION, &admin)
+            .json(&request(vec!["table_closed", "item_served"]))
+            .send()
+            .await;
+        assert_eq!(create.status(), StatusCode::CREATED);
+        let response_text = create.text().await;
+        let created: JsonWebhookSecretResponse = serde_json::from_str(&response_text).unwrap();
+        assert_eq!(created.secret.len(), 64);
+        assert_eq!(created.webhook.active, true);
+
+        let deliveries = client
+            .get(&format!("/webhooks/{}/deliveries", created.webhook.id))
+            .header(AUTHORIZATION, &admin)
+            .send()
+            .await;
+        assert_eq!(deliveries.status(), StatusCode::OK);
+
+        // disabled, the test subscription receives nothing from other tests
+        let disable = client
+            .post(&format!("/webhooks/{}/disable", created.webhook.id))
+            .header(AUTHORIZATION, &admin)
+            .send()
+            .await;
+        let response_text = disable.text().await;
+        let disabled: JsonWebhookResponse = serde_json::from_str(&response_text).unwrap();
+        assert_eq!(disabled.active, false);
+
+        let missing = client
+            .get("/webhooks/0/deliveries")
+            .header(AUTHORIZATION, &admin)
+            .send()
+            .await;
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    }
+}
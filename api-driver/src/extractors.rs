@@ -0,0 +1,248 @@
+use api_core::domain::{actor::Actor, device::Device, staff::Staff};
+use axum::{
+    async_trait,
+    extract::{Extension, FromRequest, RequestParts},
+    http::{
+        header::{IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH},
+        StatusCode,
+    },
+};
+use chrono::NaiveDateTime;
+
+use crate::auth::Claims;
+use crate::modules::{Modules, ModulesImpl};
+
+/// The staff member the request's bearer token was issued to. Staff that
+/// have been deactivated since the token was issued are rejected.
+pub struct CurrentStaff(pub Staff);
+
+#[async_trait]
+impl<B> FromRequest<B> for CurrentStaff
+where
+    B: Send,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let staff_id = req
+            .extensions()
+            .and_then(|extensions| extensions.get::<Claims>())
+            .map(|claims| claims.sub)
+            .ok_or(StatusCode::UNAUTHORIZED)?;
+        let Extension(modules) = Extension::<ModulesImpl>::from_request(req)
+            .await
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        match modules.staff_usecase().query_staff(staff_id).await {
+            Ok(staff) if staff.active => Ok(CurrentStaff(staff)),
+            _ => Err(StatusCode::UNAUTHORIZED),
+        }
+    }
+}
+
+/// Whoever is behind the request: a device using its API key or a staff
+/// member with a bearer token.
+pub struct CurrentActor(pub Actor);
+
+#[async_trait]
+impl<B> FromRequest<B> for CurrentActor
+where
+    B: Send,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let device = req
+            .extensions()
+            .and_then(|extensions| extensions.get::<Device>())
+            .cloned();
+        match device {
+            Some(device) => Ok(CurrentActor(Actor::Device(device))),
+            None => {
+                let CurrentStaff(staff) = CurrentStaff::from_request(req).await?;
+                Ok(CurrentActor(Actor::Staff(staff)))
+            }
+        }
+    }
+}
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// The optional `Idempotency-Key` a client sends to make retrying a POST safe.
+pub struct IdempotencyKey(pub Option<String>);
+
+#[async_trait]
+impl<B> FromRequest<B> for IdempotencyKey
+where
+    B: Send,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let value = req
+            .headers()
+            .and_then(|headers| headers.get(IDEMPOTENCY_KEY_HEADER));
+        match value {
+            Some(value) => value
+                .to_str()
+                .map(|key| IdempotencyKey(Some(key.to_string())))
+                .map_err(|_| StatusCode::BAD_REQUEST),
+            None => Ok(IdempotencyKey(None)),
+        }
+    }
+}
+
+/// The item version a client expects to change, taken from the strong ETag in
+/// its `If-Match` header. `*` and a missing header expect nothing; anything
+/// else cannot match a current ETag and fails with 412.
+pub struct IfMatch(pub Option<u32>);
+
+#[async_trait]
+impl<B> FromRequest<B> for IfMatch
+where
+    B: Send,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let value = req
+            .headers()
+            .and_then(|headers| headers.get(IF_MATCH))
+            .map(|value| value.to_str().map(str::trim));
+        match value {
+            None => Ok(IfMatch(None)),
+            Some(Ok("*")) => Ok(IfMatch(None)),
+            Some(Ok(etag)) => etag
+                .strip_prefix('"')
+                .and_then(|etag| etag.strip_suffix('"'))
+                .and_then(|version| version.parse::<u32>().ok())
+                .map(|version| IfMatch(Some(version)))
+                .ok_or(StatusCode::PRECONDITION_FAILED),
+            Some(Err(_)) => Err(StatusCode::PRECONDITION_FAILED),
+        }
+    }
+}
+
+/// The ETags a client already holds, from its `If-None-Match` header.
+pub struct IfNoneMatch(pub Option<String>);
+
+impl IfNoneMatch {
+    /// Weak comparison as used for conditional GETs: `W/` prefixes are
+    /// ignored and `*` matches anything.
+    pub fn matches(&self, etag: &str) -> bool {
+        let etag = etag.trim_start_matches("W/");
+        match &self.0 {
+            Some(tags) => tags
+                .split(',')
+                .map(|tag| tag.trim())
+                .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag),
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+impl<B> FromRequest<B> for IfNoneMatch
+where
+    B: Send,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let tags = req
+            .headers()
+            .and_then(|headers| headers.get(IF_NONE_MATCH))
+            .and_then(|value| value.to_str().ok())
+            .map(|tags| tags.to_string());
+        Ok(IfNoneMatch(tags))
+    }
+}
+
+/// `Last-Modified` and `If-Modified-Since` dates, the IMF-fixdate of RFC 7231.
+pub const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// The `Last-Modified` date a client already holds, from its
+/// `If-Modified-Since` header. Dates in any other format are ignored.
+pub struct IfModifiedSince(pub Option<NaiveDateTime>);
+
+impl IfModifiedSince {
+    /// Whether something last modified at `last_modified` (UTC) is still the
+    /// same. HTTP dates have whole seconds, so the fraction is dropped.
+    pub fn unchanged(&self, last_modified: Option<NaiveDateTime>) -> bool {
+        match (self.0, last_modified) {
+            (Some(since), Some(last_modified)) => last_modified.timestamp() <= since.timestamp(),
+            _ => false,
+        }
+    }
+}
+
+#[async_trait]
+impl<B> FromRequest<B> for IfModifiedSince
+where
+    B: Send,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let since = req
+            .headers()
+            .and_then(|headers| headers.get(IF_MODIFIED_SINCE))
+            .and_then(|value| value.to_str().ok())
+            .and_then(|date| NaiveDateTime::parse_from_str(date.trim(), HTTP_DATE_FORMAT).ok());
+        Ok(IfModifiedSince(since))
+    }
+}
+
+pub const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// The id of the last event a reconnecting `EventSource` received. Ids this
+/// API did not hand out are ignored.
+pub struct LastEventId(pub Option<u64>);
+
+#[async_trait]
+impl<B> FromRequest<B> for LastEventId
+where
+    B: Send,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let id = req
+            .headers()
+            .and_then(|headers| headers.get(LAST_EVENT_ID_HEADER))
+            .and_then(|value| value.to_str().ok())
+            .and_then(|id| id.trim().parse::<u64>().ok());
+        Ok(LastEventId(id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn if_none_match_uses_weak_comparison() {
+        let tags = IfNoneMatch(Some(String::from(r#""1-1-0", W/"2-3-1650000000""#)));
+        assert_eq!(tags.matches(r#"W/"2-3-1650000000""#), true);
+        assert_eq!(tags.matches(r#""1-1-0""#), true);
+        assert_eq!(tags.matches(r#"W/"2-4-1650000000""#), false);
+        assert_eq!(IfNoneMatch(Some(String::from("*"))).matches(r#""1""#), true);
+        assert_eq!(IfNoneMatch(None).matches(r#""1""#), false);
+    }
+
+    #[test]
+    fn if_modified_since_compares_whole_seconds() {
+        let since =
+            NaiveDateTime::parse_from_str("Sat, 16 Apr 2022 05:20:00 GMT", HTTP_DATE_FORMAT)
+                .unwrap();
+        let same_second = since + chrono::Duration::milliseconds(500);
+        let later = since + chrono::Duration::seconds(1);
+        assert_eq!(
+            IfModifiedSince(Some(since)).unchanged(Some(same_second)),
+            true
+        );
+        assert_eq!(IfModifiedSince(Some(since)).unchanged(Some(later)), false);
+        assert_eq!(IfModifiedSince(Some(since)).unchanged(None), false);
+        assert_eq!(IfModifiedSince(None).unchanged(Some(since)), false);
+    }
+}
@@ -1,3 +1,15 @@
+pub mod auth;
+pub mod bootstrap;
+pub mod config;
 pub mod controllers;
+pub mod extractors;
+pub mod migrate;
 pub mod modules;
+pub mod relay;
 pub mod routes;
+
+#[cfg(all(feature = "postgres", feature = "sqlite"))]
+compile_error!("the postgres and sqlite features each pick the database, enable only one of them");
+
+#[cfg(not(any(feature = "mysql", feature = "postgres", feature = "sqlite")))]
+compile_error!("enable one of the mysql, postgres or sqlite features to pick the database");
@@ -1,10 +1,122 @@
-use api_driver::modules::ModulesImpl;
+use std::{
+    env,
+    io::{self, BufRead},
+    path::PathBuf,
+    process,
+};
+
+use api_driver::{
+    bootstrap,
+    config::{Config, LogConfig},
+    migrate,
+    modules::ModulesImpl,
+    relay::{spawn_item_cache_report, spawn_outbox_relay, spawn_webhook_delivery},
+    routes,
+};
+use axum::middleware;
+use tracing::{info, level_filters::LevelFilter};
+use tracing_subscriber::{filter::Targets, layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
 async fn main() {
-    let app = api_driver::routes::router(ModulesImpl::new().await).await;
-    axum::Server::bind(&"0.0.0.0:3000".parse().unwrap())
-        .serve(app.into_make_service())
+    // the settings can also come from a `.env` file
+    dotenv::dotenv().ok();
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let config_file = match args.iter().position(|arg| arg == "--config") {
+        Some(i) if i + 1 < args.len() => {
+            let path = args.remove(i + 1);
+            args.remove(i);
+            Some(PathBuf::from(path))
+        }
+        Some(_) => usage(),
+        None => None,
+    };
+    let config = exit_on_error(
+        "config",
+        Config::load(config_file.as_deref()).map_err(|e| e.to_string()),
+    );
+    init_logging(&config.log);
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .as_slice()
+    {
+        [] => serve(config).await,
+        ["--print-config"] => print!("{}", config),
+        ["migrate"] => exit_on_error("migrate", migrate::migrate(&config, false).await),
+        ["migrate", "--check"] => exit_on_error("migrate", migrate::migrate(&config, true).await),
+        ["create-admin", name] => {
+            let password = exit_on_error("create-admin", read_password());
+            exit_on_error(
+                "create-admin",
+                bootstrap::create_admin(&config, name, &password).await,
+            )
+        }
+        _ => usage(),
+    }
+}
+
+async fn serve(config: Config) {
+    exit_on_error("migrate", migrate::prepare_database(&config).await);
+    let modules = exit_on_error(
+        "db",
+        ModulesImpl::new(&config).await.map_err(|e| e.to_string()),
+    );
+    if config.features.outbox_relay {
+        spawn_outbox_relay(modules.clone());
+        spawn_webhook_delivery(modules.clone());
+    }
+    spawn_item_cache_report(modules.clone());
+    let app = routes::router(modules, &config.features)
         .await
-        .unwrap();
+        .layer(middleware::from_fn(routes::log_requests));
+    let server = exit_on_error(
+        "server",
+        axum::Server::try_bind(&config.server.bind)
+            .map_err(|e| format!("could not listen on {}: {}", config.server.bind, e)),
+    );
+    info!("listening on {}", config.server.bind);
+    server.serve(app.into_make_service()).await.unwrap();
+}
+
+/// Logs to stderr what is at `config.level` or more severe. The libraries
+/// log no more than `info`, as hyper's debug output is a line per read and
+/// write, and sqlx only its warnings, e.g. about slow statements, as it logs
+/// every statement at `info`.
+fn init_logging(config: &LogConfig) {
+    let level = config.level.as_filter();
+    let filter = Targets::new()
+        .with_default(level.min(LevelFilter::INFO))
+        .with_target("api_driver", level)
+        .with_target("api_adapter", level)
+        .with_target("sqlx", level.min(LevelFilter::WARN));
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(io::stderr))
+        .with(filter)
+        .init();
+}
+
+/// The first line of stdin, so that the password stays out of the shell history.
+fn read_password() -> Result<String, String> {
+    let mut password = String::new();
+    io::stdin()
+        .lock()
+        .read_line(&mut password)
+        .map_err(|e| format!("could not read the password from stdin: {}", e))?;
+    Ok(password.trim_end_matches(&['\r', '\n'][..]).to_string())
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: api-driver [--config <file>] [--print-config | migrate [--check] | create-admin <name>]"
+    );
+    process::exit(2);
+}
+
+fn exit_on_error<T>(context: &str, result: Result<T, String>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("[{}] {}", context, e);
+        process::exit(1);
+    })
 }
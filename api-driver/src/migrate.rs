@@ -0,0 +1,66 @@
+#[cfg(not(any(feature = "postgres", feature = "sqlite")))]
+use api_adapter::persistence::mysql::Db;
+#[cfg(feature = "postgres")]
+use api_adapter::persistence::postgres::PgDb as Db;
+#[cfg(feature = "sqlite")]
+use api_adapter::persistence::sqlite::SqliteDb as Db;
+use api_adapter::persistence::{
+    migration::{MigrationStatus, Migrations},
+    Env,
+};
+
+use crate::config::Config;
+
+/// Brings the production database up to date before the server starts.
+///
+/// With `features.migrate_on_startup` off it is only checked instead, in which case
+/// pending migrations keep the server from starting just like a drifted history does.
+pub async fn prepare_database(config: &Config) -> Result<(), String> {
+    let db = Db::connect(&config.database)
+        .await
+        .map_err(|e| format!("production database: {}", e))?;
+    let result = if config.features.migrate_on_startup {
+        db.run_migrations().await.map_err(|e| e.to_string())
+    } else {
+        check(&db).await.map(|_| ())
+    };
+    db.pool.close().await;
+    result.map_err(|e| format!("production database: {}", e))
+}
+
+/// `api-driver migrate [--check]`: applies, or only checks, the migrations against both
+/// the production and the test database.
+pub async fn migrate(config: &Config, check_only: bool) -> Result<(), String> {
+    for (name, database) in [("production", Some(&config.database)), ("test", None)] {
+        let db = match database {
+            Some(database) => Db::connect(database).await,
+            None => Db::new(Env::Test).await,
+        }
+        .map_err(|e| format!("{} database: {}", name, e))?;
+        let result = if check_only {
+            check(&db).await
+        } else {
+            match db.run_migrations().await {
+                Ok(()) => db.check_migrations().await.map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            }
+        };
+        db.pool.close().await;
+        let status = result.map_err(|e| format!("{} database: {}", name, e))?;
+        println!(
+            "{} database: {} migration(s) applied, up to date",
+            name,
+            status.applied.len()
+        );
+    }
+    Ok(())
+}
+
+async fn check(db: &Db) -> Result<MigrationStatus, String> {
+    let status = db.check_migrations().await.map_err(|e| e.to_string())?;
+    if status.is_up_to_date() {
+        Ok(status)
+    } else {
+        Err(format!("pending migrations {:?}", status.pending))
+    }
+}
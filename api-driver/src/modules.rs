@@ -1,35 +1,179 @@
+use std::io;
+
+use api_adapter::notifier::{event_bus::ItemEventBus, logging::LoggingNotifier};
+use api_adapter::persistence::config::DbError;
+use api_adapter::repository::cached_item_repository::ItemCacheStats;
+#[cfg(feature = "postgres")]
+use api_adapter::repository_impl::postgres::PgRepositoriesImpl as RepositoriesImpl;
+#[cfg(feature = "sqlite")]
+use api_adapter::repository_impl::sqlite::SqliteRepositoriesImpl as RepositoriesImpl;
+#[cfg(not(any(feature = "postgres", feature = "sqlite")))]
 use api_adapter::repository_impl::RepositoriesImpl;
-use api_core::repository::Repositories;
-use api_usecase::usecase::item::ItemUseCase;
+use api_core::{
+    domain::{idempotency::IdempotencyPolicy, item::CookingPolicy, webhook::WebhookPolicy},
+    repository::Repositories,
+};
+use api_usecase::usecase::{
+    audit::AuditUseCase, device::DeviceUseCase, idempotency::IdempotencyUseCase, item::ItemUseCase,
+    outbox::OutboxUseCase, reservation::ReservationUseCase, staff::StaffUseCase,
+    table::TableUseCase, waitlist::WaitlistUseCase, webhook::WebhookUseCase,
+};
+use chrono::Duration;
+
+use crate::{auth::TokenKeys, config::Config};
+
 pub trait Modules {
     type Repositories: Repositories;
 
+    fn audit_usecase(&self) -> &AuditUseCase<Self::Repositories>;
+    fn device_usecase(&self) -> &DeviceUseCase<Self::Repositories>;
+    fn idempotency_usecase(&self) -> &IdempotencyUseCase<Self::Repositories>;
     fn item_usecase(&self) -> &ItemUseCase<Self::Repositories>;
+    fn outbox_usecase(&self) -> &OutboxUseCase<Self::Repositories>;
+    fn reservation_usecase(&self) -> &ReservationUseCase<Self::Repositories>;
+    fn staff_usecase(&self) -> &StaffUseCase<Self::Repositories>;
+    fn table_usecase(&self) -> &TableUseCase<Self::Repositories>;
+    fn waitlist_usecase(&self) -> &WaitlistUseCase<Self::Repositories>;
+    fn webhook_usecase(&self) -> &WebhookUseCase<Self::Repositories>;
 }
 
 #[derive(Clone)]
 pub struct ModulesImpl {
+    audit_usecase: AuditUseCase<RepositoriesImpl>,
+    device_usecase: DeviceUseCase<RepositoriesImpl>,
+    idempotency_usecase: IdempotencyUseCase<RepositoriesImpl>,
     item_usecase: ItemUseCase<RepositoriesImpl>,
+    outbox_usecase: OutboxUseCase<RepositoriesImpl>,
+    reservation_usecase: ReservationUseCase<RepositoriesImpl>,
+    staff_usecase: StaffUseCase<RepositoriesImpl>,
+    table_usecase: TableUseCase<RepositoriesImpl>,
+    waitlist_usecase: WaitlistUseCase<RepositoriesImpl>,
+    webhook_usecase: WebhookUseCase<RepositoriesImpl>,
+    item_events: ItemEventBus,
+    item_repository: <RepositoriesImpl as Repositories>::ItemRepo,
+    token_keys: TokenKeys,
 }
 
 impl Modules for ModulesImpl {
     type Repositories = RepositoriesImpl;
 
+    fn audit_usecase(&self) -> &AuditUseCase<Self::Repositories> {
+        &self.audit_usecase
+    }
+
+    fn device_usecase(&self) -> &DeviceUseCase<Self::Repositories> {
+        &self.device_usecase
+    }
+
+    fn idempotency_usecase(&self) -> &IdempotencyUseCase<Self::Repositories> {
+        &self.idempotency_usecase
+    }
+
     fn item_usecase(&self) -> &ItemUseCase<Self::Repositories> {
         &self.item_usecase
     }
+
+    fn outbox_usecase(&self) -> &OutboxUseCase<Self::Repositories> {
+        &self.outbox_usecase
+    }
+
+    fn reservation_usecase(&self) -> &ReservationUseCase<Self::Repositories> {
+        &self.reservation_usecase
+    }
+
+    fn staff_usecase(&self) -> &StaffUseCase<Self::Repositories> {
+        &self.staff_usecase
+    }
+
+    fn table_usecase(&self) -> &TableUseCase<Self::Repositories> {
+        &self.table_usecase
+    }
+
+    fn waitlist_usecase(&self) -> &WaitlistUseCase<Self::Repositories> {
+        &self.waitlist_usecase
+    }
+
+    fn webhook_usecase(&self) -> &WebhookUseCase<Self::Repositories> {
+        &self.webhook_usecase
+    }
 }
 
 impl ModulesImpl {
-    pub async fn new() -> ModulesImpl {
-        let repositories_module = RepositoriesImpl::new().await;
-        let item_usecase = ItemUseCase::new(repositories_module);
-        ModulesImpl { item_usecase }
+    /// The bus the item events among the published domain events end up on,
+    /// for push channels to subscribe to.
+    pub fn item_events(&self) -> &ItemEventBus {
+        &self.item_events
+    }
+
+    /// What the item listing cache did since the server started.
+    pub fn item_cache_stats(&self) -> ItemCacheStats {
+        self.item_repository.stats()
+    }
+
+    /// The keys the bearer tokens are signed and checked with.
+    pub fn token_keys(&self) -> &TokenKeys {
+        &self.token_keys
+    }
+
+    pub async fn new(config: &Config) -> Result<ModulesImpl, DbError> {
+        let repositories_module =
+            RepositoriesImpl::new(&config.database, config.item_cache, &config.outbox)
+                .await?
+                .with_waitlist_notifier(LoggingNotifier::new(io::stderr()));
+        Ok(ModulesImpl::with_repositories(
+            repositories_module,
+            config.cooking,
+            config.idempotency,
+            config.webhooks,
+            TokenKeys::from_config(&config.auth),
+        ))
+    }
+
+    pub async fn test() -> Result<ModulesImpl, DbError> {
+        let repositories_module = RepositoriesImpl::test().await?;
+        Ok(ModulesImpl::with_repositories(
+            repositories_module,
+            CookingPolicy::default(),
+            IdempotencyPolicy::default(),
+            WebhookPolicy::default(),
+            TokenKeys::new(b"test-secret", Duration::minutes(5)),
+        ))
     }
 
-    pub async fn test() -> ModulesImpl {
-        let repositories_module = RepositoriesImpl::test().await;
-        let item_usecase = ItemUseCase::new(repositories_module);
-        ModulesImpl { item_usecase }
+    fn with_repositories(
+        repositories_module: RepositoriesImpl,
+        cooking_policy: CookingPolicy,
+        idempotency_policy: IdempotencyPolicy,
+        webhook_policy: WebhookPolicy,
+        token_keys: TokenKeys,
+    ) -> ModulesImpl {
+        let audit_usecase = AuditUseCase::new(repositories_module.clone());
+        let device_usecase = DeviceUseCase::new(repositories_module.clone());
+        let idempotency_usecase =
+            IdempotencyUseCase::with_policy(repositories_module.clone(), idempotency_policy);
+        let item_usecase = ItemUseCase::with_policy(repositories_module.clone(), cooking_policy);
+        let outbox_usecase = OutboxUseCase::new(repositories_module.clone());
+        let reservation_usecase = ReservationUseCase::new(repositories_module.clone());
+        let staff_usecase = StaffUseCase::new(repositories_module.clone());
+        let table_usecase = TableUseCase::new(repositories_module.clone());
+        let waitlist_usecase = WaitlistUseCase::new(repositories_module.clone());
+        let item_events = repositories_module.item_event_bus().clone();
+        let item_repository = repositories_module.item_repository().clone();
+        let webhook_usecase = WebhookUseCase::with_policy(repositories_module, webhook_policy);
+        ModulesImpl {
+            audit_usecase,
+            device_usecase,
+            idempotency_usecase,
+            item_usecase,
+            outbox_usecase,
+            reservation_usecase,
+            staff_usecase,
+            table_usecase,
+            waitlist_usecase,
+            webhook_usecase,
+            item_events,
+            item_repository,
+            token_keys,
+        }
     }
 }
@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use api_adapter::repository::cached_item_repository::ItemCacheStats;
+use chrono::Local;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use tracing::{error, info};
+
+use crate::modules::{Modules, ModulesImpl};
+
+/// How often the outbox and the webhook deliveries are checked for work
+/// that is due.
+const RELAY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the item cache statistics are logged.
+const ITEM_CACHE_REPORT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Keeps relaying the outbox to the event subscribers in the background for
+/// as long as the server runs.
+pub fn spawn_outbox_relay(modules: ModulesImpl) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RELAY_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let now = Local::now().naive_local();
+            if let Err(e) = modules.outbox_usecase().relay_pending(now).await {
+                error!("outbox relay failed: {:?}", e);
+            }
+        }
+    })
+}
+
+/// Keeps making the webhook deliveries the outbox relay queues, in a task of
+/// its own so that slow receivers never hold up the outbox.
+pub fn spawn_webhook_delivery(modules: ModulesImpl) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RELAY_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let now = Local::now().naive_local();
+            if let Err(e) = modules.webhook_usecase().deliver_pending(now).await {
+                error!("webhook delivery failed: {:?}", e);
+            }
+        }
+    })
+}
+
+/// Keeps logging the hits, misses and evictions of the item listing cache at
+/// `info`, skipping the reports in which nothing changed.
+pub fn spawn_item_cache_report(modules: ModulesImpl) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ITEM_CACHE_REPORT_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut reported = ItemCacheStats::default();
+        loop {
+            interval.tick().await;
+            let stats = modules.item_cache_stats();
+            if stats != reported {
+                info!(
+                    "item cache: {} hits, {} misses, {} evictions",
+                    stats.hits, stats.misses, stats.evictions
+                );
+                reported = stats;
+            }
+        }
+    })
+}
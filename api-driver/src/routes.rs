@@ -1,17 +1,132 @@
-use crate::{controllers::item, modules::ModulesImpl};
+use std::time::Instant;
+
+use crate::{
+    auth,
+    config::Features,
+    controllers::{self, audit, device, event, item, reservation, staff, table, waitlist, webhook},
+    modules::ModulesImpl,
+};
 use axum::{
     extract::Extension,
-    routing::{delete, get, post},
+    http::Request,
+    middleware::{self, Next},
+    response::IntoResponse,
+    routing::{delete, get, post, put},
     Router,
 };
 
-pub async fn router(modules: ModulesImpl) -> Router {
-    let item_router = Router::new()
+pub async fn router(modules: ModulesImpl, features: &Features) -> Router {
+    let mut table_router = Router::new()
+        .route("/", get(table::query_tables))
+        .route("/seat", post(table::seat_party))
+        .route("/:table_number/status", put(table::update_table_status))
+        .route("/:table_number/transfer", post(item::transfer_items))
+        .route("/:table_number/merge", post(item::merge_tables))
         .route("/:table_number/item", post(item::add_item))
         .route("/:table_number/items", get(item::query_items))
         .route("/:table_number/items/:item_id", get(item::query_item))
         .route("/:table_number/items/:item_id", delete(item::delete_item))
-        .layer(Extension(modules));
+        .route(
+            "/:table_number/items/:item_id/status",
+            put(item::update_item_status),
+        );
+    if features.live_events {
+        table_router = table_router.route("/:table_number/events", get(event::stream_table_events));
+    }
+    let table_router = table_router.layer(Extension(modules.clone()));
+
+    let reservation_router = Router::new()
+        .route("/", post(reservation::make_reservation))
+        .route(
+            "/:reservation_id",
+            get(reservation::query_reservation).put(reservation::modify_reservation),
+        )
+        .route(
+            "/:reservation_id/cancel",
+            post(reservation::cancel_reservation),
+        )
+        .route("/:reservation_id/seat", post(reservation::seat_reservation))
+        .layer(Extension(modules.clone()));
+
+    let waitlist_router = Router::new()
+        .route(
+            "/",
+            get(waitlist::query_waitlist).post(waitlist::join_waitlist),
+        )
+        .route("/seat-next", post(waitlist::seat_next))
+        .route("/:entry_id/leave", post(waitlist::leave_waitlist))
+        .layer(Extension(modules.clone()));
+
+    let staff_router = Router::new()
+        .route("/", get(staff::query_staff_members).post(staff::add_staff))
+        .route("/:staff_id/deactivate", post(staff::deactivate_staff))
+        .layer(Extension(modules.clone()));
+
+    let device_router = Router::new()
+        .route(
+            "/",
+            get(device::query_devices).post(device::issue_device_key),
+        )
+        .route("/:device_id/rotate", post(device::rotate_device_key))
+        .route("/:device_id/revoke", post(device::revoke_device_key))
+        .layer(Extension(modules.clone()));
+
+    let webhook_router = Router::new()
+        .route(
+            "/",
+            get(webhook::query_webhooks).post(webhook::create_webhook),
+        )
+        .route("/:webhook_id", delete(webhook::delete_webhook))
+        .route("/:webhook_id/enable", post(webhook::enable_webhook))
+        .route("/:webhook_id/disable", post(webhook::disable_webhook))
+        .route("/:webhook_id/deliveries", get(webhook::query_deliveries))
+        .layer(Extension(modules.clone()));
+
+    let audit_router = Router::new()
+        .route("/", get(audit::query_audit))
+        .layer(Extension(modules.clone()));
+
+    let event_router = Router::new()
+        .route("/", get(event::subscribe_events))
+        .layer(Extension(modules.clone()));
+
+    let auth_router = Router::new()
+        .route("/login", post(controllers::auth::login))
+        .layer(Extension(modules.clone()));
+
+    let mut api_router = Router::new()
+        .nest("/tables", table_router)
+        .nest("/reservations", reservation_router)
+        .nest("/waitlist", waitlist_router)
+        .nest("/staff", staff_router)
+        .nest("/devices", device_router)
+        .nest("/webhooks", webhook_router)
+        .nest("/audit", audit_router);
+    if features.live_events {
+        api_router = api_router.nest("/events", event_router);
+    }
+
+    // everything except logging in needs a bearer token or a device API key
+    api_router
+        .route_layer(middleware::from_fn(auth::authenticate))
+        .nest("/auth", auth_router)
+        .layer(Extension(modules.token_keys().clone()))
+        .layer(Extension(modules))
+}
 
-    Router::new().nest("/tables", item_router)
+/// Logs every request at `debug` with the status it was answered with and how
+/// long that took.
+pub async fn log_requests<B>(req: Request<B>, next: Next<B>) -> impl IntoResponse {
+    let method = req.method().clone();
+    let uri = req.uri().clone();
+    let started = Instant::now();
+    let response = next.run(req).await;
+    tracing::debug!(
+        "{} {} {} {:?}",
+        method,
+        uri,
+        response.status().as_u16(),
+        started.elapsed()
+    );
+    response
 }
@@ -1 +1,51 @@
+pub mod audit;
+pub mod device;
+pub mod idempotency;
 pub mod item;
+pub mod outbox;
+pub mod reservation;
+pub mod staff;
+pub mod table;
+pub mod waitlist;
+pub mod webhook;
+
+use api_core::{
+    domain::{actor::Actor, audit::AuditRecord, staff::Permission},
+    error::Error,
+};
+use rand::RngCore;
+use serde::Serialize;
+
+pub(crate) fn ensure_allowed(actor: &Actor, permission: Permission) -> Result<(), Error> {
+    if actor.can(permission) {
+        Ok(())
+    } else {
+        Err(Error::Forbidden)
+    }
+}
+
+/// Table tablets only ever act on their own table.
+pub(crate) fn ensure_table_access(actor: &Actor, table_number: u32) -> Result<(), Error> {
+    if actor.is_active() && actor.can_access_table(table_number) {
+        Ok(())
+    } else {
+        Err(Error::Forbidden)
+    }
+}
+
+/// The audit record of a change about to be made, which the repository
+/// making it writes in the same transaction.
+pub(crate) fn audit(record: Result<AuditRecord, String>) -> Result<AuditRecord, Error> {
+    record.map_err(|_| Error::AuditInsertionError)
+}
+
+pub(crate) fn snapshot<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_string(value).ok()
+}
+
+/// 32 random bytes, hex encoded, for API keys and signing secrets.
+pub(crate) fn random_key() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
@@ -0,0 +1,33 @@
+use api_core::{
+    domain::{
+        actor::Actor,
+        audit::{AuditFilter, AuditRecord},
+        staff::Permission,
+    },
+    error::Error,
+    repository::{audit_repository::AuditRepository, Repositories},
+};
+
+use crate::usecase::ensure_allowed;
+
+#[derive(Clone)]
+pub struct AuditUseCase<R: Repositories> {
+    repositories: R,
+}
+
+impl<R: Repositories> AuditUseCase<R> {
+    pub fn new(repositories: R) -> Self {
+        Self { repositories }
+    }
+}
+
+impl<R: Repositories> AuditUseCase<R> {
+    pub async fn query_audit(
+        &self,
+        actor: &Actor,
+        filter: AuditFilter,
+    ) -> Result<Vec<AuditRecord>, Error> {
+        ensure_allowed(actor, Permission::ViewAuditLog)?;
+        self.repositories.audit_repository().find(filter).await
+    }
+}
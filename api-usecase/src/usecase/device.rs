@@ -0,0 +1,132 @@
+use api_core::{
+    domain::{
+        actor::Actor,
+        audit::{AuditAction, AuditRecord},
+        device::{Device, DeviceScope},
+        staff::Permission,
+    },
+    error::Error,
+    repository::{
+        device_repository::DeviceRepository, table_repository::TableRepository, Repositories,
+    },
+};
+use sha2::{Digest, Sha256};
+
+use crate::usecase::{audit, ensure_allowed, random_key, snapshot};
+
+#[derive(Clone)]
+pub struct DeviceUseCase<R: Repositories> {
+    repositories: R,
+}
+
+impl<R: Repositories> DeviceUseCase<R> {
+    pub fn new(repositories: R) -> Self {
+        Self { repositories }
+    }
+}
+
+impl<R: Repositories> DeviceUseCase<R> {
+    /// Finds the device an API key belongs to. Unknown and revoked keys are
+    /// reported the same way.
+    pub async fn authenticate(&self, key: &str) -> Result<Device, Error> {
+        let device = self
+            .repositories
+            .device_repository()
+            .find_by_key_hash(&hash_key(key))
+            .await
+            .map_err(|_| Error::InvalidCredentials)?;
+        if device.revoked {
+            return Err(Error::InvalidCredentials);
+        }
+        Ok(device)
+    }
+
+    pub async fn query_devices(&self, actor: &Actor) -> Result<Vec<Device>, Error> {
+        ensure_allowed(actor, Permission::ManageDevices)?;
+        self.repositories.device_repository().find_all().await
+    }
+
+    /// Registers a device and returns it along with its API key. The key is
+    /// not stored, so this is the only time it can be read.
+    pub async fn issue_key(
+        &self,
+        actor: &Actor,
+        name: String,
+        scope: DeviceScope,
+    ) -> Result<(Device, String), Error> {
+        ensure_allowed(actor, Permission::ManageDevices)?;
+        if let DeviceScope::Table(table_number) = scope {
+            self.repositories
+                .table_repository()
+                .find_one(table_number)
+                .await?;
+        }
+        let key = random_key();
+        let mut device =
+            Device::new(name, scope, hash_key(&key)).map_err(|_| Error::DeviceInsertionError)?;
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::DeviceKeyIssued,
+            device.scope.table_number(),
+            None,
+            None,
+            None,
+        ))?;
+        let id = self
+            .repositories
+            .device_repository()
+            .create(device.clone(), audit)
+            .await?;
+        device.id = u32::try_from(id).ok();
+        Ok((device, key))
+    }
+
+    /// Replaces a device's API key. The old key stops working straight away.
+    pub async fn rotate_key(
+        &self,
+        actor: &Actor,
+        device_id: u32,
+    ) -> Result<(Device, String), Error> {
+        ensure_allowed(actor, Permission::ManageDevices)?;
+        let repository = self.repositories.device_repository();
+        let mut device = repository.find_one(device_id).await?;
+        let key = random_key();
+        device
+            .rotate(hash_key(&key))
+            .map_err(|_| Error::InvalidDeviceKeyOperation)?;
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::DeviceKeyRotated,
+            device.scope.table_number(),
+            None,
+            None,
+            snapshot(&device),
+        ))?;
+        repository.update(device.clone(), audit).await?;
+        Ok((device, key))
+    }
+
+    pub async fn revoke_key(&self, actor: &Actor, device_id: u32) -> Result<Device, Error> {
+        ensure_allowed(actor, Permission::ManageDevices)?;
+        let repository = self.repositories.device_repository();
+        let mut device = repository.find_one(device_id).await?;
+        let before = snapshot(&device);
+        device.revoke();
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::DeviceKeyRevoked,
+            device.scope.table_number(),
+            None,
+            before,
+            snapshot(&device),
+        ))?;
+        repository.update(device.clone(), audit).await?;
+        Ok(device)
+    }
+}
+
+// API keys are long and random, so a fast hash is enough to keep them out of
+// the database and still look them up directly.
+fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
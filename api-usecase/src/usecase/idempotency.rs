@@ -0,0 +1,90 @@
+use api_core::{
+    domain::idempotency::{IdempotencyPolicy, IdempotencyRecord, StoredResponse},
+    error::Error,
+    repository::{idempotency_repository::IdempotencyRepository, Repositories},
+};
+use chrono::NaiveDateTime;
+use sha2::{Digest, Sha256};
+
+/// What to do with a request that carries an `Idempotency-Key`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum IdempotencyOutcome {
+    /// First time the key is seen: handle the request, then `complete` it.
+    Proceed,
+    /// The request was already handled: send the stored response again.
+    Replay(StoredResponse),
+}
+
+#[derive(Clone)]
+pub struct IdempotencyUseCase<R: Repositories> {
+    repositories: R,
+    policy: IdempotencyPolicy,
+}
+
+impl<R: Repositories> IdempotencyUseCase<R> {
+    pub fn new(repositories: R) -> Self {
+        Self::with_policy(repositories, IdempotencyPolicy::default())
+    }
+
+    pub fn with_policy(repositories: R, policy: IdempotencyPolicy) -> Self {
+        Self {
+            repositories,
+            policy,
+        }
+    }
+}
+
+impl<R: Repositories> IdempotencyUseCase<R> {
+    /// Claims `key` for `request`, or tells how an earlier request with the
+    /// same key ended.
+    pub async fn begin(
+        &self,
+        key: &str,
+        request: &str,
+        now: NaiveDateTime,
+    ) -> Result<IdempotencyOutcome, Error> {
+        let repository = self.repositories.idempotency_repository();
+        repository.delete_expired(now).await?;
+
+        let fingerprint = fingerprint(request);
+        if let Some(record) = repository.find_one(key).await? {
+            if !record.matches(&fingerprint) {
+                return Err(Error::IdempotencyKeyMismatch);
+            }
+            if let Some(response) = record.response {
+                return Ok(IdempotencyOutcome::Replay(response));
+            }
+            if record.is_locked(now) {
+                return Err(Error::IdempotentRequestInProgress);
+            }
+            // the request never answered, e.g. its server died: retry it
+            repository
+                .reclaim(key, now, now + self.policy.lease)
+                .await?;
+            return Ok(IdempotencyOutcome::Proceed);
+        }
+
+        let record = IdempotencyRecord::new(key.to_string(), fingerprint, &self.policy, now)
+            .map_err(|_| Error::InvalidIdempotencyKey)?;
+        repository.create(record).await?;
+        Ok(IdempotencyOutcome::Proceed)
+    }
+
+    /// Stores the response so that retries with `key` get it back.
+    pub async fn complete(&self, key: &str, status: u16, body: String) -> Result<(), Error> {
+        self.repositories
+            .idempotency_repository()
+            .complete(key, StoredResponse { status, body })
+            .await
+    }
+
+    /// Releases `key` after a failure that is worth retrying, which has to
+    /// have left nothing behind.
+    pub async fn abandon(&self, key: &str) -> Result<(), Error> {
+        self.repositories.idempotency_repository().delete(key).await
+    }
+}
+
+fn fingerprint(request: &str) -> String {
+    hex::encode(Sha256::digest(request.as_bytes()))
+}
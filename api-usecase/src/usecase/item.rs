@@ -1,17 +1,42 @@
 use api_core::{
-    domain::item::Item,
+    domain::{
+        actor::Actor,
+        audit::{AuditAction, AuditRecord},
+        item::{CookingPolicy, Item, ItemStatus, ItemsRevision},
+        item_transfer::{ItemTransfer, TransferKind},
+        staff::Permission,
+        table::{Table, TableStatus},
+    },
     error::Error,
-    repository::{item_repository::ItemRepository, Repositories},
+    repository::{
+        item_repository::ItemRepository, table_repository::TableRepository, Repositories,
+    },
 };
+use chrono::NaiveDateTime;
+
+use crate::usecase::{audit, ensure_allowed, ensure_table_access, snapshot};
 
 #[derive(Clone)]
 pub struct ItemUseCase<R: Repositories> {
     repositories: R,
+    cooking_policy: CookingPolicy,
 }
 
 impl<R: Repositories> ItemUseCase<R> {
     pub fn new(repositories: R) -> Self {
-        Self { repositories }
+        Self::with_policy(repositories, CookingPolicy::default())
+    }
+
+    pub fn with_policy(repositories: R, cooking_policy: CookingPolicy) -> Self {
+        Self {
+            repositories,
+            cooking_policy,
+        }
+    }
+
+    /// How long new orders are planned to take.
+    pub fn cooking_policy(&self) -> &CookingPolicy {
+        &self.cooking_policy
     }
 }
 
@@ -30,17 +55,201 @@ impl<R: Repositories> ItemUseCase<R> {
             .await
     }
 
-    pub async fn add_item(&self, table_number: u32, item: Item) -> Result<u64, Error> {
+    /// Tells whether a table's items changed without loading them.
+    pub async fn query_items_revision(&self, table_number: u32) -> Result<ItemsRevision, Error> {
         self.repositories
             .item_repository()
-            .create(table_number, item)
+            .find_revision(table_number)
             .await
     }
 
-    pub async fn delete_item(&self, table_number: u32, item_id: u32) -> Result<(), Error> {
+    pub async fn add_item(
+        &self,
+        actor: &Actor,
+        table_number: u32,
+        item: Item,
+    ) -> Result<u64, Error> {
+        ensure_table_access(actor, table_number)?;
+        let table = self
+            .repositories
+            .table_repository()
+            .find_one(table_number)
+            .await?;
+        if !table.is_seated() {
+            return Err(Error::TableNotSeated);
+        }
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::ItemAdded,
+            Some(table_number),
+            None,
+            None,
+            None,
+        ))?;
         self.repositories
             .item_repository()
-            .delete(table_number, item_id)
+            .create(table_number, item, audit)
+            .await
+    }
+
+    /// Records a step of the item's progress. Only cooks can mark an item
+    /// ready. With an `expected_version` the change is refused if the item
+    /// has been changed since the caller read it.
+    pub async fn update_item_status(
+        &self,
+        actor: &Actor,
+        table_number: u32,
+        item_id: u32,
+        status: ItemStatus,
+        expected_version: Option<u32>,
+        now: NaiveDateTime,
+    ) -> Result<Item, Error> {
+        ensure_table_access(actor, table_number)?;
+        if status == ItemStatus::Ready {
+            ensure_allowed(actor, Permission::MarkItemsReady)?;
+        }
+        let repository = self.repositories.item_repository();
+        let mut item = repository.find_one_for_write(table_number, item_id).await?;
+        if !item.matches_version(expected_version) {
+            return Err(Error::ItemVersionMismatch);
+        }
+        let before = snapshot(&item);
+        item.transition_to(status, now)
+            .map_err(|_| Error::InvalidItemStatusTransition)?;
+        let mut saved = item.clone();
+        saved.version += 1;
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::ItemStatusChanged,
+            Some(table_number),
+            Some(item_id),
+            before,
+            snapshot(&saved),
+        ))?;
+        repository.update(table_number, item, now, audit).await?;
+        Ok(saved)
+    }
+
+    /// Moves all or the selected items of a table to another one. A party
+    /// moving to a free table opens it, and a table left without its items is
+    /// marked for cleaning.
+    pub async fn move_items(
+        &self,
+        actor: &Actor,
+        from_table_number: u32,
+        to_table_number: u32,
+        item_ids: Option<Vec<u32>>,
+        now: NaiveDateTime,
+    ) -> Result<u64, Error> {
+        ensure_table_access(actor, from_table_number)?;
+        ensure_table_access(actor, to_table_number)?;
+        let transfer = ItemTransfer::move_items(from_table_number, to_table_number, item_ids)
+            .map_err(|_| Error::InvalidItemTransfer)?;
+        let tables = self.repositories.table_repository();
+        let from_table = tables.find_one(from_table_number).await?;
+        let mut to_table = tables.find_one(to_table_number).await?;
+        if !from_table.is_seated() {
+            return Err(Error::TableNotSeated);
+        }
+        let seated_table = if to_table.status == TableStatus::Free {
+            to_table.seat(now).map_err(|_| Error::TableNotAvailable)?;
+            Some(to_table)
+        } else if to_table.is_seated() {
+            None
+        } else {
+            return Err(Error::TableNotAvailable);
+        };
+
+        self.transfer(actor, transfer, from_table, seated_table, now)
             .await
     }
+
+    /// Merges the items of one seated table into another so that both
+    /// parties share one bill.
+    pub async fn merge_tables(
+        &self,
+        actor: &Actor,
+        from_table_number: u32,
+        into_table_number: u32,
+        now: NaiveDateTime,
+    ) -> Result<u64, Error> {
+        ensure_table_access(actor, from_table_number)?;
+        ensure_table_access(actor, into_table_number)?;
+        let transfer = ItemTransfer::merge(from_table_number, into_table_number)
+            .map_err(|_| Error::InvalidItemTransfer)?;
+        let tables = self.repositories.table_repository();
+        let from_table = tables.find_one(from_table_number).await?;
+        let into_table = tables.find_one(into_table_number).await?;
+        if !from_table.is_seated() || !into_table.is_seated() {
+            return Err(Error::TableNotSeated);
+        }
+
+        self.transfer(actor, transfer, from_table, None, now).await
+    }
+
+    async fn transfer(
+        &self,
+        actor: &Actor,
+        transfer: ItemTransfer,
+        mut from_table: Table,
+        seated_table: Option<Table>,
+        now: NaiveDateTime,
+    ) -> Result<u64, Error> {
+        let action = match transfer.kind {
+            TransferKind::Move => AuditAction::ItemsMoved,
+            TransferKind::Merge => AuditAction::TablesMerged,
+        };
+        let after = snapshot(&transfer);
+        let from_table_number = transfer.from_table_number;
+        // a table that gives up all its items is left to clean
+        let cleared_table = if transfer.moves_all_items() {
+            from_table
+                .transition_to(TableStatus::NeedsCleaning, now)
+                .map_err(|_| Error::InvalidTableStatusTransition)?;
+            Some(from_table)
+        } else {
+            None
+        };
+        let audit = audit(AuditRecord::new(
+            actor,
+            action,
+            Some(from_table_number),
+            None,
+            None,
+            after,
+        ))?;
+        self.repositories
+            .item_repository()
+            .transfer(transfer, seated_table, cleared_table, now, audit)
+            .await
+    }
+
+    /// Voids an item. Once it has been served only managers can void it.
+    pub async fn delete_item(
+        &self,
+        actor: &Actor,
+        table_number: u32,
+        item_id: u32,
+        expected_version: Option<u32>,
+        now: NaiveDateTime,
+    ) -> Result<(), Error> {
+        ensure_table_access(actor, table_number)?;
+        let repository = self.repositories.item_repository();
+        let item = repository.find_one_for_write(table_number, item_id).await?;
+        if !item.matches_version(expected_version) {
+            return Err(Error::ItemVersionMismatch);
+        }
+        if item.status == ItemStatus::Served {
+            ensure_allowed(actor, Permission::VoidServedItems)?;
+        }
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::ItemDeleted,
+            Some(table_number),
+            Some(item_id),
+            snapshot(&item),
+            None,
+        ))?;
+        repository.delete(table_number, item, now, audit).await
+    }
 }
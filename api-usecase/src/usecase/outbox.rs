@@ -0,0 +1,70 @@
+use api_core::{
+    domain::outbox::RetryPolicy,
+    error::Error,
+    notifier::{EventPublisher, Publication},
+    repository::{outbox_repository::OutboxRepository, Repositories},
+};
+use chrono::NaiveDateTime;
+
+#[derive(Clone)]
+pub struct OutboxUseCase<R: Repositories> {
+    repositories: R,
+    retry_policy: RetryPolicy,
+}
+
+impl<R: Repositories> OutboxUseCase<R> {
+    /// Events relayed in one go.
+    pub const BATCH_SIZE: u32 = 100;
+
+    pub fn new(repositories: R) -> Self {
+        Self::with_retry_policy(repositories, RetryPolicy::default())
+    }
+
+    pub fn with_retry_policy(repositories: R, retry_policy: RetryPolicy) -> Self {
+        Self {
+            repositories,
+            retry_policy,
+        }
+    }
+}
+
+impl<R: Repositories> OutboxUseCase<R> {
+    /// Publishes the events that are due, oldest first, and returns how many
+    /// were delivered. An event is only marked delivered once every
+    /// subscriber took it; otherwise it is tried again later, for the
+    /// subscribers that failed it only, so delivery is at least once.
+    pub async fn relay_pending(&self, now: NaiveDateTime) -> Result<usize, Error> {
+        let outbox = self.repositories.outbox_repository();
+        let mut delivered = 0;
+        for entry in outbox.find_pending(now, Self::BATCH_SIZE).await? {
+            let attempts = entry.attempts + 1;
+            let publication = match entry.event {
+                Some(event) => {
+                    self.repositories
+                        .event_publisher()
+                        .publish(entry.id, &event, &entry.handled_by)
+                        .await
+                }
+                None => Publication {
+                    handled_by: entry.handled_by,
+                    failures: vec![format!("{:?}", Error::OutboxStoreError)],
+                },
+            };
+            if publication.is_complete() {
+                outbox.mark_delivered(entry.id, now).await?;
+                delivered += 1;
+            } else {
+                let next_attempt_at = self.retry_policy.next_attempt_at(attempts, now);
+                outbox
+                    .mark_failed(
+                        entry.id,
+                        publication.failures.join("; "),
+                        &publication.handled_by,
+                        next_attempt_at,
+                    )
+                    .await?;
+            }
+        }
+        Ok(delivered)
+    }
+}
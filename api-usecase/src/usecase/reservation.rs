@@ -0,0 +1,144 @@
+use api_core::{
+    domain::{
+        actor::Actor,
+        audit::{AuditAction, AuditRecord},
+        reservation::Reservation,
+    },
+    error::Error,
+    repository::{
+        reservation_repository::ReservationRepository, table_repository::TableRepository,
+        Repositories,
+    },
+};
+use chrono::NaiveDateTime;
+
+use crate::usecase::{audit, snapshot};
+
+#[derive(Clone)]
+pub struct ReservationUseCase<R: Repositories> {
+    repositories: R,
+}
+
+impl<R: Repositories> ReservationUseCase<R> {
+    pub fn new(repositories: R) -> Self {
+        Self { repositories }
+    }
+}
+
+impl<R: Repositories> ReservationUseCase<R> {
+    pub async fn query_reservation(&self, reservation_id: u32) -> Result<Reservation, Error> {
+        self.repositories
+            .reservation_repository()
+            .find_one(reservation_id)
+            .await
+    }
+
+    /// Books a table. Fails when the party doesn't fit it or another booking
+    /// overlaps.
+    pub async fn make_reservation(
+        &self,
+        actor: &Actor,
+        reservation: Reservation,
+    ) -> Result<u64, Error> {
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::ReservationMade,
+            Some(reservation.table_number),
+            None,
+            None,
+            None,
+        ))?;
+        self.repositories
+            .reservation_repository()
+            .create(reservation, audit)
+            .await
+    }
+
+    pub async fn modify_reservation(
+        &self,
+        actor: &Actor,
+        reservation_id: u32,
+        changes: Reservation,
+    ) -> Result<Reservation, Error> {
+        let current = self.query_reservation(reservation_id).await?;
+        if current.status != changes.status {
+            return Err(Error::InvalidReservationStatusTransition);
+        }
+        let reservation = Reservation {
+            id: current.id,
+            ..changes
+        };
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::ReservationModified,
+            Some(reservation.table_number),
+            None,
+            snapshot(&current),
+            snapshot(&reservation),
+        ))?;
+        self.repositories
+            .reservation_repository()
+            .update(reservation.clone(), audit)
+            .await?;
+        Ok(reservation)
+    }
+
+    pub async fn cancel_reservation(
+        &self,
+        actor: &Actor,
+        reservation_id: u32,
+    ) -> Result<Reservation, Error> {
+        let mut reservation = self.query_reservation(reservation_id).await?;
+        let before = snapshot(&reservation);
+        reservation
+            .cancel()
+            .map_err(|_| Error::InvalidReservationStatusTransition)?;
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::ReservationCancelled,
+            Some(reservation.table_number),
+            None,
+            before,
+            snapshot(&reservation),
+        ))?;
+        self.repositories
+            .reservation_repository()
+            .update(reservation.clone(), audit)
+            .await?;
+        Ok(reservation)
+    }
+
+    /// Seats the party of a booked reservation, which opens its table.
+    pub async fn seat_reservation(
+        &self,
+        actor: &Actor,
+        reservation_id: u32,
+        now: NaiveDateTime,
+    ) -> Result<Reservation, Error> {
+        let mut reservation = self.query_reservation(reservation_id).await?;
+        let before = snapshot(&reservation);
+        reservation
+            .seat()
+            .map_err(|_| Error::InvalidReservationStatusTransition)?;
+        let mut table = self
+            .repositories
+            .table_repository()
+            .find_one(reservation.table_number)
+            .await?;
+        table.seat(now).map_err(|_| Error::TableNotAvailable)?;
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::ReservationSeated,
+            Some(reservation.table_number),
+            None,
+            before,
+            snapshot(&reservation),
+        ))?;
+
+        self.repositories
+            .reservation_repository()
+            .seat(reservation.clone(), table, now, audit)
+            .await?;
+        Ok(reservation)
+    }
+}
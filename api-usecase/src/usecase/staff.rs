@@ -0,0 +1,143 @@
+use api_core::{
+    domain::{
+        actor::Actor,
+        audit::{AuditAction, AuditRecord},
+        staff::{Permission, Role, Staff},
+    },
+    error::Error,
+    repository::{staff_repository::StaffRepository, Repositories},
+};
+
+use crate::usecase::{audit, ensure_allowed, snapshot};
+
+/// The shortest password a staff member is given.
+pub const MIN_PASSWORD_LEN: usize = 12;
+
+/// A hash no password is checked against successfully, verified for unknown
+/// names so that a login takes as long whether the name is in use or not.
+const UNKNOWN_STAFF_PASSWORD_HASH: &str =
+    "$2b$12$I6dQpsS6SF8FUVpopJla5eSK7pMKX3Jk8o5.jlexve06gfQVWz4d.";
+
+fn hash_password(password: &str) -> Result<String, Error> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(Error::PasswordTooShort);
+    }
+    bcrypt::hash(password, bcrypt::DEFAULT_COST).map_err(|_| Error::StaffInsertionError)
+}
+
+#[derive(Clone)]
+pub struct StaffUseCase<R: Repositories> {
+    repositories: R,
+}
+
+impl<R: Repositories> StaffUseCase<R> {
+    pub fn new(repositories: R) -> Self {
+        Self { repositories }
+    }
+}
+
+impl<R: Repositories> StaffUseCase<R> {
+    pub async fn query_staff(&self, staff_id: u32) -> Result<Staff, Error> {
+        self.repositories
+            .staff_repository()
+            .find_one(staff_id)
+            .await
+    }
+
+    /// Checks a login. Unknown names, inactive staff and wrong passwords are
+    /// all reported the same way.
+    pub async fn authenticate(&self, name: &str, password: &str) -> Result<Staff, Error> {
+        let staff = match self
+            .repositories
+            .staff_repository()
+            .find_by_name(name)
+            .await
+        {
+            Ok(staff) => staff,
+            Err(_) => {
+                let _ = bcrypt::verify(password, UNKNOWN_STAFF_PASSWORD_HASH);
+                return Err(Error::InvalidCredentials);
+            }
+        };
+        let verified = match &staff.password_hash {
+            Some(hash) => bcrypt::verify(password, hash).unwrap_or(false),
+            None => {
+                let _ = bcrypt::verify(password, UNKNOWN_STAFF_PASSWORD_HASH);
+                false
+            }
+        };
+        if staff.active && verified {
+            Ok(staff)
+        } else {
+            Err(Error::InvalidCredentials)
+        }
+    }
+
+    pub async fn query_staff_members(&self, actor: &Actor) -> Result<Vec<Staff>, Error> {
+        ensure_allowed(actor, Permission::ManageStaff)?;
+        self.repositories.staff_repository().find_all().await
+    }
+
+    pub async fn add_staff(
+        &self,
+        actor: &Actor,
+        mut staff: Staff,
+        password: &str,
+    ) -> Result<u64, Error> {
+        ensure_allowed(actor, Permission::ManageStaff)?;
+        staff.password_hash = Some(hash_password(password)?);
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::StaffAdded,
+            None,
+            None,
+            None,
+            None,
+        ))?;
+        self.repositories
+            .staff_repository()
+            .create(staff, audit)
+            .await
+    }
+
+    /// Adds the first admin, for a database no active admin can log in to yet:
+    /// the migrations create no accounts. From then on staff are added by an
+    /// admin through `add_staff`, so this is refused.
+    pub async fn bootstrap_admin(&self, name: String, password: &str) -> Result<Staff, Error> {
+        let hash = hash_password(password)?;
+        let repository = self.repositories.staff_repository();
+        let has_admin = repository
+            .find_all()
+            .await?
+            .iter()
+            .any(|staff| staff.active && staff.role == Role::Admin);
+        if has_admin {
+            return Err(Error::Forbidden);
+        }
+        let mut staff = Staff::new(name, Role::Admin).map_err(|_| Error::StaffInsertionError)?;
+        staff.password_hash = Some(hash);
+        // nobody was there to add them but whoever runs the server
+        let audit = AuditRecord::by_system(AuditAction::StaffAdded, None, None, None, None);
+        let id = repository.create(staff.clone(), audit).await?;
+        staff.id = u32::try_from(id).ok();
+        Ok(staff)
+    }
+
+    pub async fn deactivate_staff(&self, actor: &Actor, staff_id: u32) -> Result<Staff, Error> {
+        ensure_allowed(actor, Permission::ManageStaff)?;
+        let repository = self.repositories.staff_repository();
+        let mut staff = repository.find_one(staff_id).await?;
+        let before = snapshot(&staff);
+        staff.active = false;
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::StaffDeactivated,
+            None,
+            None,
+            before,
+            snapshot(&staff),
+        ))?;
+        repository.update(staff.clone(), audit).await?;
+        Ok(staff)
+    }
+}
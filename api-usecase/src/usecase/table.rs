@@ -0,0 +1,93 @@
+use api_core::{
+    domain::{
+        actor::Actor,
+        audit::{AuditAction, AuditRecord},
+        table::{pick_table_for, Table, TableStatus},
+    },
+    error::Error,
+    repository::{table_repository::TableRepository, Repositories},
+};
+use chrono::NaiveDateTime;
+
+use crate::usecase::{audit, snapshot};
+
+#[derive(Clone)]
+pub struct TableUseCase<R: Repositories> {
+    repositories: R,
+}
+
+impl<R: Repositories> TableUseCase<R> {
+    pub fn new(repositories: R) -> Self {
+        Self { repositories }
+    }
+}
+
+impl<R: Repositories> TableUseCase<R> {
+    pub async fn query_tables(&self) -> Result<Vec<Table>, Error> {
+        self.repositories.table_repository().find_all().await
+    }
+
+    /// Seats the party at the best free table. A table another host seats in
+    /// the meantime is passed over for the next best one.
+    pub async fn seat_party(
+        &self,
+        actor: &Actor,
+        party_size: u32,
+        zone: Option<String>,
+        now: NaiveDateTime,
+    ) -> Result<Table, Error> {
+        let mut tables = self.query_tables().await?;
+        loop {
+            let mut table = pick_table_for(&tables, party_size, zone.as_deref())
+                .cloned()
+                .ok_or(Error::TableNotAvailable)?;
+            let before = snapshot(&table);
+            table.seat(now).map_err(|_| Error::TableNotAvailable)?;
+            let audit = audit(AuditRecord::new(
+                actor,
+                AuditAction::TableSeated,
+                Some(table.number),
+                None,
+                before,
+                snapshot(&table),
+            ))?;
+            match self
+                .repositories
+                .table_repository()
+                .seat(table.clone(), now, audit)
+                .await
+            {
+                Err(Error::TableNotAvailable) => {
+                    tables.retain(|other| other.number != table.number)
+                }
+                result => return result.map(|_| table),
+            }
+        }
+    }
+
+    pub async fn update_table_status(
+        &self,
+        actor: &Actor,
+        table_number: u32,
+        status: TableStatus,
+        now: NaiveDateTime,
+    ) -> Result<Table, Error> {
+        let repository = self.repositories.table_repository();
+        let mut table = repository.find_one(table_number).await?;
+        let before = snapshot(&table);
+        let previous = table.status;
+        table
+            .transition_to(status, now)
+            .map_err(|_| Error::InvalidTableStatusTransition)?;
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::TableStatusChanged,
+            Some(table_number),
+            None,
+            before,
+            snapshot(&table),
+        ))?;
+        repository.update(table.clone(), previous, now, audit).await?;
+        Ok(table)
+    }
+}
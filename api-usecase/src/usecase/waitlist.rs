@@ -0,0 +1,155 @@
+use api_core::{
+    domain::{
+        actor::Actor,
+        audit::{AuditAction, AuditRecord},
+        reservation::Reservation,
+        table::Table,
+        waitlist::{TurnoverPolicy, WaitlistEntry},
+    },
+    error::Error,
+    repository::{
+        reservation_repository::ReservationRepository, table_repository::TableRepository,
+        waitlist_repository::WaitlistRepository, Repositories,
+    },
+};
+use chrono::{Duration, NaiveDateTime};
+
+use crate::usecase::{audit, snapshot};
+
+// how far ahead reservations are taken into account when quoting and seating
+const RESERVATION_HORIZON_HOURS: i64 = 6;
+
+#[derive(Clone)]
+pub struct WaitlistUseCase<R: Repositories> {
+    repositories: R,
+    policy: TurnoverPolicy,
+}
+
+impl<R: Repositories> WaitlistUseCase<R> {
+    pub fn new(repositories: R) -> Self {
+        Self {
+            repositories,
+            policy: TurnoverPolicy::default(),
+        }
+    }
+}
+
+impl<R: Repositories> WaitlistUseCase<R> {
+    pub async fn query_waitlist(&self) -> Result<Vec<WaitlistEntry>, Error> {
+        self.repositories.waitlist_repository().find_waiting().await
+    }
+
+    /// Puts a walk-in party at the end of the queue with a quoted wait time.
+    /// The guest is told about it from the outbox.
+    pub async fn join_waitlist(
+        &self,
+        actor: &Actor,
+        mut entry: WaitlistEntry,
+    ) -> Result<WaitlistEntry, Error> {
+        let now = entry.joined_at;
+        let tables = self.repositories.table_repository().find_all().await?;
+        let reservations = self.upcoming_reservations(now).await?;
+        let waiting_ahead = self.query_waitlist().await?;
+        let quote = self
+            .policy
+            .quote_wait(
+                entry.party_size,
+                &tables,
+                &reservations,
+                &waiting_ahead,
+                now,
+            )
+            .ok_or(Error::TableCapacityExceeded)?;
+        entry.quoted_wait_minutes = quote.num_minutes();
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::WaitlistJoined,
+            None,
+            None,
+            None,
+            None,
+        ))?;
+
+        let entry_id = self
+            .repositories
+            .waitlist_repository()
+            .create(entry.clone(), audit)
+            .await?;
+        entry.id = u32::try_from(entry_id).ok();
+        Ok(entry)
+    }
+
+    pub async fn leave_waitlist(
+        &self,
+        actor: &Actor,
+        entry_id: u32,
+    ) -> Result<WaitlistEntry, Error> {
+        let repository = self.repositories.waitlist_repository();
+        let mut entry = repository.find_one(entry_id).await?;
+        let before = snapshot(&entry);
+        entry
+            .leave()
+            .map_err(|_| Error::InvalidWaitlistStatusTransition)?;
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::WaitlistLeft,
+            None,
+            None,
+            before,
+            snapshot(&entry),
+        ))?;
+        repository.update(entry.clone(), audit).await?;
+        Ok(entry)
+    }
+
+    /// Seats the longest waiting party that fits a free table, skipping
+    /// parties no free table can take yet. They are called in from the
+    /// outbox.
+    pub async fn seat_next(
+        &self,
+        actor: &Actor,
+        now: NaiveDateTime,
+    ) -> Result<(WaitlistEntry, Table), Error> {
+        let waiting = self.query_waitlist().await?;
+        if waiting.is_empty() {
+            return Err(Error::WaitlistEntryNotFoundError);
+        }
+        let tables = self.repositories.table_repository().find_all().await?;
+        let reservations = self.upcoming_reservations(now).await?;
+
+        let (mut entry, mut table) = waiting
+            .into_iter()
+            .find_map(|entry| {
+                self.policy
+                    .pick_table(entry.party_size, &tables, &reservations, now)
+                    .map(|table| (entry, table))
+            })
+            .ok_or(Error::TableNotAvailable)?;
+        table.seat(now).map_err(|_| Error::TableNotAvailable)?;
+        let before = snapshot(&entry);
+        entry
+            .seat()
+            .map_err(|_| Error::InvalidWaitlistStatusTransition)?;
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::WaitlistSeated,
+            Some(table.number),
+            None,
+            before,
+            snapshot(&entry),
+        ))?;
+
+        self.repositories
+            .waitlist_repository()
+            .seat(entry.clone(), table.clone(), now, audit)
+            .await?;
+        Ok((entry, table))
+    }
+
+    async fn upcoming_reservations(&self, now: NaiveDateTime) -> Result<Vec<Reservation>, Error> {
+        self.repositories
+            .reservation_repository()
+            .find_booked_between(now, now + Duration::hours(RESERVATION_HORIZON_HOURS))
+            .await
+    }
+}
@@ -0,0 +1,241 @@
+use api_core::{
+    domain::{
+        actor::Actor,
+        audit::{AuditAction, AuditRecord},
+        staff::Permission,
+        webhook::{WebhookDelivery, WebhookPolicy, WebhookSubscription},
+    },
+    error::Error,
+    notifier::WebhookSender,
+    repository::{webhook_repository::WebhookRepository, Repositories},
+};
+use chrono::NaiveDateTime;
+use futures_util::{stream, StreamExt};
+use std::time::{Duration, Instant};
+
+use crate::usecase::{audit, ensure_allowed, random_key, snapshot};
+
+#[derive(Clone)]
+pub struct WebhookUseCase<R: Repositories> {
+    repositories: R,
+    policy: WebhookPolicy,
+}
+
+impl<R: Repositories> WebhookUseCase<R> {
+    /// Deliveries made in one go.
+    pub const BATCH_SIZE: u32 = 50;
+    /// Subscriptions delivered to at the same time.
+    pub const CONCURRENCY: usize = 8;
+    /// How long one subscription is delivered to in one go. A slow receiver
+    /// only holds up its own deliveries, and those left over wait for the
+    /// next go.
+    pub const TIME_PER_SUBSCRIPTION: Duration = Duration::from_secs(30);
+    /// Entries of a delivery log shown at once.
+    pub const LOG_SIZE: u32 = 100;
+
+    pub fn new(repositories: R) -> Self {
+        Self::with_policy(repositories, WebhookPolicy::default())
+    }
+
+    pub fn with_policy(repositories: R, policy: WebhookPolicy) -> Self {
+        Self {
+            repositories,
+            policy,
+        }
+    }
+}
+
+impl<R: Repositories> WebhookUseCase<R> {
+    pub async fn query_webhooks(&self, actor: &Actor) -> Result<Vec<WebhookSubscription>, Error> {
+        ensure_allowed(actor, Permission::ManageWebhooks)?;
+        self.repositories.webhook_repository().find_all().await
+    }
+
+    /// Subscribes `url` to the named events, or to all of them when none are
+    /// named. Returns the subscription along with its signing secret, which
+    /// is only shown this once.
+    pub async fn create_webhook(
+        &self,
+        actor: &Actor,
+        url: String,
+        event_types: Vec<String>,
+    ) -> Result<(WebhookSubscription, String), Error> {
+        ensure_allowed(actor, Permission::ManageWebhooks)?;
+        let secret = random_key();
+        let mut subscription = WebhookSubscription::new(url, secret.clone(), event_types)
+            .map_err(|_| Error::InvalidWebhookSubscription)?;
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::WebhookCreated,
+            None,
+            None,
+            None,
+            None,
+        ))?;
+        let id = self
+            .repositories
+            .webhook_repository()
+            .create(subscription.clone(), audit)
+            .await?;
+        subscription.id = u32::try_from(id).ok();
+        Ok((subscription, secret))
+    }
+
+    /// Turns a subscription off, or back on once its receiver works again.
+    pub async fn set_webhook_active(
+        &self,
+        actor: &Actor,
+        subscription_id: u32,
+        active: bool,
+    ) -> Result<WebhookSubscription, Error> {
+        ensure_allowed(actor, Permission::ManageWebhooks)?;
+        let repository = self.repositories.webhook_repository();
+        let mut subscription = repository.find_one(subscription_id).await?;
+        let before = snapshot(&subscription);
+        let action = if active {
+            subscription.enable();
+            AuditAction::WebhookEnabled
+        } else {
+            subscription.disable();
+            AuditAction::WebhookDisabled
+        };
+        let audit = audit(AuditRecord::new(
+            actor,
+            action,
+            None,
+            None,
+            before,
+            snapshot(&subscription),
+        ))?;
+        repository.update(subscription.clone(), audit).await?;
+        Ok(subscription)
+    }
+
+    pub async fn delete_webhook(&self, actor: &Actor, subscription_id: u32) -> Result<(), Error> {
+        ensure_allowed(actor, Permission::ManageWebhooks)?;
+        let repository = self.repositories.webhook_repository();
+        let subscription = repository.find_one(subscription_id).await?;
+        let audit = audit(AuditRecord::new(
+            actor,
+            AuditAction::WebhookDeleted,
+            None,
+            None,
+            snapshot(&subscription),
+            None,
+        ))?;
+        repository.delete(subscription_id, audit).await
+    }
+
+    /// The latest deliveries of a subscription, newest first.
+    pub async fn query_deliveries(
+        &self,
+        actor: &Actor,
+        subscription_id: u32,
+    ) -> Result<Vec<WebhookDelivery>, Error> {
+        ensure_allowed(actor, Permission::ManageWebhooks)?;
+        let repository = self.repositories.webhook_repository();
+        repository.find_one(subscription_id).await?;
+        repository
+            .find_deliveries(subscription_id, Self::LOG_SIZE)
+            .await
+    }
+
+    /// Makes the deliveries that are due and returns how many went through.
+    /// Failed ones are tried again with growing delays until the policy gives
+    /// up on them; a subscription failing too often in a row is disabled.
+    /// Subscriptions are delivered to side by side, each in order.
+    pub async fn deliver_pending(&self, now: NaiveDateTime) -> Result<usize, Error> {
+        let mut queues: Vec<(u32, Vec<WebhookDelivery>)> = Vec::new();
+        for delivery in self
+            .repositories
+            .webhook_repository()
+            .find_due_deliveries(now, Self::BATCH_SIZE)
+            .await?
+        {
+            match queues
+                .iter_mut()
+                .find(|(subscription_id, _)| *subscription_id == delivery.subscription_id)
+            {
+                Some((_, queue)) => queue.push(delivery),
+                None => queues.push((delivery.subscription_id, vec![delivery])),
+            }
+        }
+
+        let results = stream::iter(queues)
+            .map(|(subscription_id, queue)| self.deliver_to(subscription_id, queue, now))
+            .buffer_unordered(Self::CONCURRENCY)
+            .collect::<Vec<Result<usize, Error>>>()
+            .await;
+        let mut delivered = 0;
+        for result in results {
+            delivered += result?;
+        }
+        Ok(delivered)
+    }
+
+    /// Makes the deliveries queued for one subscription, until they are done
+    /// or `TIME_PER_SUBSCRIPTION` is up.
+    async fn deliver_to(
+        &self,
+        subscription_id: u32,
+        queue: Vec<WebhookDelivery>,
+        now: NaiveDateTime,
+    ) -> Result<usize, Error> {
+        let repository = self.repositories.webhook_repository();
+        let started = Instant::now();
+        let mut subscription = match repository.find_one(subscription_id).await {
+            Ok(subscription) => Some(subscription),
+            Err(Error::WebhookNotFoundError) => None,
+            Err(e) => return Err(e),
+        };
+        let mut delivered = 0;
+        for mut delivery in queue {
+            let subscription = match subscription.as_mut() {
+                Some(subscription) if subscription.active => subscription,
+                Some(_) => {
+                    delivery.abandon(String::from("the subscription is disabled"));
+                    repository.update_delivery(delivery).await?;
+                    continue;
+                }
+                None => {
+                    delivery.abandon(String::from("the subscription is gone"));
+                    repository.update_delivery(delivery).await?;
+                    continue;
+                }
+            };
+            if started.elapsed() >= Self::TIME_PER_SUBSCRIPTION {
+                break;
+            }
+
+            let outcome = self
+                .repositories
+                .webhook_sender()
+                .send(subscription, &delivery)
+                .await;
+            if delivery.record_attempt(outcome, &self.policy, now) {
+                delivered += 1;
+                if subscription.consecutive_failures > 0 {
+                    subscription.record_success();
+                    repository.update_failures(subscription.clone()).await?;
+                }
+            } else {
+                let before = snapshot(&*subscription);
+                subscription.record_failure(&self.policy);
+                if subscription.active {
+                    repository.update_failures(subscription.clone()).await?;
+                } else {
+                    let audit = AuditRecord::by_system(
+                        AuditAction::WebhookDisabled,
+                        None,
+                        None,
+                        before,
+                        snapshot(&*subscription),
+                    );
+                    repository.update(subscription.clone(), audit).await?;
+                }
+            }
+            repository.update_delivery(delivery).await?;
+        }
+        Ok(delivered)
+    }
+}
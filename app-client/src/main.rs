@@ -1,6 +1,10 @@
+use std::env;
+
+use api_driver::controllers::auth::{JsonLoginRequest, JsonLoginResponse};
 use api_driver::controllers::item::{
     JsonItemAddingRequest, JsonItemAddlingResponse, JsonItemResponse,
 };
+use api_driver::controllers::table::{JsonSeatingRequest, JsonTableResponse};
 
 
 
@@ -8,6 +12,36 @@ use api_driver::controllers::item::{
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let client = reqwest::Client::new();
 
+    // there are no default accounts, log in as the admin added with `create-admin`
+    let login_response = client
+        .post(login_endpoint())
+        .header("Content-Type", "application/json")
+        .json(&JsonLoginRequest {
+            name: env::var("STAFF_NAME").unwrap_or_else(|_| "admin".to_string()),
+            password: env::var("STAFF_PASSWORD")
+                .map_err(|_| "set STAFF_PASSWORD to the password of STAFF_NAME")?,
+        })
+        .send()
+        .await?;
+    let login: JsonLoginResponse =
+        serde_json::from_str(&login_response.text().await.unwrap()).unwrap();
+    let token = login.access_token;
+
+    let seat_party_response = client
+        .post(seat_party_endpoint())
+        .header("Content-Type", "application/json")
+        .bearer_auth(&token)
+        .json(&JsonSeatingRequest {
+            party_size: 2,
+            zone: None,
+        })
+        .send()
+        .await?;
+    let table: JsonTableResponse =
+        serde_json::from_str(&seat_party_response.text().await.unwrap()).unwrap();
+    println!("{:#?}", table);
+    let table_number = table.number.to_string();
+
     let post_item_requests = vec![
         JsonItemAddingRequest {
             name: "Sukiyaki".to_string(),
@@ -22,8 +56,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut item_ids: Vec<u64> = Vec::new();
     for request in post_item_requests {
         let response = client
-            .post(add_item_endpoint("1"))
+            .post(add_item_endpoint(&table_number))
             .header("Content-Type", "application/json")
+            .bearer_auth(&token)
             .json(&request)
             .send()
             .await?;
@@ -34,13 +69,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("{:#?}", response_json);
     }
 
-    let query_items_response = client.get(query_items_endpoint("1")).send().await?;
+    let query_items_response = client
+        .get(query_items_endpoint(&table_number))
+        .bearer_auth(&token)
+        .send()
+        .await?;
     let query_items_response_json: Vec<JsonItemResponse> =
         serde_json::from_str(&query_items_response.text().await.unwrap()).unwrap();
     println!("{:#?}", query_items_response_json);
 
     let query_item_response = client
-        .get(query_item_endpoint("1", &item_ids[0].to_string()))
+        .get(query_item_endpoint(&table_number, &item_ids[0].to_string()))
+        .bearer_auth(&token)
         .send()
         .await?;
     let query_item_response_json: JsonItemResponse =
@@ -48,7 +88,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("{:#?}", query_item_response_json);
 
     let delete_item_response = client
-        .delete(delete_item_endpoint("1", &item_ids[0].to_string()))
+        .delete(delete_item_endpoint(&table_number, &item_ids[0].to_string()))
+        .bearer_auth(&token)
         .send()
         .await?;
     println!("{:#?}", delete_item_response);
@@ -60,6 +101,14 @@ fn api_url() -> &'static str {
     "http://localhost:3000"
 }
 
+fn login_endpoint() -> String {
+    format!("{}/auth/login", api_url())
+}
+
+fn seat_party_endpoint() -> String {
+    format!("{}/tables/seat", api_url())
+}
+
 fn add_item_endpoint(table_number_str: &str) -> String {
     format!("{}/tables/{}/item", api_url(), table_number_str)
 }
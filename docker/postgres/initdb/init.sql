@@ -0,0 +1,3 @@
+-- restaurant is created from POSTGRES_DB. The schema of both is owned by the
+-- migrations in api-adapter/migrations/postgres, which the application applies.
+CREATE DATABASE restaurant_test;